@@ -8,12 +8,17 @@
 use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
 use std::collections::BTreeMap;
+use thiserror::Error;
 use util::{algebra::FieldElement, csprng::Csprng};
 
 use crate::{
+    ballot_chain::{BallotChain, BallotChainError},
     ballot_style::BallotStyle,
     confirmation_code::confirmation_code,
-    contest_encrypted::{ContestEncrypted, ScaledContestEncrypted},
+    contest_data::ContestDataError,
+    contest_encrypted::{
+        ContestEncrypted, ContestEncryptedValidationError, ScaledContestEncrypted,
+    },
     contest_selection::ContestSelection,
     device::Device,
     election_manifest::{ContestIndex, ElectionManifest},
@@ -32,6 +37,42 @@ pub enum BallotState {
     Challenged,
 }
 
+/// Represents errors occurring while casting or challenging a [`BallotEncrypted`].
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum BallotStateError {
+    /// Occurs if the ballot was already cast or challenged.
+    #[error("The ballot {code} was already decided: {state:?}")]
+    AlreadyDecided { code: HValue, state: BallotState },
+}
+
+/// Represents errors occurring while encrypting a [`BallotEncrypted`].
+#[derive(Error, Debug, PartialEq)]
+pub enum BallotEncryptionError {
+    /// Occurs if the contest data of a contest cannot be encrypted.
+    #[error("Could not encrypt the contest data of contest {0}: {1}")]
+    ContestData(ContestIndex, ContestDataError),
+    /// Occurs if the ballot cannot be appended to the ballot chain.
+    #[error(transparent)]
+    BallotChain(#[from] BallotChainError),
+}
+
+/// Represents errors occurring while validating a [`BallotEncrypted`].
+#[derive(Error, Debug, PartialEq)]
+pub enum BallotValidationError {
+    /// Occurs if the ballot style does not exist in the manifest.
+    #[error("The ballot style {0} does not exist in the manifest.")]
+    UnknownBallotStyle(Index<BallotStyle>),
+    /// Occurs if a contest of the ballot style does not exist in the manifest.
+    #[error("The contest {0} does not exist in the manifest.")]
+    UnknownContest(ContestIndex),
+    /// Occurs if the ballot does not contain a contest of its ballot style.
+    #[error("The ballot does not contain contest {0}.")]
+    MissingContest(ContestIndex),
+    /// Occurs if a contest of the ballot is invalid.
+    #[error("Contest {0} is invalid: {1}")]
+    InvalidContest(ContestIndex, ContestEncryptedValidationError),
+}
+
 /// An encrypted ballot.
 #[derive(Debug, Serialize, Deserialize)]
 pub struct BallotEncrypted {
@@ -41,14 +82,24 @@ pub struct BallotEncrypted {
     /// Confirmation code
     pub confirmation_code: HValue,
 
-    /// State of the ballot
-    pub state: BallotState,
+    /// State of the ballot, see [`BallotEncrypted::cast`] and [`BallotEncrypted::challenge`]
+    state: BallotState,
 
     /// Date (and time) of ballot generation
     pub date: String,
 
     /// Device that generated this ballot
     pub device: String,
+
+    /// The auxiliary input `B_aux` of the confirmation code, i.e., the previous confirmation
+    /// code of the device's [`BallotChain`], or zero if the ballot is not chained
+    #[serde(default)]
+    pub b_aux: HValue,
+
+    /// The ballot ID assigned by the [`EncryptionDevice`](crate::device::EncryptionDevice),
+    /// if any
+    #[serde(default)]
+    pub ballot_id: Option<u64>,
     // TODO: Have an optional field to store election record data for pre-encrypted ballots
 }
 
@@ -60,19 +111,22 @@ pub struct ScaledBallotEncrypted {
 }
 
 impl BallotEncrypted {
+    /// Creates an uncast ballot from its encrypted contests. Whether the ballot is cast or
+    /// challenged is decided with [`BallotEncrypted::cast`] or [`BallotEncrypted::challenge`].
     pub fn new(
         contests: &BTreeMap<ContestIndex, ContestEncrypted>,
-        state: BallotState,
         confirmation_code: HValue,
         date: &str,
         device: &str,
     ) -> BallotEncrypted {
         BallotEncrypted {
             contests: contests.clone(),
-            state,
+            state: BallotState::Uncast,
             confirmation_code,
             date: date.to_string(),
             device: device.to_string(),
+            b_aux: HValue::default(),
+            ballot_id: None,
         }
     }
 
@@ -81,7 +135,46 @@ impl BallotEncrypted {
         csprng: &mut Csprng,
         primary_nonce: &[u8],
         ctest_selections: &BTreeMap<ContestIndex, ContestSelection>,
-    ) -> BallotEncrypted {
+    ) -> Result<BallotEncrypted, BallotEncryptionError> {
+        Self::new_from_selections_with_b_aux(
+            device,
+            csprng,
+            primary_nonce,
+            ctest_selections,
+            HValue::default(),
+        )
+    }
+
+    /// Encrypts a ballot as the next ballot of the device's [`BallotChain`] and appends it to
+    /// the chain.
+    pub fn new_from_selections_chained(
+        device: &Device,
+        csprng: &mut Csprng,
+        primary_nonce: &[u8],
+        ctest_selections: &BTreeMap<ContestIndex, ContestSelection>,
+        chain: &mut BallotChain,
+    ) -> Result<BallotEncrypted, BallotEncryptionError> {
+        if chain.closed {
+            return Err(BallotChainError::Closed(chain.device.clone()).into());
+        }
+        let ballot = Self::new_from_selections_with_b_aux(
+            device,
+            csprng,
+            primary_nonce,
+            ctest_selections,
+            chain.b_aux(),
+        )?;
+        chain.append(&ballot)?;
+        Ok(ballot)
+    }
+
+    fn new_from_selections_with_b_aux(
+        device: &Device,
+        csprng: &mut Csprng,
+        primary_nonce: &[u8],
+        ctest_selections: &BTreeMap<ContestIndex, ContestSelection>,
+        b_aux: HValue,
+    ) -> Result<BallotEncrypted, BallotEncryptionError> {
         let mut contests = BTreeMap::new();
 
         for (&c_idx, selection) in ctest_selections {
@@ -93,7 +186,8 @@ impl BallotEncrypted {
                 device.header.manifest.contests.get(c_idx).unwrap(),
                 c_idx,
                 selection,
-            );
+            )
+            .map_err(|e| BallotEncryptionError::ContestData(c_idx, e))?;
 
             contests.insert(c_idx, contest_encrypted);
         }
@@ -107,16 +201,21 @@ impl BallotEncrypted {
         //         selection,
         //     ));
         // }
-        let confirmation_code =
-            confirmation_code(&device.header.hashes_ext.h_e, contests.values(), &[0u8; 32]);
+        let confirmation_code = confirmation_code(
+            &device.header.hashes_ext.h_e,
+            contests.values(),
+            b_aux.as_ref(),
+        );
 
-        BallotEncrypted {
+        Ok(BallotEncrypted {
             contests,
             state: BallotState::Uncast,
             confirmation_code,
             date: device.header.parameters.varying_parameters.date.clone(),
             device: device.uuid.clone(),
-        }
+            b_aux,
+            ballot_id: None,
+        })
     }
 
     pub fn contests(&self) -> &BTreeMap<ContestIndex, ContestEncrypted> {
@@ -131,6 +230,31 @@ impl BallotEncrypted {
         &self.date
     }
 
+    pub fn state(&self) -> &BallotState {
+        &self.state
+    }
+
+    /// Casts the ballot. Only an uncast ballot can be cast.
+    pub fn cast(&mut self) -> Result<(), BallotStateError> {
+        self.decide(BallotState::Cast)
+    }
+
+    /// Challenges the ballot. Only an uncast ballot can be challenged.
+    pub fn challenge(&mut self) -> Result<(), BallotStateError> {
+        self.decide(BallotState::Challenged)
+    }
+
+    fn decide(&mut self, state: BallotState) -> Result<(), BallotStateError> {
+        if self.state != BallotState::Uncast {
+            return Err(BallotStateError::AlreadyDecided {
+                code: self.confirmation_code,
+                state: self.state.clone(),
+            });
+        }
+        self.state = state;
+        Ok(())
+    }
+
     pub fn device(&self) -> &String {
         &self.device
     }
@@ -139,22 +263,39 @@ impl BallotEncrypted {
     /// a ballot style it checks that all contests are voted on in the
     /// ballot style, and that all of the vote proofs are correct.
     pub fn verify(&self, header: &PreVotingData, ballot_style_index: Index<BallotStyle>) -> bool {
-        let Some(ballot_style) = header.manifest.ballot_styles.get(ballot_style_index) else {
-            return false;
-        };
-        for contest_index in &ballot_style.contests {
-            let Some(contest) = header.manifest.contests.get(*contest_index) else {
-                return false;
-            };
-            let Some(contest_encrypted) = self.contests().get(contest_index) else {
-                return false;
-            };
+        self.validate(header, ballot_style_index).is_ok()
+    }
 
-            if !contest_encrypted.verify(header, contest.selection_limit) {
-                return false;
-            }
+    /// Validates the proofs of all contests of the given ballot style. In contrast to
+    /// [`BallotEncrypted::verify`] it reports which contest failed and why.
+    pub fn validate(
+        &self,
+        header: &PreVotingData,
+        ballot_style_index: Index<BallotStyle>,
+    ) -> Result<(), BallotValidationError> {
+        let ballot_style = header
+            .manifest
+            .ballot_styles
+            .get(ballot_style_index)
+            .ok_or(BallotValidationError::UnknownBallotStyle(
+                ballot_style_index,
+            ))?;
+        for &contest_index in &ballot_style.contests {
+            let contest = header
+                .manifest
+                .contests
+                .get(contest_index)
+                .ok_or(BallotValidationError::UnknownContest(contest_index))?;
+            let contest_encrypted = self
+                .contests()
+                .get(&contest_index)
+                .ok_or(BallotValidationError::MissingContest(contest_index))?;
+
+            contest_encrypted
+                .validate(header, contest)
+                .map_err(|e| BallotValidationError::InvalidContest(contest_index, e))?;
         }
-        true
+        Ok(())
     }
 
     /// Writes a `BallotEncrypted` to a `std::io::Write`.
@@ -169,6 +310,11 @@ impl BallotEncrypted {
             .context("Error writing serialized voter selection to file")
     }
 
+    /// Reads a `BallotEncrypted` from a `std::io::Read`.
+    pub fn from_stdioread(stdioread: &mut dyn std::io::Read) -> Result<Self> {
+        serde_json::from_reader(stdioread).context("Reading BallotEncrypted")
+    }
+
     /// Scale a [`BallotEncrypted`] by a factor, producing a [`ScaledBallotEncrypted`].
     /// Each encrypted vote in the ballot gets scaled by the same factor.
     pub fn scale(
@@ -254,7 +400,7 @@ impl<'a> BallotTallyBuilder<'a> {
 #[cfg(test)]
 #[allow(clippy::unwrap_used)]
 mod test {
-    use std::collections::BTreeSet;
+    use std::{collections::BTreeSet, num::NonZeroU32};
 
     use super::*;
     use crate::{
@@ -272,9 +418,11 @@ mod test {
         hashes_ext::HashesExt,
         index::Index,
         joint_election_public_key::JointElectionPublicKey,
+        vendor_data::VendorData,
         verifiable_decryption::{
             CombinedDecryptionShare, DecryptionProof, DecryptionShare, VerifiableDecryption,
         },
+        zk::ProofRangeValidationError,
     };
     use std::iter::zip;
     use util::csprng::Csprng;
@@ -337,56 +485,79 @@ mod test {
         let selections = BTreeMap::from([
             (
                 Index::from_one_based_index(1).unwrap(),
-                ContestSelection { vote: vec![1, 0] },
+                ContestSelection {
+                    vote: vec![1, 0],
+                    write_ins: Vec::new(),
+                },
             ),
             (
                 Index::from_one_based_index(2).unwrap(),
                 ContestSelection {
                     vote: vec![0, 1, 0, 0],
+                    write_ins: Vec::new(),
                 },
             ),
             (
                 Index::from_one_based_index(3).unwrap(),
                 ContestSelection {
                     vote: vec![0, 0, 1],
+                    write_ins: Vec::new(),
                 },
             ),
             (
                 Index::from_one_based_index(4).unwrap(),
                 ContestSelection {
                     vote: vec![1, 0, 0],
+                    write_ins: Vec::new(),
                 },
             ),
             (
                 Index::from_one_based_index(5).unwrap(),
                 ContestSelection {
                     vote: vec![0, 1, 1, 0, 0, 0, 0, 1, 0, 0, 0],
+                    write_ins: Vec::new(),
                 },
             ),
             (
                 Index::from_one_based_index(6).unwrap(),
-                ContestSelection { vote: vec![1, 0] },
+                ContestSelection {
+                    vote: vec![1, 0],
+                    write_ins: Vec::new(),
+                },
             ),
             (
                 Index::from_one_based_index(7).unwrap(),
-                ContestSelection { vote: vec![0, 0] },
+                ContestSelection {
+                    vote: vec![0, 0],
+                    write_ins: Vec::new(),
+                },
             ),
             (
                 Index::from_one_based_index(8).unwrap(),
-                ContestSelection { vote: vec![0, 1] },
+                ContestSelection {
+                    vote: vec![0, 1],
+                    write_ins: Vec::new(),
+                },
             ),
             (
                 Index::from_one_based_index(9).unwrap(),
-                ContestSelection { vote: vec![1, 0] },
+                ContestSelection {
+                    vote: vec![1, 0],
+                    write_ins: Vec::new(),
+                },
             ),
             (
                 Index::from_one_based_index(11).unwrap(),
-                ContestSelection { vote: vec![0, 1] },
+                ContestSelection {
+                    vote: vec![0, 1],
+                    write_ins: Vec::new(),
+                },
             ),
         ]);
 
         let ballot_from_selections =
-            BallotEncrypted::new_from_selections(&device, &mut csprng, &primary_nonce, &selections);
+            BallotEncrypted::new_from_selections(&device, &mut csprng, &primary_nonce, &selections)
+                .unwrap();
 
         // Let's verify the ballot proofs.
 
@@ -396,6 +567,86 @@ mod test {
         assert!(verify_result)
     }
 
+    /// Testing that an option with a vote limit may receive several votes of the same voter
+    #[test]
+    fn test_option_vote_limits() {
+        let election_parameters = example_election_parameters();
+        let guardian_public_keys: Vec<_> = (1..=5).map(|i| g_key(i).make_public_key()).collect();
+
+        let mut election_manifest = short_manifest();
+        let contest_index = Index::from_one_based_index(1).unwrap();
+        let contest = election_manifest.contests.get_mut(contest_index).unwrap();
+        contest
+            .options
+            .get_mut(Index::from_one_based_index(1).unwrap())
+            .unwrap()
+            .opt_vote_limit = NonZeroU32::new(2);
+        assert_eq!(contest.option_vote_limits(), vec![2, 1, 1, 1]);
+
+        let pre_voting_data = PreVotingData::compute(
+            election_manifest,
+            election_parameters.clone(),
+            &guardian_public_keys,
+        )
+        .unwrap();
+        let device = Device::new("Some encryption device", pre_voting_data);
+        let mut csprng = Csprng::new(b"test_option_vote_limits");
+        let selections = BTreeMap::from([
+            (
+                contest_index,
+                ContestSelection {
+                    vote: vec![2, 0, 0, 0],
+                    write_ins: Vec::new(),
+                },
+            ),
+            (
+                Index::from_one_based_index(3).unwrap(),
+                ContestSelection {
+                    vote: vec![0, 0, 0],
+                    write_ins: Vec::new(),
+                },
+            ),
+        ]);
+        let ballot =
+            BallotEncrypted::new_from_selections(&device, &mut csprng, &[1, 2, 3], &selections)
+                .unwrap();
+        assert!(ballot.verify(&device.header, Index::from_one_based_index(1).unwrap()));
+
+        // The option limit is part of the manifest hash, and the same ballot is invalid for
+        // the manifest without it.
+        let pre_voting_data_no_limits =
+            PreVotingData::compute(short_manifest(), election_parameters, &guardian_public_keys)
+                .unwrap();
+        assert_ne!(
+            pre_voting_data_no_limits.hashes.h_m,
+            device.header.hashes.h_m
+        );
+        let contest_no_limits = pre_voting_data_no_limits
+            .manifest
+            .contests
+            .get(contest_index)
+            .unwrap();
+        assert!(
+            !ballot.contests[&contest_index].verify(&pre_voting_data_no_limits, contest_no_limits)
+        );
+        assert_eq!(
+            ballot.validate(
+                &pre_voting_data_no_limits,
+                Index::from_one_based_index(1).unwrap()
+            ),
+            Err(BallotValidationError::InvalidContest(
+                contest_index,
+                ContestEncryptedValidationError::InvalidOptionProof(
+                    1,
+                    ProofRangeValidationError::WrongLength {
+                        expected: 2,
+                        found: 3
+                    }
+                )
+            ))
+        );
+    }
+
     fn short_manifest() -> ElectionManifest {
         let contests = [
             // Contest index 1:
@@ -405,19 +656,28 @@ mod test {
                 options: [
                     ContestOption {
                         label: "Élyria Moonshadow\n(Crystâlheärt)".to_string(),
+                        opt_vote_limit: None,
+                        vendor_data: VendorData::default(),
                     },
                     ContestOption {
                         label: "Archímedes Darkstone\n(Ætherwïng)".to_string(),
+                        opt_vote_limit: None,
+                        vendor_data: VendorData::default(),
                     },
                     ContestOption {
                         label: "Seraphína Stormbinder\n(Independent)".to_string(),
+                        opt_vote_limit: None,
+                        vendor_data: VendorData::default(),
                     },
                     ContestOption {
                         label: "Gávrïel Runëbørne\n(Stärsky)".to_string(),
+                        opt_vote_limit: None,
+                        vendor_data: VendorData::default(),
                     },
                 ]
                 .try_into()
                 .unwrap(),
+                vendor_data: VendorData::default(),
             },
             // Contest index 2:
             Contest {
@@ -426,16 +686,23 @@ mod test {
                 options: [
                     ContestOption {
                         label: "Tïtus Stormforge\n(Ætherwïng)".to_string(),
+                        opt_vote_limit: None,
+                        vendor_data: VendorData::default(),
                     },
                     ContestOption {
                         label: "Fæ Willowgrove\n(Crystâlheärt)".to_string(),
+                        opt_vote_limit: None,
+                        vendor_data: VendorData::default(),
                     },
                     ContestOption {
                         label: "Tèrra Stonebinder\n(Independent)".to_string(),
+                        opt_vote_limit: None,
+                        vendor_data: VendorData::default(),
                     },
                 ]
                 .try_into()
                 .unwrap(),
+                vendor_data: VendorData::default(),
             },
             // Contest index 3:
             Contest {
@@ -444,16 +711,23 @@ mod test {
                 options: [
                     ContestOption {
                         label: "Äeliana Sunsong\n(Crystâlheärt)".to_string(),
+                        opt_vote_limit: None,
+                        vendor_data: VendorData::default(),
                     },
                     ContestOption {
                         label: "Thâlia Shadowdance\n(Ætherwïng)".to_string(),
+                        opt_vote_limit: None,
+                        vendor_data: VendorData::default(),
                     },
                     ContestOption {
                         label: "Jasper Moonstep\n(Stärsky)".to_string(),
+                        opt_vote_limit: None,
+                        vendor_data: VendorData::default(),
                     },
                 ]
                 .try_into()
                 .unwrap(),
+                vendor_data: VendorData::default(),
             },
         ]
         .try_into()
@@ -466,6 +740,7 @@ mod test {
                 contests: BTreeSet::from(
                     [1u32, 3].map(|ix1| ContestIndex::from_one_based_index(ix1).unwrap()),
                 ),
+                vendor_data: VendorData::default(),
             },
             // Ballot style index 2:
             BallotStyle {
@@ -473,6 +748,7 @@ mod test {
                 contests: BTreeSet::from(
                     [2u32, 3].map(|ix1| ContestIndex::from_one_based_index(ix1).unwrap()),
                 ),
+                vendor_data: VendorData::default(),
             },
             // Ballot style index 3:
             BallotStyle {
@@ -480,6 +756,7 @@ mod test {
                 contests: BTreeSet::from(
                     [1, 2u32, 3].map(|ix1| ContestIndex::from_one_based_index(ix1).unwrap()),
                 ),
+                vendor_data: VendorData::default(),
             },
         ]
         .try_into()
@@ -489,6 +766,7 @@ mod test {
             label: "General Election - The United Realms of Imaginaria".to_string(),
             contests,
             ballot_styles,
+            vendor_data: VendorData::default(),
         }
     }
 
@@ -588,11 +866,8 @@ mod test {
 
         let hashes = Hashes::compute(&election_parameters, &election_manifest).unwrap();
 
-        let hashes_ext = HashesExt::compute(
-            &election_parameters,
-            &hashes,
-            &joint_election_public_key,
-        );
+        let hashes_ext =
+            HashesExt::compute(&election_parameters, &hashes, &joint_election_public_key);
 
         let pre_voting_data = PreVotingData {
             manifest: election_manifest.clone(),
@@ -611,12 +886,14 @@ mod test {
                 Index::from_one_based_index(1).unwrap(),
                 ContestSelection {
                     vote: vec![1, 1, 0, 0],
+                    write_ins: Vec::new(),
                 },
             ),
             (
                 Index::from_one_based_index(3).unwrap(),
                 ContestSelection {
                     vote: vec![0, 1, 0],
+                    write_ins: Vec::new(),
                 },
             ),
         ]);
@@ -627,12 +904,14 @@ mod test {
                 Index::from_one_based_index(2).unwrap(),
                 ContestSelection {
                     vote: vec![0, 1, 0],
+                    write_ins: Vec::new(),
                 },
             ),
             (
                 Index::from_one_based_index(3).unwrap(),
                 ContestSelection {
                     vote: vec![0, 1, 0],
+                    write_ins: Vec::new(),
                 },
             ),
         ]);
@@ -642,33 +921,39 @@ mod test {
                 Index::from_one_based_index(1).unwrap(),
                 ContestSelection {
                     vote: vec![1, 0, 0, 0],
+                    write_ins: Vec::new(),
                 },
             ),
             (
                 Index::from_one_based_index(2).unwrap(),
                 ContestSelection {
                     vote: vec![1, 0, 0],
+                    write_ins: Vec::new(),
                 },
             ),
             (
                 Index::from_one_based_index(3).unwrap(),
                 ContestSelection {
                     vote: vec![1, 0, 0],
+                    write_ins: Vec::new(),
                 },
             ),
         ]);
         let ballot_voter1 =
-            BallotEncrypted::new_from_selections(&device, &mut csprng, &primary_nonce, &voter1);
+            BallotEncrypted::new_from_selections(&device, &mut csprng, &primary_nonce, &voter1)
+                .unwrap();
         let verify_result1 =
             ballot_voter1.verify(&device.header, Index::from_one_based_index(1).unwrap());
         assert!(verify_result1);
         let ballot_voter2 =
-            BallotEncrypted::new_from_selections(&device, &mut csprng, &primary_nonce, &voter2);
+            BallotEncrypted::new_from_selections(&device, &mut csprng, &primary_nonce, &voter2)
+                .unwrap();
         let verify_result2 =
             ballot_voter2.verify(&device.header, Index::from_one_based_index(2).unwrap());
         assert!(verify_result2);
         let ballot_voter3 =
-            BallotEncrypted::new_from_selections(&device, &mut csprng, &primary_nonce, &voter3);
+            BallotEncrypted::new_from_selections(&device, &mut csprng, &primary_nonce, &voter3)
+                .unwrap();
         let verify_result3 =
             ballot_voter3.verify(&device.header, Index::from_one_based_index(3).unwrap());
         assert!(verify_result3);
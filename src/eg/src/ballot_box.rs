@@ -0,0 +1,490 @@
+// Copyright (C) Microsoft Corporation. All rights reserved.
+
+#![deny(clippy::unwrap_used)]
+#![deny(clippy::expect_used)]
+#![deny(clippy::panic)]
+#![deny(clippy::manual_assert)]
+
+//! This module provides the [`BallotBox`], which records the voters' decisions to cast or to
+//! challenge their encrypted ballots. For more details see Section `3.5` of the Electionguard
+//! specification `2.0.0`.
+//!
+//! Every encrypted ballot is submitted to the ballot box exactly once, either with
+//! [`BallotBox::cast`] or with [`BallotBox::challenge`]. The ballot box refuses a second ballot
+//! with the same confirmation code and returns a [`BallotBoxEntry`] for every accepted ballot.
+//!
+//! The entries form a tamper-evident log: they are a hash chain seeded with the extended base
+//! hash `H_E`, so that every entry commits to the election and to all earlier entries, see
+//! [`verify_ballot_box_entries`]. The entries are not signed. Anyone who knows `H_E` can compute
+//! a valid chain, so the log only shows that published entries were not altered, reordered or
+//! dropped afterwards, not who created them.
+//!
+//! A ballot box can be resumed from its persisted entries and ballots with
+//! [`BallotBox::from_entries`]. Once voting is over, [`BallotBox::into_election_record_body`]
+//! computes the encrypted tally of the cast ballots and publishes the entries in the election
+//! record, where the verifier checks them with [`verify_ballot_box`]. The challenged ballots
+//! proceed to their
+//! [`ChallengedBallotDecryption`](crate::challenged_ballot::ChallengedBallotDecryption).
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::{
+    ballot::{BallotEncrypted, BallotState, BallotStateError},
+    election_record::{ElectionRecordBody, PreVotingData},
+    hash::{eg_h, HValue},
+};
+
+/// Represents errors occurring while submitting a ballot to a [`BallotBox`].
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum BallotBoxError {
+    /// Occurs if a ballot with the same confirmation code is already in the ballot box.
+    #[error("A ballot with confirmation code {0} is already in the ballot box.")]
+    DuplicateConfirmationCode(HValue),
+    /// Occurs if the ballot was already cast or challenged.
+    #[error(transparent)]
+    State(#[from] BallotStateError),
+    /// Occurs if an entry is not at the next position or does not refer to the previous entry.
+    #[error("The ballot box entry at position {0} does not continue the chain of entries.")]
+    BrokenEntryChain(usize),
+    /// Occurs if the hash of an entry does not match its content.
+    #[error("The hash of the ballot box entry at position {0} does not match the entry.")]
+    EntryHashMismatch(usize),
+    /// Occurs if there is no ballot for an entry, or it differs from the entry.
+    #[error("The ballot box entry at position {0} does not match its ballot.")]
+    EntryMismatch(usize),
+    /// Occurs if a cast or challenged ballot has no entry.
+    #[error("The ballot {0} is cast or challenged, but has no ballot box entry.")]
+    MissingEntry(HValue),
+}
+
+/// The record of a ballot accepted by the [`BallotBox`], a link of its tamper-evident log.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BallotBoxEntry {
+    /// The position of the ballot in the ballot box, starting at `1`
+    pub position: usize,
+    /// The confirmation code of the ballot
+    pub confirmation_code: HValue,
+    /// The device that encrypted the ballot
+    pub device: String,
+    /// The ballot ID assigned by the device, if any
+    pub ballot_id: Option<u64>,
+    /// Whether the ballot was cast or challenged
+    pub state: BallotState,
+    /// The hash of the previous entry, or the seed of the ballot box for the first entry
+    pub previous_hash: HValue,
+    /// The hash of this entry, see [`BallotBoxEntry::compute_hash`]
+    pub entry_hash: HValue,
+}
+
+/// The ballot box, i.e., every ballot that was cast or challenged, in order of submission.
+#[derive(Debug)]
+pub struct BallotBox {
+    ballots: Vec<BallotEncrypted>,
+    entries: Vec<BallotBoxEntry>,
+    confirmation_codes: BTreeSet<HValue>,
+    h_e: HValue,
+}
+
+/// Computes the seed of the chain of ballot box entries, i.e., `H(H_E; "BALLOT BOX")`.
+///
+/// The ballot box is not part of the specification, so its hashes use the domain separator
+/// `"BALLOT BOX"` rather than a single byte.
+fn ballot_box_seed(h_e: &HValue) -> HValue {
+    eg_h(h_e, b"BALLOT BOX")
+}
+
+impl BallotBoxEntry {
+    /// Computes the hash of the entry from the extended base hash `H_E` and all other fields,
+    /// including the hash of the previous entry.
+    pub fn compute_hash(&self, h_e: &HValue) -> HValue {
+        let mut v = b"BALLOT BOX".to_vec();
+        v.extend_from_slice(self.previous_hash.as_ref());
+        v.extend_from_slice(&(self.position as u64).to_be_bytes());
+        v.extend_from_slice(self.confirmation_code.as_ref());
+        v.extend_from_slice(&(self.device.len() as u64).to_be_bytes());
+        v.extend_from_slice(self.device.as_bytes());
+        match self.ballot_id {
+            Some(ballot_id) => {
+                v.push(1);
+                v.extend_from_slice(&ballot_id.to_be_bytes());
+            }
+            None => v.push(0),
+        }
+        v.push(match self.state {
+            BallotState::Uncast => 0,
+            BallotState::Cast => 1,
+            BallotState::Challenged => 2,
+        });
+        eg_h(h_e, &v)
+    }
+
+    /// Returns whether the entry records the given ballot with its current state.
+    pub fn matches(&self, ballot: &BallotEncrypted) -> bool {
+        self.confirmation_code == ballot.confirmation_code
+            && self.device == ballot.device
+            && self.ballot_id == ballot.ballot_id
+            && &self.state == ballot.state()
+    }
+
+    /// Reads a `BallotBoxEntry` from a `std::io::Read`.
+    pub fn from_stdioread(stdioread: &mut dyn std::io::Read) -> Result<Self> {
+        serde_json::from_reader(stdioread).context("Reading BallotBoxEntry")
+    }
+
+    /// Writes a `BallotBoxEntry` to a `std::io::Write`.
+    pub fn to_stdiowrite(&self, stdiowrite: &mut dyn std::io::Write) -> Result<()> {
+        let mut ser = serde_json::Serializer::pretty(stdiowrite);
+
+        self.serialize(&mut ser)
+            .map_err(Into::<anyhow::Error>::into)
+            .and_then(|_| ser.into_inner().write_all(b"\n").map_err(Into::into))
+            .context("Writing BallotBoxEntry")
+    }
+}
+
+impl BallotBox {
+    /// Creates an empty ballot box for the election.
+    pub fn new(pre_voting_data: &PreVotingData) -> Self {
+        BallotBox {
+            ballots: Vec::new(),
+            entries: Vec::new(),
+            confirmation_codes: BTreeSet::new(),
+            h_e: pre_voting_data.hashes_ext.h_e,
+        }
+    }
+
+    /// Resumes a ballot box from its persisted entries and ballots, both in order of submission.
+    ///
+    /// Fails if the entries do not form a valid chain, if an entry does not match the ballot at
+    /// its position, or if a confirmation code occurs twice.
+    pub fn from_entries(
+        pre_voting_data: &PreVotingData,
+        entries: Vec<BallotBoxEntry>,
+        ballots: Vec<BallotEncrypted>,
+    ) -> Result<Self, BallotBoxError> {
+        verify_ballot_box_entries(pre_voting_data, &entries)?;
+
+        let mut confirmation_codes = BTreeSet::new();
+        for (position, (entry, ballot)) in (1..).zip(entries.iter().zip(&ballots)) {
+            if !entry.matches(ballot) {
+                return Err(BallotBoxError::EntryMismatch(position));
+            }
+            if !confirmation_codes.insert(entry.confirmation_code) {
+                return Err(BallotBoxError::DuplicateConfirmationCode(
+                    entry.confirmation_code,
+                ));
+            }
+        }
+        if entries.len() != ballots.len() {
+            return Err(BallotBoxError::EntryMismatch(
+                entries.len().min(ballots.len()) + 1,
+            ));
+        }
+
+        Ok(BallotBox {
+            ballots,
+            entries,
+            confirmation_codes,
+            h_e: pre_voting_data.hashes_ext.h_e,
+        })
+    }
+
+    /// Casts an uncast ballot and accepts it into the ballot box.
+    pub fn cast(&mut self, ballot: BallotEncrypted) -> Result<BallotBoxEntry, BallotBoxError> {
+        self.submit(ballot, BallotEncrypted::cast)
+    }
+
+    /// Challenges an uncast ballot and accepts it into the ballot box.
+    pub fn challenge(&mut self, ballot: BallotEncrypted) -> Result<BallotBoxEntry, BallotBoxError> {
+        self.submit(ballot, BallotEncrypted::challenge)
+    }
+
+    fn submit(
+        &mut self,
+        mut ballot: BallotEncrypted,
+        decide: fn(&mut BallotEncrypted) -> Result<(), BallotStateError>,
+    ) -> Result<BallotBoxEntry, BallotBoxError> {
+        let confirmation_code = ballot.confirmation_code;
+        if self.confirmation_codes.contains(&confirmation_code) {
+            return Err(BallotBoxError::DuplicateConfirmationCode(confirmation_code));
+        }
+        decide(&mut ballot)?;
+
+        let mut entry = BallotBoxEntry {
+            position: self.ballots.len() + 1,
+            confirmation_code,
+            device: ballot.device.clone(),
+            ballot_id: ballot.ballot_id,
+            state: ballot.state().clone(),
+            previous_hash: self
+                .entries
+                .last()
+                .map_or_else(|| ballot_box_seed(&self.h_e), |entry| entry.entry_hash),
+            entry_hash: HValue::default(),
+        };
+        entry.entry_hash = entry.compute_hash(&self.h_e);
+        self.confirmation_codes.insert(confirmation_code);
+        self.ballots.push(ballot);
+        self.entries.push(entry.clone());
+        Ok(entry)
+    }
+
+    /// Returns whether a ballot with the given confirmation code is in the ballot box.
+    pub fn contains(&self, confirmation_code: &HValue) -> bool {
+        self.confirmation_codes.contains(confirmation_code)
+    }
+
+    /// Returns every ballot in the ballot box, in order of submission.
+    pub fn ballots(&self) -> &[BallotEncrypted] {
+        &self.ballots
+    }
+
+    /// Returns the entries of every ballot in the ballot box, in order of submission.
+    pub fn entries(&self) -> &[BallotBoxEntry] {
+        &self.entries
+    }
+
+    /// Returns an iterator over the cast ballots.
+    pub fn cast_ballots(&self) -> impl Iterator<Item = &BallotEncrypted> {
+        self.ballots
+            .iter()
+            .filter(|ballot| ballot.state() == &BallotState::Cast)
+    }
+
+    /// Returns an iterator over the challenged ballots.
+    pub fn challenged_ballots(&self) -> impl Iterator<Item = &BallotEncrypted> {
+        self.ballots
+            .iter()
+            .filter(|ballot| ballot.state() == &BallotState::Challenged)
+    }
+
+    /// Closes the ballot box and creates the body of the election record, including the
+    /// entries of the ballot box and the encrypted tally of the cast ballots.
+    pub fn into_election_record_body(
+        self,
+        pre_voting_data: &PreVotingData,
+    ) -> Result<ElectionRecordBody> {
+        let mut body = ElectionRecordBody::new(self.ballots);
+        body.ballot_box_entries = self.entries;
+        body.encrypted_tally = Some(body.compute_encrypted_tally(pre_voting_data)?);
+        Ok(body)
+    }
+}
+
+/// Verifies the entries returned by a [`BallotBox`], in order of submission.
+///
+/// This checks that the entries are at consecutive positions starting at `1`, that each entry
+/// refers to the hash of the previous one (or to the seed of the ballot box), and that the hash
+/// of every entry matches its content.
+pub fn verify_ballot_box_entries<'a>(
+    pre_voting_data: &PreVotingData,
+    entries: impl IntoIterator<Item = &'a BallotBoxEntry>,
+) -> Result<(), BallotBoxError> {
+    let h_e = &pre_voting_data.hashes_ext.h_e;
+    let mut previous_hash = ballot_box_seed(h_e);
+    for (entry, position) in entries.into_iter().zip(1..) {
+        if entry.position != position || entry.previous_hash != previous_hash {
+            return Err(BallotBoxError::BrokenEntryChain(position));
+        }
+        if entry.entry_hash != entry.compute_hash(h_e) {
+            return Err(BallotBoxError::EntryHashMismatch(position));
+        }
+        previous_hash = entry.entry_hash;
+    }
+    Ok(())
+}
+
+/// Verifies the entries of a [`BallotBox`] against the ballots of the election.
+///
+/// In addition to [`verify_ballot_box_entries`], this checks that no confirmation code occurs
+/// twice, that every entry matches the ballot with its confirmation code, and that every cast or
+/// challenged ballot has an entry. The ballots may be in any order.
+pub fn verify_ballot_box<'a>(
+    pre_voting_data: &PreVotingData,
+    entries: &[BallotBoxEntry],
+    ballots: impl IntoIterator<Item = &'a BallotEncrypted>,
+) -> Result<(), BallotBoxError> {
+    verify_ballot_box_entries(pre_voting_data, entries)?;
+
+    let mut decided_ballots = BTreeMap::new();
+    for ballot in ballots {
+        if ballot.state() != &BallotState::Uncast {
+            decided_ballots
+                .entry(ballot.confirmation_code)
+                .or_insert(ballot);
+        }
+    }
+
+    let mut confirmation_codes = BTreeSet::new();
+    for entry in entries {
+        if !confirmation_codes.insert(entry.confirmation_code) {
+            return Err(BallotBoxError::DuplicateConfirmationCode(
+                entry.confirmation_code,
+            ));
+        }
+        match decided_ballots.get(&entry.confirmation_code) {
+            Some(ballot) if entry.matches(ballot) => {}
+            _ => return Err(BallotBoxError::EntryMismatch(entry.position)),
+        }
+    }
+    if let Some(code) = decided_ballots
+        .keys()
+        .find(|code| !confirmation_codes.contains(code))
+    {
+        return Err(BallotBoxError::MissingEntry(*code));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod test {
+    use util::csprng::Csprng;
+
+    use super::*;
+    use crate::{
+        challenged_ballot::ChallengedBallotDecryption, device::Device,
+        example_election::ExampleElection,
+        example_election_parameters::example_election_parameters,
+    };
+
+    #[test]
+    fn test_ballot_box() {
+        let mut csprng = Csprng::new(b"test_ballot_box");
+        let example_election = ExampleElection::new(&mut csprng, example_election_parameters());
+        let pre_voting_data = &example_election.pre_voting_data;
+
+        let device = Device::new("Some encryption device", pre_voting_data.clone());
+        let mut ballots = (0..3).map(|n| {
+            BallotEncrypted::new_from_selections(
+                &device,
+                &mut csprng,
+                &[n as u8],
+                &example_election.selections(0),
+            )
+            .unwrap()
+        });
+
+        let mut ballot_box = BallotBox::new(pre_voting_data);
+        let entry1 = ballot_box.cast(ballots.next().unwrap()).unwrap();
+        assert_eq!(entry1.position, 1);
+        assert_eq!(entry1.state, BallotState::Cast);
+        let challenged = ballots.next().unwrap();
+        let challenged_code = challenged.confirmation_code;
+        let entry2 = ballot_box.challenge(challenged).unwrap();
+        assert_eq!(entry2.position, 2);
+        assert_eq!(entry2.state, BallotState::Challenged);
+        assert_eq!(entry2.previous_hash, entry1.entry_hash);
+        verify_ballot_box_entries(pre_voting_data, [&entry1, &entry2]).unwrap();
+
+        // The entries are bound to their content, their order and the election.
+        let mut tampered = entry2.clone();
+        tampered.state = BallotState::Cast;
+        assert_eq!(
+            verify_ballot_box_entries(pre_voting_data, [&entry1, &tampered]),
+            Err(BallotBoxError::EntryHashMismatch(2))
+        );
+        assert_eq!(
+            verify_ballot_box_entries(pre_voting_data, [&entry2]),
+            Err(BallotBoxError::BrokenEntryChain(1))
+        );
+        let mut other_election = pre_voting_data.clone();
+        other_election.hashes_ext.h_e = HValue::from([1u8; 32]);
+        assert_eq!(
+            verify_ballot_box_entries(&other_election, [&entry1, &entry2]),
+            Err(BallotBoxError::BrokenEntryChain(1))
+        );
+
+        // A ballot cannot be submitted twice, nor can its decision be changed.
+        let ballot = ballots.next().unwrap();
+        let duplicate: BallotEncrypted =
+            serde_json::from_value(serde_json::to_value(&ballot).unwrap()).unwrap();
+        ballot_box.cast(ballot).unwrap();
+        assert_eq!(
+            ballot_box.challenge(duplicate),
+            Err(BallotBoxError::DuplicateConfirmationCode(
+                ballot_box.ballots()[2].confirmation_code
+            ))
+        );
+        let mut cast: BallotEncrypted =
+            serde_json::from_value(serde_json::to_value(&ballot_box.ballots()[0]).unwrap())
+                .unwrap();
+        assert!(matches!(
+            cast.challenge(),
+            Err(BallotStateError::AlreadyDecided { .. })
+        ));
+
+        // A ballot box resumed from its entries and ballots still refuses the duplicate.
+        let to_owned = |ballot: &BallotEncrypted| -> BallotEncrypted {
+            serde_json::from_value(serde_json::to_value(ballot).unwrap()).unwrap()
+        };
+        let entries = ballot_box.entries().to_vec();
+        let ballots = ballot_box
+            .ballots()
+            .iter()
+            .map(to_owned)
+            .collect::<Vec<_>>();
+        let mut resumed =
+            BallotBox::from_entries(pre_voting_data, entries.clone(), ballots).unwrap();
+        assert_eq!(
+            resumed.cast(to_owned(&ballot_box.ballots()[2])),
+            Err(BallotBoxError::DuplicateConfirmationCode(
+                ballot_box.ballots()[2].confirmation_code
+            ))
+        );
+        assert_eq!(
+            BallotBox::from_entries(
+                pre_voting_data,
+                entries.clone(),
+                ballot_box.ballots()[1..].iter().map(to_owned).collect()
+            )
+            .unwrap_err(),
+            BallotBoxError::EntryMismatch(1)
+        );
+        assert_eq!(
+            BallotBox::from_entries(
+                pre_voting_data,
+                entries[..2].to_vec(),
+                ballot_box.ballots().iter().map(to_owned).collect()
+            )
+            .unwrap_err(),
+            BallotBoxError::EntryMismatch(3)
+        );
+
+        // The entries are checked against the ballots in any order.
+        let mut ballots = ballot_box.ballots().iter().collect::<Vec<_>>();
+        ballots.reverse();
+        verify_ballot_box(pre_voting_data, &entries, ballots.iter().copied()).unwrap();
+        assert_eq!(
+            verify_ballot_box(pre_voting_data, &entries, &ballot_box.ballots()[1..]),
+            Err(BallotBoxError::EntryMismatch(1))
+        );
+        assert_eq!(
+            verify_ballot_box(pre_voting_data, &entries[..2], ballot_box.ballots()),
+            Err(BallotBoxError::MissingEntry(
+                ballot_box.ballots()[2].confirmation_code
+            ))
+        );
+
+        // The challenged ballot proceeds to its decryption.
+        let challenged = ballot_box.challenged_ballots().next().unwrap();
+        ChallengedBallotDecryption::from_primary_nonce(pre_voting_data, challenged, &[1u8])
+            .unwrap()
+            .validate(pre_voting_data, challenged)
+            .unwrap();
+
+        let body = ballot_box
+            .into_election_record_body(pre_voting_data)
+            .unwrap();
+        body.validate().unwrap();
+        assert_eq!(body.ballot_box_entries, entries);
+        assert_eq!(body.cast_ballots.len(), 2);
+        assert_eq!(body.challenged_ballots, BTreeSet::from([challenged_code]));
+        assert!(body.encrypted_tally.is_some());
+    }
+}
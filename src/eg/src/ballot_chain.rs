@@ -0,0 +1,336 @@
+// Copyright (C) Microsoft Corporation. All rights reserved.
+
+#![deny(clippy::unwrap_used)]
+#![deny(clippy::expect_used)]
+#![deny(clippy::panic)]
+#![deny(clippy::manual_assert)]
+
+//! This module provides the [`BallotChain`], i.e., the chaining of the confirmation codes of the
+//! ballots encrypted by a device. For more details see Section `3.4.4` of the Electionguard
+//! specification `2.0.0`.
+//!
+//! Whether ballots are chained is determined by
+//! [`VaryingParameters::ballot_chaining`](crate::varying_parameters::VaryingParameters::ballot_chaining).
+//! If ballots are chained, the auxiliary input `B_aux` of the confirmation code (Equation `59`)
+//! of the first ballot of a device is the seed `H_0 = H(H_E; 24, device)`, and the `B_aux` of
+//! every later ballot is the confirmation code of the previous ballot. At the end of the
+//! election the chain is closed with `H̄ = H(H_E; 24, H_ℓ, "CLOSE")`, where `H_ℓ` is the last
+//! confirmation code of the chain.
+
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::{
+    ballot::BallotEncrypted,
+    confirmation_code::confirmation_code,
+    election_record::PreVotingData,
+    hash::{eg_h, HValue},
+    varying_parameters::BallotChaining,
+};
+
+/// Represents errors occurring while chaining ballots or verifying a ballot chain.
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum BallotChainError {
+    /// Occurs if ballots are chained although the election parameters prohibit it.
+    #[error("Ballot chaining is prohibited by the election parameters.")]
+    Prohibited,
+    /// Occurs if a ballot was encrypted by a different device than the chain belongs to.
+    #[error("The ballot {code} was encrypted by device {given:?}, but the chain belongs to {expected:?}.")]
+    DeviceMismatch {
+        code: HValue,
+        expected: String,
+        given: String,
+    },
+    /// Occurs if the `B_aux` of a ballot is not the previous confirmation code of the chain.
+    #[error("The ballot {code} at position {position} does not continue the chain.")]
+    BrokenChain { position: usize, code: HValue },
+    /// Occurs if the confirmation code of a ballot does not match its contests and `B_aux`.
+    #[error("The confirmation code {0} does not match the ballot's contest hashes and B_aux.")]
+    ConfirmationCodeMismatch(HValue),
+    /// Occurs if a ballot is appended to a closed chain.
+    #[error("The ballot chain of device {0:?} is already closed.")]
+    Closed(String),
+    /// Occurs if the chain of a device has no closing record.
+    #[error("The ballot chain of device {0:?} has not been closed.")]
+    MissingClosing(String),
+    /// Occurs if the closing record does not match the chain.
+    #[error("The closing record of device {0:?} does not match its ballot chain.")]
+    ClosingMismatch(String),
+}
+
+/// The chain state of an encryption device.
+///
+/// The state must be persisted by the device between ballots, e.g., with
+/// [`BallotChain::to_stdiowrite`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BallotChain {
+    /// The identifier of the device
+    pub device: String,
+    /// The seed `H_0` of the chain
+    pub seed: HValue,
+    /// The confirmation code of the last ballot of the chain, if any
+    pub last: Option<HValue>,
+    /// The number of ballots in the chain
+    pub len: usize,
+    /// Whether the chain has been closed
+    pub closed: bool,
+}
+
+/// The record closing the ballot chain of a device, published in the election record.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BallotChainClosing {
+    /// The identifier of the device
+    pub device: String,
+    /// The number of ballots in the chain
+    pub len: usize,
+    /// The last confirmation code `H_ℓ` of the chain, or the seed `H_0` if the chain is empty
+    pub last: HValue,
+    /// The closing hash `H̄`
+    pub closing_hash: HValue,
+}
+
+/// Computes the seed `H_0` of the ballot chain of a device.
+pub fn chain_seed(pre_voting_data: &PreVotingData, device: &str) -> HValue {
+    let mut v = vec![0x24];
+    v.extend_from_slice(device.as_bytes());
+    eg_h(&pre_voting_data.hashes_ext.h_e, &v)
+}
+
+/// Computes the closing hash `H̄` of a ballot chain with last confirmation code `last`.
+fn closing_hash(pre_voting_data: &PreVotingData, last: &HValue) -> HValue {
+    let mut v = vec![0x24];
+    v.extend_from_slice(last.as_ref());
+    v.extend_from_slice(b"CLOSE");
+    eg_h(&pre_voting_data.hashes_ext.h_e, &v)
+}
+
+/// Checks that the confirmation code of `ballot` matches its contest hashes and `B_aux`.
+pub fn verify_confirmation_code(
+    pre_voting_data: &PreVotingData,
+    ballot: &BallotEncrypted,
+) -> Result<(), BallotChainError> {
+    let code = confirmation_code(
+        &pre_voting_data.hashes_ext.h_e,
+        ballot.contests.values(),
+        ballot.b_aux.as_ref(),
+    );
+    if code != ballot.confirmation_code {
+        return Err(BallotChainError::ConfirmationCodeMismatch(
+            ballot.confirmation_code,
+        ));
+    }
+    Ok(())
+}
+
+impl BallotChain {
+    /// Starts the ballot chain of a device.
+    ///
+    /// Fails if the election parameters prohibit ballot chaining.
+    pub fn new(pre_voting_data: &PreVotingData, device: &str) -> Result<Self, BallotChainError> {
+        if pre_voting_data
+            .parameters
+            .varying_parameters
+            .ballot_chaining
+            == BallotChaining::Prohibited
+        {
+            return Err(BallotChainError::Prohibited);
+        }
+
+        Ok(BallotChain {
+            device: device.to_string(),
+            seed: chain_seed(pre_voting_data, device),
+            last: None,
+            len: 0,
+            closed: false,
+        })
+    }
+
+    /// The `B_aux` of the next ballot of the chain.
+    pub fn b_aux(&self) -> HValue {
+        self.last.unwrap_or(self.seed)
+    }
+
+    /// Appends a ballot to the chain.
+    ///
+    /// Fails if the chain is closed, or if the ballot was not encrypted by the device with the
+    /// `B_aux` of [`BallotChain::b_aux`].
+    pub fn append(&mut self, ballot: &BallotEncrypted) -> Result<(), BallotChainError> {
+        if self.closed {
+            return Err(BallotChainError::Closed(self.device.clone()));
+        }
+        if ballot.device != self.device {
+            return Err(BallotChainError::DeviceMismatch {
+                code: ballot.confirmation_code,
+                expected: self.device.clone(),
+                given: ballot.device.clone(),
+            });
+        }
+        if ballot.b_aux != self.b_aux() {
+            return Err(BallotChainError::BrokenChain {
+                position: self.len + 1,
+                code: ballot.confirmation_code,
+            });
+        }
+
+        self.last = Some(ballot.confirmation_code);
+        self.len += 1;
+        Ok(())
+    }
+
+    /// Closes the chain. No further ballots can be appended.
+    pub fn close(&mut self, pre_voting_data: &PreVotingData) -> BallotChainClosing {
+        self.closed = true;
+        let last = self.b_aux();
+        BallotChainClosing {
+            device: self.device.clone(),
+            len: self.len,
+            last,
+            closing_hash: closing_hash(pre_voting_data, &last),
+        }
+    }
+
+    /// Reads a `BallotChain` from a `std::io::Read`.
+    pub fn from_stdioread(stdioread: &mut dyn std::io::Read) -> anyhow::Result<Self> {
+        serde_json::from_reader(stdioread).context("Reading BallotChain")
+    }
+
+    /// Writes a `BallotChain` to a `std::io::Write`.
+    pub fn to_stdiowrite(&self, stdiowrite: &mut dyn std::io::Write) -> anyhow::Result<()> {
+        let mut ser = serde_json::Serializer::pretty(stdiowrite);
+
+        self.serialize(&mut ser)
+            .map_err(Into::<anyhow::Error>::into)
+            .and_then(|_| ser.into_inner().write_all(b"\n").map_err(Into::into))
+            .context("Writing BallotChain")
+    }
+}
+
+/// Verifies the ballot chain of a device.
+///
+/// The arguments are
+/// - `pre_voting_data` - the pre-voting data
+/// - `device` - the identifier of the device
+/// - `ballots` - the ballots encrypted by the device, in the order of encryption
+/// - `opt_closing` - the record closing the chain
+///
+/// This checks that the confirmation code of every ballot matches its contests and `B_aux`, that
+/// the `B_aux` of every ballot is the previous confirmation code (or the seed `H_0`), and that
+/// the closing record matches the end of the chain.
+pub fn verify_ballot_chain<'a>(
+    pre_voting_data: &PreVotingData,
+    device: &str,
+    ballots: impl IntoIterator<Item = &'a BallotEncrypted>,
+    opt_closing: Option<&BallotChainClosing>,
+) -> Result<(), BallotChainError> {
+    let mut chain = BallotChain {
+        device: device.to_string(),
+        seed: chain_seed(pre_voting_data, device),
+        last: None,
+        len: 0,
+        closed: false,
+    };
+
+    for ballot in ballots {
+        verify_confirmation_code(pre_voting_data, ballot)?;
+        chain.append(ballot)?;
+    }
+
+    let Some(closing) = opt_closing else {
+        return Err(BallotChainError::MissingClosing(device.to_string()));
+    };
+    if *closing != chain.close(pre_voting_data) {
+        return Err(BallotChainError::ClosingMismatch(device.to_string()));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod test {
+    use util::csprng::Csprng;
+
+    use super::*;
+    use crate::{
+        device::Device, example_election::ExampleElection,
+        example_election_parameters::example_election_parameters,
+    };
+
+    #[test]
+    fn test_ballot_chain() {
+        let mut csprng = Csprng::new(b"test_ballot_chain");
+        let mut election_parameters = example_election_parameters();
+        election_parameters.varying_parameters.ballot_chaining = BallotChaining::Required;
+
+        let example_election = ExampleElection::new(&mut csprng, election_parameters);
+        let pre_voting_data = &example_election.pre_voting_data;
+
+        let device = Device::new("Some encryption device", pre_voting_data.clone());
+
+        let mut chain = BallotChain::new(pre_voting_data, &device.uuid).unwrap();
+        let ballots = (0..3)
+            .map(|n| {
+                BallotEncrypted::new_from_selections_chained(
+                    &device,
+                    &mut csprng,
+                    &[n as u8],
+                    &example_election.selections(n),
+                    &mut chain,
+                )
+                .unwrap()
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(ballots[0].b_aux, chain.seed);
+        assert_eq!(ballots[1].b_aux, ballots[0].confirmation_code);
+        let closing = chain.close(pre_voting_data);
+        assert_eq!(closing.len, 3);
+
+        verify_ballot_chain(pre_voting_data, &device.uuid, &ballots, Some(&closing)).unwrap();
+
+        assert_eq!(
+            verify_ballot_chain(pre_voting_data, &device.uuid, &ballots, None),
+            Err(BallotChainError::MissingClosing(device.uuid.clone()))
+        );
+        assert_eq!(
+            verify_ballot_chain(pre_voting_data, &device.uuid, &ballots[..2], Some(&closing)),
+            Err(BallotChainError::ClosingMismatch(device.uuid.clone()))
+        );
+        assert_eq!(
+            verify_ballot_chain(
+                pre_voting_data,
+                &device.uuid,
+                [&ballots[1], &ballots[0], &ballots[2]],
+                Some(&closing)
+            ),
+            Err(BallotChainError::BrokenChain {
+                position: 1,
+                code: ballots[1].confirmation_code
+            })
+        );
+
+        // A closed chain cannot be extended.
+        assert_eq!(
+            BallotEncrypted::new_from_selections_chained(
+                &device,
+                &mut csprng,
+                &[3u8],
+                &example_election.selections(3),
+                &mut chain,
+            )
+            .unwrap_err(),
+            BallotChainError::Closed(device.uuid.clone()).into()
+        );
+
+        // Chaining must not be used if it is prohibited.
+        let mut pre_voting_data = pre_voting_data.clone();
+        pre_voting_data
+            .parameters
+            .varying_parameters
+            .ballot_chaining = BallotChaining::Prohibited;
+        assert_eq!(
+            BallotChain::new(&pre_voting_data, &device.uuid),
+            Err(BallotChainError::Prohibited)
+        );
+    }
+}
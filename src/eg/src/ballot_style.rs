@@ -5,14 +5,17 @@
 #![deny(clippy::panic)]
 #![deny(clippy::manual_assert)]
 
-use std::collections::BTreeSet;
+use std::collections::{BTreeMap, BTreeSet};
 
 //? use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
+use util::csprng::Csprng;
 
-use crate::election_manifest::ContestIndex;
+use crate::contest_selection::ContestSelection;
+use crate::election_manifest::{ContestIndex, ElectionManifest};
 use crate::index::Index;
 use crate::vec1::HasIndexTypeMarker;
+use crate::vendor_data::VendorData;
 
 /// A 1-based index of a `BallotStyle` in the order it is defined in the `ElectionManifest`.
 pub type BallotStyleIndex = Index<BallotStyle>;
@@ -26,6 +29,34 @@ pub struct BallotStyle {
 
     /// The indices of the `Contest`s which appear on ballots of this style.
     pub contests: BTreeSet<ContestIndex>,
+
+    /// Additional data pertaining to this ballot style, e.g., ballot rotation information,
+    /// opaque to ElectionGuard.
+    #[serde(default, skip_serializing_if = "VendorData::is_empty")]
+    pub vendor_data: VendorData,
 }
 
 impl HasIndexTypeMarker for BallotStyle {}
+
+impl BallotStyle {
+    /// Picks a random selection for every contest of this ballot style, e.g., to generate
+    /// synthetic ballots for testing.
+    ///
+    /// Returns `None` if a contest of the ballot style is not in the manifest.
+    pub fn pick_random_selections(
+        &self,
+        csprng: &mut Csprng,
+        manifest: &ElectionManifest,
+    ) -> Option<BTreeMap<ContestIndex, ContestSelection>> {
+        self.contests
+            .iter()
+            .map(|&contest_index| {
+                let contest = manifest.contests.get(contest_index)?;
+                Some((
+                    contest_index,
+                    ContestSelection::new_pick_random_for_contest(csprng, contest),
+                ))
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,680 @@
+// Copyright (C) Microsoft Corporation. All rights reserved.
+
+#![deny(clippy::unwrap_used)]
+#![deny(clippy::expect_used)]
+#![deny(clippy::panic)]
+#![deny(clippy::manual_assert)]
+
+//! This module provides the [`ChallengedBallotDecryption`], i.e., the published plaintext of a
+//! challenged ballot together with the evidence that it is the content of the ballot's
+//! ciphertexts. For more details see Section `3.7` of the Electionguard specification `2.0.0`.
+//!
+//! There are two ways to establish the plaintext of a challenged ballot.
+//! 1. The guardians decrypt every selection, e.g., with a
+//!    [`TallyDecryptionCeremony`](crate::tally_decryption::TallyDecryptionCeremony) on the
+//!    ciphertexts returned by [`ChallengedBallotDecryption::ballot_ciphertexts`], producing a
+//!    [`VerifiableDecryption`](crate::verifiable_decryption::VerifiableDecryption) for every
+//!    option.
+//! 2. The encryption device reveals the primary nonce `ξ_B` of the ballot. Anyone can then
+//!    re-derive the selection nonces of Equation `22` and re-encrypt the plaintext.
+//!
+//! The encrypted contest data of every contest, e.g., write-in text, is decrypted alongside,
+//! either by the guardians (see
+//! [`TallyDecryptionCeremony::finalize_contest_data`](crate::tally_decryption::TallyDecryptionCeremony::finalize_contest_data))
+//! or with the revealed primary nonce.
+//!
+//! In both cases the contest hashes and the confirmation code are recomputed from the ballot's
+//! ciphertexts, so the decryption is bound to the confirmation code the voter received.
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use util::algebra::FieldElement;
+
+use crate::{
+    ballot::{BallotEncrypted, BallotState},
+    ballot_chain,
+    contest_data::{ContestData, ContestDataDecryption, ContestDataError},
+    contest_hash::contest_hash,
+    election_manifest::{ContestIndex, ContestOptionIndex, ElectionManifest},
+    election_record::{DecryptedTally, EncryptedTally, PreVotingData},
+    hash::HValue,
+    joint_election_public_key::Ciphertext,
+    nonce::encrypted as nonce,
+    verifiable_decryption::DecryptionProofValidationError,
+};
+
+/// The evidence that the plaintext of a [`ChallengedBallotDecryption`] is correct.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum ChallengedBallotEvidence {
+    /// The guardians' verifiable decryption of every option of every contest, and of the
+    /// contest data of every contest that has one
+    GuardianDecryption(
+        DecryptedTally,
+        BTreeMap<ContestIndex, ContestDataDecryption>,
+    ),
+    /// The primary nonce `ξ_B` revealed by the encryption device
+    PrimaryNonce(Vec<u8>),
+}
+
+/// The decryption of a challenged ballot.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChallengedBallotDecryption {
+    /// The confirmation code of the challenged ballot
+    pub confirmation_code: HValue,
+    /// For every contest the plaintext vote of every option
+    pub contests: BTreeMap<ContestIndex, Vec<u32>>,
+    /// For every contest with encrypted contest data the decrypted contest data
+    #[serde(default)]
+    pub contest_data: BTreeMap<ContestIndex, ContestData>,
+    /// The evidence that the plaintext is correct
+    pub evidence: ChallengedBallotEvidence,
+}
+
+/// Represents errors occurring while decrypting or verifying a challenged ballot.
+#[derive(Error, Debug)]
+pub enum ChallengedBallotError {
+    /// Occurs if the ballot has not been challenged.
+    #[error("The ballot {0} has not been challenged.")]
+    NotChallenged(HValue),
+    /// Occurs if the decryption belongs to a different ballot.
+    #[error("The decryption is for ballot {given}, but the ballot is {expected}.")]
+    WrongBallot { given: HValue, expected: HValue },
+    /// Occurs if a contest hash does not match the contest's ciphertexts.
+    #[error("The contest hash of contest {0} does not match its ciphertexts.")]
+    ContestHashMismatch(ContestIndex),
+    /// Occurs if the confirmation code does not match the contest hashes.
+    #[error("The confirmation code does not match the contest hashes.")]
+    ConfirmationCodeMismatch,
+    /// Occurs if the decryption does not have one entry per option of every contest.
+    #[error("The decryption of contest {0} does not match the ballot.")]
+    ShapeMismatch(ContestIndex),
+    /// Occurs if a contest is not in the manifest.
+    #[error("Contest {0} is not in the election manifest.")]
+    UnknownContest(ContestIndex),
+    /// Occurs if a plaintext could not be recovered from the primary nonce.
+    #[error("Contest {0}, option {1} is not an encryption of an admissible vote with the revealed nonce.")]
+    NonceMismatch(ContestIndex, usize),
+    /// Occurs if a plaintext does not match its verifiable decryption.
+    #[error("The plaintext of contest {0}, option {1} does not match its decryption.")]
+    PlaintextMismatch(ContestIndex, usize),
+    /// Occurs if the proof of correct decryption of an option is invalid.
+    #[error("The decryption of contest {0}, option {1} is invalid: {2}")]
+    InvalidDecryption(ContestIndex, usize, DecryptionProofValidationError),
+    /// Occurs if the contest data of a contest could not be decrypted.
+    #[error("The contest data of contest {0} could not be decrypted: {1}")]
+    ContestData(ContestIndex, ContestDataError),
+    /// Occurs if the contest data does not match its decryption.
+    #[error("The contest data of contest {0} does not match its decryption.")]
+    ContestDataMismatch(ContestIndex),
+    /// Occurs if the vote of an option exceeds the option's limit.
+    #[error("The vote {2} of contest {0}, option {1} exceeds the option's limit of {3}.")]
+    OptionLimitExceeded(ContestIndex, usize, u32, usize),
+    /// Occurs if the votes of a contest exceed the selection limit.
+    #[error("The {1} votes of contest {0} exceed the selection limit of {2}.")]
+    SelectionLimitExceeded(ContestIndex, u64, usize),
+}
+
+/// Checks that the contest hashes and the confirmation code of `ballot` match its ciphertexts.
+fn verify_confirmation_code(
+    pre_voting_data: &PreVotingData,
+    ballot: &BallotEncrypted,
+) -> Result<(), ChallengedBallotError> {
+    for (&contest_index, contest) in &ballot.contests {
+        let contest_data = contest.contest_data.as_ref();
+        if contest_hash(
+            pre_voting_data,
+            contest_index,
+            &contest.selection,
+            contest_data,
+        ) != contest.contest_hash
+        {
+            return Err(ChallengedBallotError::ContestHashMismatch(contest_index));
+        }
+    }
+    ballot_chain::verify_confirmation_code(pre_voting_data, ballot)
+        .map_err(|_| ChallengedBallotError::ConfirmationCodeMismatch)
+}
+
+/// Returns the plaintext `v` with `0 <= v <= max` such that `ct` is the encryption of `v` with
+/// the given nonce, if any.
+///
+/// The nonce is revealed, so the variable-time exponentiations are used. `alpha = g^xi` does not
+/// depend on `v` and is checked once, and the candidates `beta = K^(xi + v)` are obtained by
+/// multiplying `K^xi` with `K` for every `v`.
+fn reencrypt_with_nonce(
+    pre_voting_data: &PreVotingData,
+    ct: &Ciphertext,
+    nonce: &FieldElement,
+    max: usize,
+) -> Option<u32> {
+    let group = &pre_voting_data.parameters.fixed_parameters.group;
+    let public_key = &pre_voting_data.public_key;
+
+    if group.g_exp(nonce) != ct.alpha {
+        return None;
+    }
+
+    let mut beta = public_key.exp(nonce, group);
+    for v in 0..=max {
+        if beta == ct.beta {
+            return u32::try_from(v).ok();
+        }
+        beta = beta.mul(&public_key.joint_election_public_key, group);
+    }
+    None
+}
+
+impl ChallengedBallotDecryption {
+    /// Returns the ciphertexts of a ballot in the shape of an [`EncryptedTally`], so that the
+    /// guardians can decrypt them with a
+    /// [`TallyDecryptionCeremony`](crate::tally_decryption::TallyDecryptionCeremony).
+    pub fn ballot_ciphertexts(ballot: &BallotEncrypted) -> EncryptedTally {
+        ballot
+            .contests
+            .iter()
+            .map(|(&contest_index, contest)| (contest_index, contest.selection.clone()))
+            .collect()
+    }
+
+    /// Returns the ciphertexts whose decryption by the guardians yields the contest data of a
+    /// ballot, i.e., one ciphertext for every contest with encrypted contest data, see
+    /// [`ContestDataCiphertext::decryption_ciphertext`](crate::contest_data::ContestDataCiphertext::decryption_ciphertext).
+    pub fn contest_data_ciphertexts(ballot: &BallotEncrypted) -> EncryptedTally {
+        ballot
+            .contests
+            .iter()
+            .filter_map(|(&contest_index, contest)| {
+                let contest_data = contest.contest_data.as_ref()?;
+                Some((contest_index, vec![contest_data.decryption_ciphertext()]))
+            })
+            .collect()
+    }
+
+    /// Decrypts the contest data of every contest of a ballot that has one, using the evidence.
+    fn decrypt_contest_data(
+        pre_voting_data: &PreVotingData,
+        ballot: &BallotEncrypted,
+        evidence: &ChallengedBallotEvidence,
+    ) -> Result<BTreeMap<ContestIndex, ContestData>, ChallengedBallotError> {
+        ballot
+            .contests
+            .iter()
+            .filter_map(|(&contest_index, contest)| {
+                let ct = contest.contest_data.as_ref()?;
+                let data = match evidence {
+                    ChallengedBallotEvidence::GuardianDecryption(_, contest_data_decryptions) => {
+                        match contest_data_decryptions.get(&contest_index) {
+                            Some(decryption) => {
+                                decryption.decrypt(pre_voting_data, contest_index, ct)
+                            }
+                            None => {
+                                return Some(Err(ChallengedBallotError::ShapeMismatch(
+                                    contest_index,
+                                )))
+                            }
+                        }
+                    }
+                    ChallengedBallotEvidence::PrimaryNonce(primary_nonce) => {
+                        ct.decrypt_with_nonce(pre_voting_data, primary_nonce, contest_index)
+                    }
+                };
+                Some(
+                    data.map(|data| (contest_index, data))
+                        .map_err(|e| ChallengedBallotError::ContestData(contest_index, e)),
+                )
+            })
+            .collect()
+    }
+
+    /// Creates the decryption of a challenged ballot from the guardians' verifiable decryption
+    /// of its ciphertexts.
+    ///
+    /// The arguments are
+    /// - `pre_voting_data` - the pre-voting data
+    /// - `ballot` - the challenged ballot
+    /// - `decryptions` - a verifiable decryption of every option of every contest of the ballot
+    /// - `contest_data_decryptions` - the decryption of the contest data of every contest of the
+    ///   ballot that has one
+    pub fn from_guardian_decryption(
+        pre_voting_data: &PreVotingData,
+        ballot: &BallotEncrypted,
+        decryptions: DecryptedTally,
+        contest_data_decryptions: BTreeMap<ContestIndex, ContestDataDecryption>,
+    ) -> Result<ChallengedBallotDecryption, ChallengedBallotError> {
+        let field = &pre_voting_data.parameters.fixed_parameters.field;
+        if ballot.state() != &BallotState::Challenged {
+            return Err(ChallengedBallotError::NotChallenged(
+                ballot.confirmation_code,
+            ));
+        }
+
+        let mut contests = BTreeMap::new();
+        for (&contest_index, contest) in &ballot.contests {
+            let Some(contest_decryptions) = decryptions.get(&contest_index) else {
+                return Err(ChallengedBallotError::ShapeMismatch(contest_index));
+            };
+            let Some(manifest_contest) = pre_voting_data.manifest.contests.get(contest_index)
+            else {
+                return Err(ChallengedBallotError::UnknownContest(contest_index));
+            };
+            if contest_decryptions.len() != contest.selection.len() {
+                return Err(ChallengedBallotError::ShapeMismatch(contest_index));
+            }
+
+            let votes = contest_decryptions
+                .iter()
+                .enumerate()
+                .map(|(ix, decryption)| {
+                    (0..=manifest_contest.selection_limit)
+                        .find(|&v| decryption.plain_text == FieldElement::from(v, field))
+                        .and_then(|v| u32::try_from(v).ok())
+                        .ok_or(ChallengedBallotError::PlaintextMismatch(
+                            contest_index,
+                            ix + 1,
+                        ))
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            contests.insert(contest_index, votes);
+        }
+
+        let evidence =
+            ChallengedBallotEvidence::GuardianDecryption(decryptions, contest_data_decryptions);
+        let contest_data = Self::decrypt_contest_data(pre_voting_data, ballot, &evidence)?;
+
+        let self_ = ChallengedBallotDecryption {
+            confirmation_code: ballot.confirmation_code,
+            contests,
+            contest_data,
+            evidence,
+        };
+        self_.validate(pre_voting_data, ballot)?;
+        Ok(self_)
+    }
+
+    /// Creates the decryption of a challenged ballot from the primary nonce revealed by the
+    /// encryption device.
+    ///
+    /// The arguments are
+    /// - `pre_voting_data` - the pre-voting data
+    /// - `ballot` - the challenged ballot
+    /// - `primary_nonce` - the primary nonce `ξ_B` the ballot was encrypted with
+    pub fn from_primary_nonce(
+        pre_voting_data: &PreVotingData,
+        ballot: &BallotEncrypted,
+        primary_nonce: &[u8],
+    ) -> Result<ChallengedBallotDecryption, ChallengedBallotError> {
+        if ballot.state() != &BallotState::Challenged {
+            return Err(ChallengedBallotError::NotChallenged(
+                ballot.confirmation_code,
+            ));
+        }
+
+        let mut contests = BTreeMap::new();
+        for (&contest_index, contest) in &ballot.contests {
+            let Some(manifest_contest) = pre_voting_data.manifest.contests.get(contest_index)
+            else {
+                return Err(ChallengedBallotError::UnknownContest(contest_index));
+            };
+
+            let mut votes = Vec::with_capacity(contest.selection.len());
+            for (ct, j) in contest.selection.iter().zip(1..) {
+                let nonce = ContestOptionIndex::from_one_based_index(j)
+                    .map(|o_idx| nonce(pre_voting_data, primary_nonce, contest_index, o_idx));
+                let Some(vote) = nonce.ok().and_then(|nonce| {
+                    reencrypt_with_nonce(
+                        pre_voting_data,
+                        ct,
+                        &nonce,
+                        manifest_contest.selection_limit,
+                    )
+                }) else {
+                    return Err(ChallengedBallotError::NonceMismatch(
+                        contest_index,
+                        j as usize,
+                    ));
+                };
+                votes.push(vote);
+            }
+            contests.insert(contest_index, votes);
+        }
+
+        let evidence = ChallengedBallotEvidence::PrimaryNonce(primary_nonce.to_vec());
+        let contest_data = Self::decrypt_contest_data(pre_voting_data, ballot, &evidence)?;
+
+        let self_ = ChallengedBallotDecryption {
+            confirmation_code: ballot.confirmation_code,
+            contests,
+            contest_data,
+            evidence,
+        };
+        self_.validate(pre_voting_data, ballot)?;
+        Ok(self_)
+    }
+
+    /// Verifies that the plaintext is the content of the ballot's ciphertexts.
+    ///
+    /// This checks that the decryption belongs to the ballot, that the ballot's contest hashes
+    /// and confirmation code match its ciphertexts, and that the evidence establishes the
+    /// plaintext of every option, i.e., that every verifiable decryption is valid or that every
+    /// ciphertext is the encryption of the plaintext with the nonce derived from the revealed
+    /// primary nonce. Likewise, the evidence must establish the decrypted contest data.
+    ///
+    /// This corresponds to Verification `12`.
+    pub fn validate(
+        &self,
+        pre_voting_data: &PreVotingData,
+        ballot: &BallotEncrypted,
+    ) -> Result<(), ChallengedBallotError> {
+        let fixed_parameters = &pre_voting_data.parameters.fixed_parameters;
+        let field = &fixed_parameters.field;
+
+        if self.confirmation_code != ballot.confirmation_code {
+            return Err(ChallengedBallotError::WrongBallot {
+                given: self.confirmation_code,
+                expected: ballot.confirmation_code,
+            });
+        }
+        verify_confirmation_code(pre_voting_data, ballot)?;
+
+        if let Some(&contest_index) = self
+            .contests
+            .keys()
+            .find(|c| !ballot.contests.contains_key(c))
+        {
+            return Err(ChallengedBallotError::ShapeMismatch(contest_index));
+        }
+
+        for (&contest_index, contest) in &ballot.contests {
+            let Some(votes) = self.contests.get(&contest_index) else {
+                return Err(ChallengedBallotError::ShapeMismatch(contest_index));
+            };
+            if votes.len() != contest.selection.len() {
+                return Err(ChallengedBallotError::ShapeMismatch(contest_index));
+            }
+
+            match &self.evidence {
+                ChallengedBallotEvidence::GuardianDecryption(decryptions, _) => {
+                    let Some(contest_decryptions) = decryptions.get(&contest_index) else {
+                        return Err(ChallengedBallotError::ShapeMismatch(contest_index));
+                    };
+                    if contest_decryptions.len() != contest.selection.len() {
+                        return Err(ChallengedBallotError::ShapeMismatch(contest_index));
+                    }
+                    for (j, (&vote, (decryption, ct))) in votes
+                        .iter()
+                        .zip(contest_decryptions.iter().zip(&contest.selection))
+                        .enumerate()
+                        .map(|(ix, x)| (ix + 1, x))
+                    {
+                        if decryption.plain_text != FieldElement::from(vote, field) {
+                            return Err(ChallengedBallotError::PlaintextMismatch(contest_index, j));
+                        }
+                        decryption
+                            .validate(
+                                fixed_parameters,
+                                &pre_voting_data.hashes_ext,
+                                &pre_voting_data.public_key,
+                                ct,
+                            )
+                            .map_err(|e| {
+                                ChallengedBallotError::InvalidDecryption(contest_index, j, e)
+                            })?;
+                    }
+                }
+                ChallengedBallotEvidence::PrimaryNonce(primary_nonce) => {
+                    for (j, (&vote, ct)) in votes.iter().zip(&contest.selection).enumerate() {
+                        let nonce =
+                            ContestOptionIndex::from_one_based_index(j as u32 + 1).map(|o_idx| {
+                                nonce(pre_voting_data, primary_nonce, contest_index, o_idx)
+                            });
+                        let matches = nonce.is_ok_and(|nonce| {
+                            pre_voting_data.public_key.encrypt_with(
+                                fixed_parameters,
+                                &nonce,
+                                vote as usize,
+                            ) == *ct
+                        });
+                        if !matches {
+                            return Err(ChallengedBallotError::NonceMismatch(contest_index, j + 1));
+                        }
+                    }
+                }
+            }
+        }
+
+        let contest_data = Self::decrypt_contest_data(pre_voting_data, ballot, &self.evidence)?;
+        if let Some(&contest_index) = self
+            .contest_data
+            .keys()
+            .chain(contest_data.keys())
+            .find(|c| self.contest_data.get(c) != contest_data.get(c))
+        {
+            return Err(ChallengedBallotError::ContestDataMismatch(contest_index));
+        }
+
+        Ok(())
+    }
+
+    /// Verifies that the plaintext is an admissible vote, i.e., that the vote of every option
+    /// does not exceed the option's vote limit and that the votes of every contest do not exceed
+    /// the selection limit.
+    ///
+    /// This corresponds to Verification `13`.
+    pub fn validate_content(
+        &self,
+        manifest: &ElectionManifest,
+    ) -> Result<(), ChallengedBallotError> {
+        for (&contest_index, votes) in &self.contests {
+            let Some(contest) = manifest.contests.get(contest_index) else {
+                return Err(ChallengedBallotError::UnknownContest(contest_index));
+            };
+            let option_limits = contest.option_vote_limits();
+            if let Some((ix, &vote)) = votes
+                .iter()
+                .enumerate()
+                .find(|&(ix, &vote)| vote as usize > option_limits.get(ix).copied().unwrap_or(1))
+            {
+                return Err(ChallengedBallotError::OptionLimitExceeded(
+                    contest_index,
+                    ix + 1,
+                    vote,
+                    option_limits.get(ix).copied().unwrap_or(1),
+                ));
+            }
+            let total: u64 = votes.iter().map(|&vote| u64::from(vote)).sum();
+            if total > contest.selection_limit as u64 {
+                return Err(ChallengedBallotError::SelectionLimitExceeded(
+                    contest_index,
+                    total,
+                    contest.selection_limit,
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod test {
+    use std::iter::zip;
+
+    use util::csprng::Csprng;
+
+    use super::*;
+    use crate::{
+        ballot::BallotEncryptionError,
+        contest_selection::ContestSelection,
+        device::Device,
+        example_election::ExampleElection,
+        example_election_parameters::example_election_parameters,
+        tally_decryption::{
+            GuardianTallyDecryptionResponses, GuardianTallyDecryptionShares,
+            TallyDecryptionCeremony,
+        },
+    };
+
+    #[test]
+    fn test_challenged_ballot_decryption() {
+        let mut csprng = Csprng::new(b"test_challenged_ballot_decryption");
+        let example_election = ExampleElection::new(&mut csprng, example_election_parameters());
+        let ExampleElection {
+            guardian_public_keys,
+            pre_voting_data,
+            ..
+        } = &example_election;
+
+        let device = Device::new("Some encryption device", pre_voting_data.clone());
+        let contest_index = ExampleElection::contest_index();
+        let mut selections = example_election.selections(1);
+        let vote = selections[&contest_index].vote.clone();
+        selections
+            .get_mut(&contest_index)
+            .unwrap()
+            .write_ins
+            .push("Zéphyr Quill".to_string());
+        let primary_nonce = [7u8; 32];
+        let mut ballot =
+            BallotEncrypted::new_from_selections(&device, &mut csprng, &primary_nonce, &selections)
+                .unwrap();
+
+        // A write-in too long to be encoded is reported.
+        let too_long = BTreeMap::from([(
+            contest_index,
+            ContestSelection {
+                vote: vote.clone(),
+                write_ins: vec!["x".repeat(1 << 16)],
+            },
+        )]);
+        assert_eq!(
+            BallotEncrypted::new_from_selections(&device, &mut csprng, &primary_nonce, &too_long)
+                .unwrap_err(),
+            BallotEncryptionError::ContestData(contest_index, ContestDataError::TooLarge)
+        );
+
+        // Only challenged ballots are decrypted.
+        assert!(matches!(
+            ChallengedBallotDecryption::from_primary_nonce(
+                pre_voting_data,
+                &ballot,
+                &primary_nonce
+            ),
+            Err(ChallengedBallotError::NotChallenged(_))
+        ));
+        ballot.challenge().unwrap();
+
+        let expected = BTreeMap::from([(contest_index, vote.iter().map(|&v| v as u32).collect())]);
+
+        // The fast path with the revealed primary nonce.
+        let decryption = ChallengedBallotDecryption::from_primary_nonce(
+            pre_voting_data,
+            &ballot,
+            &primary_nonce,
+        )
+        .unwrap();
+        assert_eq!(decryption.contests, expected);
+        let expected_contest_data = BTreeMap::from([(
+            contest_index,
+            ContestData {
+                write_ins: vec!["Zéphyr Quill".to_string()],
+                ..ContestData::default()
+            },
+        )]);
+        assert_eq!(decryption.contest_data, expected_contest_data);
+        decryption
+            .validate_content(&pre_voting_data.manifest)
+            .unwrap();
+        assert!(matches!(
+            ChallengedBallotDecryption::from_primary_nonce(pre_voting_data, &ballot, &[8u8; 32]),
+            Err(ChallengedBallotError::NonceMismatch(_, 1))
+        ));
+
+        // A claimed plaintext that differs from the ballot is rejected.
+        let mut wrong = decryption.clone();
+        wrong.contests.get_mut(&contest_index).unwrap().swap(0, 1);
+        assert!(matches!(
+            wrong.validate(pre_voting_data, &ballot),
+            Err(ChallengedBallotError::NonceMismatch(_, 1))
+        ));
+        let mut wrong = decryption.clone();
+        wrong
+            .contest_data
+            .get_mut(&contest_index)
+            .unwrap()
+            .write_ins[0] = "Zephyr".to_string();
+        assert!(matches!(
+            wrong.validate(pre_voting_data, &ballot),
+            Err(ChallengedBallotError::ContestDataMismatch(_))
+        ));
+
+        // The guardian path with a quorum of k guardians.
+        let k = pre_voting_data
+            .parameters
+            .varying_parameters
+            .k
+            .as_quantity();
+        let key_shares =
+            example_election.key_shares(&mut csprng, &example_election.guardian_secret_keys[..k]);
+
+        let mut run_ceremony = |ciphertexts: EncryptedTally| {
+            let mut ceremony = TallyDecryptionCeremony::new(ciphertexts.clone());
+            let states = key_shares
+                .iter()
+                .map(|key_share| {
+                    let (shares, state) = GuardianTallyDecryptionShares::generate(
+                        &mut csprng,
+                        pre_voting_data,
+                        &ciphertexts,
+                        key_share,
+                    );
+                    ceremony.add_shares(shares).unwrap();
+                    state
+                })
+                .collect::<Vec<_>>();
+            for (state, key_share) in zip(states, &key_shares) {
+                let responses = GuardianTallyDecryptionResponses::generate(
+                    pre_voting_data,
+                    &ceremony,
+                    state,
+                    key_share,
+                )
+                .unwrap();
+                ceremony.add_responses(responses).unwrap();
+            }
+            ceremony
+        };
+        let (decryptions, _) =
+            run_ceremony(ChallengedBallotDecryption::ballot_ciphertexts(&ballot))
+                .finalize(pre_voting_data, guardian_public_keys)
+                .unwrap();
+        let contest_data_decryptions = run_ceremony(
+            ChallengedBallotDecryption::contest_data_ciphertexts(&ballot),
+        )
+        .finalize_contest_data(pre_voting_data, guardian_public_keys)
+        .unwrap();
+
+        let decryption = ChallengedBallotDecryption::from_guardian_decryption(
+            pre_voting_data,
+            &ballot,
+            decryptions,
+            contest_data_decryptions,
+        )
+        .unwrap();
+        assert_eq!(decryption.contests, expected);
+        assert_eq!(decryption.contest_data, expected_contest_data);
+
+        // A tampered ciphertext no longer matches the confirmation code.
+        ballot
+            .contests
+            .get_mut(&contest_index)
+            .unwrap()
+            .selection
+            .swap(0, 1);
+        assert!(matches!(
+            decryption.validate(pre_voting_data, &ballot),
+            Err(ChallengedBallotError::ContestHashMismatch(_))
+        ));
+    }
+}
@@ -0,0 +1,516 @@
+// Copyright (C) Microsoft Corporation. All rights reserved.
+
+#![deny(clippy::unwrap_used)]
+#![deny(clippy::expect_used)]
+#![deny(clippy::panic)]
+#![deny(clippy::manual_assert)]
+
+//! This module provides the encrypted contest data field of a
+//! [`ContestEncrypted`](crate::contest_encrypted::ContestEncrypted), which records overvotes,
+//! undervotes, null votes and write-in text. For more details see Section `3.3.3` of the
+//! Electionguard specification `2.0.0`.
+//!
+//! The contest data `D` is encoded as described in [`ContestData::to_bytes`], padded with zeros to
+//! `b_D` blocks of 32 bytes, and encrypted under the joint election public key `K` as follows.
+//! - The nonce is `ξ = H(H_E; 0x20, ξ_B, ind_c(Λ), "contest data")` (Equation `50`).
+//! - The secret key is `k = H(H_E; 0x22, K, α, β)` with `α = g^ξ` and `β = K^ξ` (Equation `51`).
+//! - The MAC key `k_0` and the encryption keys `k_1, ..., k_{b_D}` are
+//!   `k_i = HMAC(k, b(i,4) ∥ Label ∥ 0x00 ∥ Context ∥ b((b_D + 1) · 256, 4))` with
+//!   `Label = b("data_enc_keys")` and `Context = b("contest_data") ∥ b(ind_c(Λ), 4)`
+//!   (Equation `52`).
+//! - The ciphertext is `C_D = (C_0, C_1, C_2)` with `C_0 = α`,
+//!   `C_1 = b(D) ⊕ (k_1 ∥ ... ∥ k_{b_D})` and `C_2 = HMAC(k_0, C_0 ∥ C_1)` (Equation `53`).
+//!
+//! The ciphertext is part of the contest hash, so it is bound to the confirmation code. The
+//! contest data of a challenged ballot is decrypted either with the primary nonce revealed by
+//! the encryption device or by the guardians, who jointly compute `β = C_0^s` together with a
+//! proof of correct decryption, see [`ContestDataDecryption`].
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use util::{
+    algebra::{Group, GroupElement},
+    bitwise::xor,
+};
+
+use crate::{
+    contest_selection::{ContestSelection, ContestSelectionPlaintext},
+    election_manifest::{Contest, ContestIndex},
+    election_record::PreVotingData,
+    hash::{eg_h, eg_hmac, HValue},
+    joint_election_public_key::Ciphertext,
+    nonce::contest_data as nonce,
+    verifiable_decryption::{
+        CombinedDecryptionShare, DecryptionProof, DecryptionProofValidationError,
+    },
+};
+
+/// The size of a block of the contest data in bytes.
+pub const CONTEST_DATA_BLOCK_BYTES: usize = 32;
+
+/// The minimum number of blocks of the encoded contest data. Shorter contest data is padded to
+/// this length, so that the ciphertexts of typical contest data do not reveal their length.
+pub const CONTEST_DATA_MIN_BLOCKS: usize = 4;
+
+/// The contest data of a contest on a ballot.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ContestData {
+    /// The voter's selections if the contest was overvoted, empty otherwise. An overvoted
+    /// contest is encrypted as if no option were selected.
+    pub overvote: Vec<ContestSelectionPlaintext>,
+    /// The number of votes the voter did not make use of
+    pub undervotes: u32,
+    /// Whether the voter neither selected an option nor wrote in a candidate
+    pub null_vote: bool,
+    /// The write-in text of the voter
+    pub write_ins: Vec<String>,
+}
+
+/// The encrypted contest data `C_D = (C_0, C_1, C_2)` of Equation `53`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ContestDataCiphertext {
+    /// First ciphertext part `C_0`
+    pub c0: GroupElement,
+    /// Second ciphertext part `C_1`, a whole number of blocks
+    pub c1: Vec<u8>,
+    /// Third ciphertext part `C_2`, the MAC
+    pub c2: HValue,
+}
+
+/// The guardians' decryption of a [`ContestDataCiphertext`], i.e., `β = C_0^s` together with a
+/// proof of correct decryption.
+///
+/// The proof is the proof of Section `3.6.3` for the ciphertext returned by
+/// [`ContestDataCiphertext::decryption_ciphertext`]. It can be computed with a
+/// [`TallyDecryptionCeremony`](crate::tally_decryption::TallyDecryptionCeremony), see
+/// [`TallyDecryptionCeremony::finalize_contest_data`](crate::tally_decryption::TallyDecryptionCeremony::finalize_contest_data).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContestDataDecryption {
+    /// The combined decryption share `β = C_0^s`
+    pub beta: GroupElement,
+    /// The proof of correct decryption
+    pub proof: DecryptionProof,
+}
+
+/// Represents errors occurring while encoding, decrypting or decoding contest data.
+#[derive(Error, Debug, PartialEq)]
+pub enum ContestDataError {
+    /// Occurs if the contest data is too large to be encoded.
+    #[error("The contest data is too large to be encoded.")]
+    TooLarge,
+    /// Occurs if the ciphertext is not a whole number of blocks.
+    #[error("The contest data ciphertext is not a whole number of blocks.")]
+    InvalidLength,
+    /// Occurs if the ciphertext was not encrypted with the revealed primary nonce.
+    #[error("The contest data was not encrypted with the revealed nonce.")]
+    NonceMismatch,
+    /// Occurs if the MAC does not verify.
+    #[error("The MAC of the contest data does not verify.")]
+    InvalidMac,
+    /// Occurs if the decrypted contest data cannot be decoded.
+    #[error("The decrypted contest data is malformed.")]
+    Malformed,
+    /// Occurs if the proof of correct decryption is invalid.
+    #[error("The decryption of the contest data is invalid: {0}")]
+    InvalidDecryption(#[from] DecryptionProofValidationError),
+}
+
+impl ContestData {
+    /// Computes the contest data of a voter's selection for a contest.
+    pub fn new(contest: &Contest, selection: &ContestSelection) -> Self {
+        let num_votes: usize = selection.vote.iter().map(|&v| v as usize).sum();
+        if !contest.admits(&selection.vote) {
+            return ContestData {
+                overvote: selection.vote.clone(),
+                undervotes: 0,
+                null_vote: false,
+                write_ins: selection.write_ins.clone(),
+            };
+        }
+        ContestData {
+            overvote: Vec::new(),
+            undervotes: (contest.selection_limit - num_votes) as u32,
+            null_vote: num_votes == 0 && selection.write_ins.is_empty(),
+            write_ins: selection.write_ins.clone(),
+        }
+    }
+
+    /// Returns whether the contest was overvoted.
+    pub fn is_overvote(&self) -> bool {
+        !self.overvote.is_empty()
+    }
+
+    /// Encodes the contest data as
+    ///
+    /// `b(null_vote, 1) ∥ b(undervotes, 4) ∥ b(m, 2) ∥ overvote ∥ b(w, 2) ∥ W_1 ∥ ... ∥ W_w`,
+    ///
+    /// where `m` is the number of entries of `overvote`, each of which takes one byte, and where
+    /// `W_i = b(len_i, 2) ∥ b(write_in_i)` is the UTF-8 encoding of the `i`-th write-in, prefixed
+    /// by its length in bytes. The encoding is padded with zeros to a whole number of at least
+    /// [`CONTEST_DATA_MIN_BLOCKS`] blocks.
+    pub fn to_bytes(&self) -> Result<Vec<u8>, ContestDataError> {
+        fn len_u16(len: usize) -> Result<[u8; 2], ContestDataError> {
+            u16::try_from(len)
+                .map(u16::to_be_bytes)
+                .map_err(|_| ContestDataError::TooLarge)
+        }
+
+        let mut v = vec![self.null_vote as u8];
+        v.extend_from_slice(&self.undervotes.to_be_bytes());
+        v.extend_from_slice(&len_u16(self.overvote.len())?);
+        v.extend_from_slice(&self.overvote);
+        v.extend_from_slice(&len_u16(self.write_ins.len())?);
+        for write_in in &self.write_ins {
+            v.extend_from_slice(&len_u16(write_in.len())?);
+            v.extend_from_slice(write_in.as_bytes());
+        }
+
+        let b_d = v
+            .len()
+            .div_ceil(CONTEST_DATA_BLOCK_BYTES)
+            .max(CONTEST_DATA_MIN_BLOCKS);
+        v.resize(b_d * CONTEST_DATA_BLOCK_BYTES, 0);
+        Ok(v)
+    }
+
+    /// Decodes contest data encoded with [`ContestData::to_bytes`].
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, ContestDataError> {
+        struct Reader<'a>(&'a [u8]);
+        impl<'a> Reader<'a> {
+            fn take(&mut self, n: usize) -> Result<&'a [u8], ContestDataError> {
+                if self.0.len() < n {
+                    return Err(ContestDataError::Malformed);
+                }
+                let (head, tail) = self.0.split_at(n);
+                self.0 = tail;
+                Ok(head)
+            }
+            fn take_u16(&mut self) -> Result<usize, ContestDataError> {
+                let b = self.take(2)?;
+                Ok(u16::from_be_bytes([b[0], b[1]]) as usize)
+            }
+        }
+
+        let mut r = Reader(bytes);
+        let null_vote = match r.take(1)? {
+            [0] => false,
+            [1] => true,
+            _ => return Err(ContestDataError::Malformed),
+        };
+        let b = r.take(4)?;
+        let undervotes = u32::from_be_bytes([b[0], b[1], b[2], b[3]]);
+        let m = r.take_u16()?;
+        let overvote = r.take(m)?.to_vec();
+        let w = r.take_u16()?;
+        let write_ins = (0..w)
+            .map(|_| {
+                let len = r.take_u16()?;
+                String::from_utf8(r.take(len)?.to_vec()).map_err(|_| ContestDataError::Malformed)
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        if r.0.iter().any(|&byte| byte != 0) {
+            return Err(ContestDataError::Malformed);
+        }
+
+        Ok(ContestData {
+            overvote,
+            undervotes,
+            null_vote,
+            write_ins,
+        })
+    }
+}
+
+impl ContestDataCiphertext {
+    /// This function computes the secret key as defined in Equation `51`.
+    fn secret_key(header: &PreVotingData, alpha: &GroupElement, beta: &GroupElement) -> HValue {
+        let group = &header.parameters.fixed_parameters.group;
+        // v = 0x22 | b(K, 512) | b(alpha, 512) | b(beta, 512)
+        let mut v = vec![0x22];
+        v.extend_from_slice(
+            header
+                .public_key
+                .joint_election_public_key
+                .to_be_bytes_left_pad(group)
+                .as_slice(),
+        );
+        v.extend_from_slice(alpha.to_be_bytes_left_pad(group).as_slice());
+        v.extend_from_slice(beta.to_be_bytes_left_pad(group).as_slice());
+        eg_h(&header.hashes_ext.h_e, &v)
+    }
+
+    /// This function computes the MAC key `k_0` and the concatenated encryption keys
+    /// `k_1 ∥ ... ∥ k_{b_D}` as in Equation `52`.
+    ///
+    /// The arguments are
+    /// - `k` - the secret key as in Equation `51`
+    /// - `contest_index` - the index of the contest
+    /// - `b_d` - the number of blocks of the contest data
+    fn mac_and_encryption_keys(
+        k: &HValue,
+        contest_index: ContestIndex,
+        b_d: usize,
+    ) -> (HValue, Vec<u8>) {
+        // label = b("data_enc_keys", 13)
+        let label = b"data_enc_keys";
+        // context = b("contest_data", 12) | b(ind_c, 4)
+        let mut context = b"contest_data".to_vec();
+        context.extend_from_slice(&contest_index.get_one_based_u32().to_be_bytes());
+        let bit_len = ((b_d as u32) + 1) * 256;
+
+        let mut keys = (0..=b_d as u32).map(|i| {
+            // v = b(i, 4) | label | 0x00 | context | b((b_D + 1) * 256, 4)
+            let mut v = i.to_be_bytes().to_vec();
+            v.extend_from_slice(label);
+            v.push(0x00);
+            v.extend_from_slice(&context);
+            v.extend_from_slice(&bit_len.to_be_bytes());
+            eg_hmac(k, &v)
+        });
+        let k0 = keys.next().unwrap_or_default();
+        let keystream = keys.flat_map(|k_i| k_i.0).collect();
+        (k0, keystream)
+    }
+
+    /// This function computes the MAC `C_2` as in Equation `53`.
+    fn mac(header: &PreVotingData, k0: &HValue, c0: &GroupElement, c1: &[u8]) -> HValue {
+        let group = &header.parameters.fixed_parameters.group;
+        let mut v = c0.to_be_bytes_left_pad(group);
+        v.extend_from_slice(c1);
+        eg_hmac(k0, &v)
+    }
+
+    /// Encrypts the contest data of a contest with the nonce derived from the primary nonce.
+    ///
+    /// The arguments are
+    /// - `header` - the pre-voting data
+    /// - `primary_nonce` - the primary nonce `ξ_B` of the ballot
+    /// - `contest_index` - the index of the contest
+    /// - `data` - the contest data
+    pub fn encrypt(
+        header: &PreVotingData,
+        primary_nonce: &[u8],
+        contest_index: ContestIndex,
+        data: &ContestData,
+    ) -> Result<Self, ContestDataError> {
+        let group = &header.parameters.fixed_parameters.group;
+        let bytes = data.to_bytes()?;
+        let b_d = bytes.len() / CONTEST_DATA_BLOCK_BYTES;
+
+        let xi = nonce(header, primary_nonce, contest_index);
+        let alpha = group.g_exp_secret(&xi);
+        let beta = header.public_key.exp_secret(&xi, group);
+
+        let k = Self::secret_key(header, &alpha, &beta);
+        let (k0, keystream) = Self::mac_and_encryption_keys(&k, contest_index, b_d);
+        let c1 = xor(&bytes, &keystream);
+        let c2 = Self::mac(header, &k0, &alpha, &c1);
+
+        Ok(ContestDataCiphertext { c0: alpha, c1, c2 })
+    }
+
+    /// Decrypts the contest data given `β = K^ξ = C_0^s`, after checking the MAC.
+    fn decrypt_with_beta(
+        &self,
+        header: &PreVotingData,
+        contest_index: ContestIndex,
+        beta: &GroupElement,
+    ) -> Result<ContestData, ContestDataError> {
+        if self.c1.is_empty() || !self.c1.len().is_multiple_of(CONTEST_DATA_BLOCK_BYTES) {
+            return Err(ContestDataError::InvalidLength);
+        }
+        let b_d = self.c1.len() / CONTEST_DATA_BLOCK_BYTES;
+
+        let k = Self::secret_key(header, &self.c0, beta);
+        let (k0, keystream) = Self::mac_and_encryption_keys(&k, contest_index, b_d);
+        if Self::mac(header, &k0, &self.c0, &self.c1) != self.c2 {
+            return Err(ContestDataError::InvalidMac);
+        }
+
+        ContestData::from_bytes(&xor(&self.c1, &keystream))
+    }
+
+    /// Decrypts the contest data with the nonce derived from the primary nonce revealed by the
+    /// encryption device.
+    pub fn decrypt_with_nonce(
+        &self,
+        header: &PreVotingData,
+        primary_nonce: &[u8],
+        contest_index: ContestIndex,
+    ) -> Result<ContestData, ContestDataError> {
+        let group = &header.parameters.fixed_parameters.group;
+        let xi = nonce(header, primary_nonce, contest_index);
+        if group.g_exp(&xi) != self.c0 {
+            return Err(ContestDataError::NonceMismatch);
+        }
+        let beta = header.public_key.exp(&xi, group);
+        self.decrypt_with_beta(header, contest_index, &beta)
+    }
+
+    /// Returns the ciphertext `(C_0, 1)` whose combined decryption share is `β = C_0^s`. The
+    /// guardians decrypt the contest data by computing the decryption shares of this ciphertext.
+    pub fn decryption_ciphertext(&self) -> Ciphertext {
+        Ciphertext {
+            alpha: self.c0.clone(),
+            beta: Group::one(),
+        }
+    }
+
+    /// Returns the encoding `b(C_0, 512) ∥ C_1 ∥ C_2` of the ciphertext as part of the contest
+    /// hash.
+    pub fn to_hash_bytes(&self, header: &PreVotingData) -> Vec<u8> {
+        let group = &header.parameters.fixed_parameters.group;
+        let mut v = self.c0.to_be_bytes_left_pad(group);
+        v.extend_from_slice(&self.c1);
+        v.extend_from_slice(self.c2.0.as_slice());
+        v
+    }
+}
+
+impl ContestDataDecryption {
+    /// Creates the decryption from the combined decryption share and proof of the ciphertext
+    /// returned by [`ContestDataCiphertext::decryption_ciphertext`].
+    pub fn new(m: &CombinedDecryptionShare, proof: DecryptionProof) -> Self {
+        ContestDataDecryption {
+            beta: m.0.clone(),
+            proof,
+        }
+    }
+
+    /// Verifies the proof of correct decryption and decrypts the contest data.
+    ///
+    /// The arguments are
+    /// - `header` - the pre-voting data
+    /// - `contest_index` - the index of the contest
+    /// - `ciphertext` - the encrypted contest data
+    pub fn decrypt(
+        &self,
+        header: &PreVotingData,
+        contest_index: ContestIndex,
+        ciphertext: &ContestDataCiphertext,
+    ) -> Result<ContestData, ContestDataError> {
+        self.proof.validate(
+            &header.parameters.fixed_parameters,
+            &header.hashes_ext,
+            &header.public_key,
+            &ciphertext.decryption_ciphertext(),
+            &CombinedDecryptionShare(self.beta.clone()),
+        )?;
+        ciphertext.decrypt_with_beta(header, contest_index, &self.beta)
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod test {
+    use util::csprng::Csprng;
+
+    use super::*;
+    use crate::{
+        example_election_manifest::example_election_manifest,
+        example_election_parameters::example_election_parameters,
+        guardian_secret_key::GuardianSecretKey,
+    };
+
+    #[test]
+    fn test_contest_data_encoding() {
+        let data = ContestData {
+            overvote: vec![1, 1, 0],
+            undervotes: 0,
+            null_vote: false,
+            write_ins: vec!["Zéphyr Quill".to_string(), String::new()],
+        };
+        let bytes = data.to_bytes().unwrap();
+        assert_eq!(
+            bytes.len(),
+            CONTEST_DATA_MIN_BLOCKS * CONTEST_DATA_BLOCK_BYTES
+        );
+        assert_eq!(ContestData::from_bytes(&bytes).unwrap(), data);
+
+        let long = ContestData {
+            write_ins: vec!["x".repeat(200)],
+            ..ContestData::default()
+        };
+        let bytes = long.to_bytes().unwrap();
+        assert_eq!(bytes.len(), 7 * CONTEST_DATA_BLOCK_BYTES);
+        assert_eq!(ContestData::from_bytes(&bytes).unwrap(), long);
+
+        let mut bytes = data.to_bytes().unwrap();
+        *bytes.last_mut().unwrap() = 1;
+        assert_eq!(
+            ContestData::from_bytes(&bytes),
+            Err(ContestDataError::Malformed)
+        );
+    }
+
+    #[test]
+    fn test_contest_data_new() {
+        let manifest = example_election_manifest();
+        let contest = manifest
+            .contests
+            .get(ContestIndex::from_one_based_index(1).unwrap())
+            .unwrap();
+        let selection = |vote: Vec<u8>| ContestSelection {
+            vote,
+            write_ins: Vec::new(),
+        };
+
+        let data = ContestData::new(contest, &selection(vec![0, 0]));
+        assert!(data.null_vote);
+        assert_eq!(data.undervotes, 1);
+        let data = ContestData::new(contest, &selection(vec![1, 0]));
+        assert!(!data.null_vote && !data.is_overvote());
+        assert_eq!(data.undervotes, 0);
+        let data = ContestData::new(contest, &selection(vec![1, 1]));
+        assert_eq!(data.overvote, vec![1, 1]);
+    }
+
+    #[test]
+    fn test_contest_data_encryption() {
+        let mut csprng = Csprng::new(b"test_contest_data_encryption");
+        let election_parameters = example_election_parameters();
+        let guardian_public_keys = election_parameters
+            .varying_parameters
+            .each_guardian_i()
+            .map(|i| {
+                GuardianSecretKey::generate(&mut csprng, &election_parameters, i, None)
+                    .make_public_key()
+            })
+            .collect::<Vec<_>>();
+        let pre_voting_data = PreVotingData::compute(
+            example_election_manifest(),
+            election_parameters,
+            &guardian_public_keys,
+        )
+        .unwrap();
+
+        let contest_index = ContestIndex::from_one_based_index(2).unwrap();
+        let data = ContestData {
+            undervotes: 1,
+            write_ins: vec!["Zéphyr Quill".to_string()],
+            ..ContestData::default()
+        };
+        let primary_nonce = [4u8, 2];
+        let ct =
+            ContestDataCiphertext::encrypt(&pre_voting_data, &primary_nonce, contest_index, &data)
+                .unwrap();
+        assert_eq!(
+            ct.decrypt_with_nonce(&pre_voting_data, &primary_nonce, contest_index)
+                .unwrap(),
+            data
+        );
+
+        // The nonce depends on the contest, and the MAC detects tampering.
+        let other_index = ContestIndex::from_one_based_index(3).unwrap();
+        assert_eq!(
+            ct.decrypt_with_nonce(&pre_voting_data, &primary_nonce, other_index),
+            Err(ContestDataError::NonceMismatch)
+        );
+        let mut tampered = ct.clone();
+        tampered.c1[0] ^= 1;
+        assert_eq!(
+            tampered.decrypt_with_nonce(&pre_voting_data, &primary_nonce, contest_index),
+            Err(ContestDataError::InvalidMac)
+        );
+    }
+}
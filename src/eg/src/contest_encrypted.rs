@@ -6,9 +6,11 @@
 #![deny(clippy::manual_assert)]
 
 use serde::{Deserialize, Serialize};
+use thiserror::Error;
 use util::{algebra::FieldElement, csprng::Csprng};
 
 use crate::{
+    contest_data::{ContestData, ContestDataCiphertext, ContestDataError},
     contest_hash,
     contest_selection::ContestSelection,
     device::Device,
@@ -20,7 +22,7 @@ use crate::{
     joint_election_public_key::{Ciphertext, Nonce},
     nonce::encrypted as nonce,
     vec1::Vec1,
-    zk::ProofRange,
+    zk::{ProofRange, ProofRangeValidationError},
 };
 
 // /// A contest.
@@ -46,6 +48,23 @@ use crate::{
 /// A 1-based index of a [`ContestEncrypted`] in the order it is defined in the [`crate::ballot::BallotEncrypted`].
 pub type ContestEncryptedIndex = Index<ContestEncrypted>;
 
+/// Represents errors occurring while validating a [`ContestEncrypted`].
+#[derive(Error, Debug, PartialEq)]
+pub enum ContestEncryptedValidationError {
+    /// Occurs if the number of ciphertexts does not match the number of options of the contest.
+    #[error("{found} ciphertexts given, but the contest has {expected} options.")]
+    WrongNumberOfCiphertexts { expected: usize, found: usize },
+    /// Occurs if the range proof of an option is missing.
+    #[error("The range proof of option {0} is missing.")]
+    MissingOptionProof(u32),
+    /// Occurs if the range proof of an option is invalid (Verification `5`).
+    #[error("The range proof of option {0} is invalid: {1}")]
+    InvalidOptionProof(u32, ProofRangeValidationError),
+    /// Occurs if the range proof of the selection limit is invalid (Verification `6`).
+    #[error("The range proof of the selection limit is invalid: {0}")]
+    InvalidSelectionLimitProof(ProofRangeValidationError),
+}
+
 /// A contest in an encrypted ballot.
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct ContestEncrypted {
@@ -60,6 +79,11 @@ pub struct ContestEncrypted {
 
     // Proof of satisfying the selection limit.
     pub proof_selection_limit: ProofRange,
+
+    /// Encrypted contest data, i.e., overvotes, undervotes, null votes and write-ins. Absent for
+    /// contests of pre-encrypted ballots.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub contest_data: Option<ContestDataCiphertext>,
 }
 
 /// A scaled version of [`ContestEncrypted`]. This means that each encrypted vote on the contest
@@ -111,6 +135,10 @@ impl ContestEncrypted {
         vote
     }
 
+    /// Encrypts the voter's selections on a contest, including the contest data.
+    ///
+    /// Fails with [`ContestDataError::TooLarge`] if the contest data, e.g. a write-in, is too
+    /// large to be encoded.
     pub fn new(
         device: &Device,
         csprng: &mut Csprng,
@@ -118,45 +146,69 @@ impl ContestEncrypted {
         contest: &Contest,
         contest_index: ContestIndex,
         pt_vote: &ContestSelection,
-    ) -> ContestEncrypted {
+    ) -> Result<ContestEncrypted, ContestDataError> {
+        // An overvoted contest is encrypted as if no option were selected, and the voter's
+        // selections are recorded in the contest data.
+        let data = ContestData::new(contest, pt_vote);
+        let counted_vote;
+        let pt_vote = if data.is_overvote() {
+            counted_vote = ContestSelection {
+                vote: vec![0; contest.options.len()],
+                write_ins: Vec::new(),
+            };
+            &counted_vote
+        } else {
+            pt_vote
+        };
+
         let selection_and_nonce =
             Self::encrypt_selection(&device.header, primary_nonce, contest_index, pt_vote);
         let selection = selection_and_nonce
             .iter()
             .map(|(ct, _)| ct.clone())
             .collect::<Vec<_>>();
-        let contest_hash = contest_hash::contest_hash(&device.header, contest_index, &selection);
+        let contest_data =
+            ContestDataCiphertext::encrypt(&device.header, primary_nonce, contest_index, &data)?;
+        let contest_hash = contest_hash::contest_hash(
+            &device.header,
+            contest_index,
+            &selection,
+            Some(&contest_data),
+        );
 
+        let option_limits = contest.option_vote_limits();
         let mut proof_ballot_correctness = Vec1::new();
         for (i, (sel, nonce)) in selection_and_nonce.iter().enumerate() {
+            let option_limit = option_limits.get(i).copied().unwrap_or(1);
             #[allow(clippy::unwrap_used)] //? TODO: Remove temp development code
             proof_ballot_correctness
-                .try_push(sel.proof_ballot_correctness(
+                .try_push(sel.proof_option_limit(
                     &device.header,
                     csprng,
-                    pt_vote.vote[i] == 1u8,
+                    pt_vote.vote[i] as usize,
                     nonce,
+                    option_limit,
                 ))
                 .unwrap();
         }
 
-        let mut num_selections = 0;
-        pt_vote.vote.iter().for_each(|v| num_selections += v);
+        let num_selections: usize = pt_vote.vote.iter().map(|&v| v as usize).sum();
 
         let proof_selection_limit = ContestEncrypted::proof_selection_limit(
             &device.header,
             csprng,
             &selection_and_nonce,
-            num_selections as usize,
+            num_selections,
             contest.selection_limit,
         );
 
-        ContestEncrypted {
+        Ok(ContestEncrypted {
             selection,
             contest_hash,
             proof_ballot_correctness,
             proof_selection_limit,
-        }
+            contest_data: Some(contest_data),
+        })
     }
 
     pub fn get_proof_ballot_correctness(&self) -> &Vec1<ProofRange> {
@@ -186,18 +238,6 @@ impl ContestEncrypted {
         )
     }
 
-    /// Verify the proof that the selection limit is satisfied.
-    fn verify_selection_limit(&self, header: &PreVotingData, selection_limit: usize) -> bool {
-        let combined_ct =
-            Self::sum_selection_vector(&header.parameters.fixed_parameters, &self.selection);
-        ProofRange::verify(
-            &self.proof_selection_limit,
-            header,
-            &combined_ct,
-            selection_limit,
-        )
-    }
-
     /// Sum up the encrypted votes on a contest and their nonces. The sum of the nonces can be used
     /// to proof properties about the sum of the ciphertexts, e.g. that it satisfies the selection
     /// limit.
@@ -237,22 +277,42 @@ impl ContestEncrypted {
         sum_ct
     }
 
-    /// Verify the proof that each encrypted vote is an encryption of 0 or 1,
-    /// and that the selection limit is satisfied.
-    pub fn verify(&self, header: &PreVotingData, selection_limit: usize) -> bool {
-        for (ct, j) in self.selection.iter().zip(1..) {
-            let Ok(idx) = Index::from_one_based_index(j) else {
-                return false;
-            };
-            let Some(proof) = self.proof_ballot_correctness.get(idx) else {
-                return false;
-            };
-            if !ct.verify_ballot_correctness(header, proof) {
-                return false;
-            }
+    /// Verify the proof that each encrypted vote is an encryption of a vote between 0 and the
+    /// option's vote limit, and that the selection limit of the contest is satisfied.
+    pub fn verify(&self, header: &PreVotingData, contest: &Contest) -> bool {
+        self.validate(header, contest).is_ok()
+    }
+
+    /// Validates the proof that each encrypted vote is an encryption of a vote between 0 and the
+    /// option's vote limit, and that the selection limit of the contest is satisfied. In contrast
+    /// to [`ContestEncrypted::verify`] it reports which check failed.
+    pub fn validate(
+        &self,
+        header: &PreVotingData,
+        contest: &Contest,
+    ) -> Result<(), ContestEncryptedValidationError> {
+        let option_limits = contest.option_vote_limits();
+        if self.selection.len() != option_limits.len() {
+            return Err(ContestEncryptedValidationError::WrongNumberOfCiphertexts {
+                expected: option_limits.len(),
+                found: self.selection.len(),
+            });
+        }
+        for ((ct, j), &option_limit) in self.selection.iter().zip(1..).zip(&option_limits) {
+            let proof = Index::from_one_based_index(j)
+                .ok()
+                .and_then(|idx| self.proof_ballot_correctness.get(idx))
+                .ok_or(ContestEncryptedValidationError::MissingOptionProof(j))?;
+            proof
+                .validate(header, ct, option_limit)
+                .map_err(|e| ContestEncryptedValidationError::InvalidOptionProof(j, e))?;
         }
 
-        self.verify_selection_limit(header, selection_limit)
+        let combined_ct =
+            Self::sum_selection_vector(&header.parameters.fixed_parameters, &self.selection);
+        self.proof_selection_limit
+            .validate(header, &combined_ct, contest.selection_limit)
+            .map_err(ContestEncryptedValidationError::InvalidSelectionLimitProof)
     }
 
     /// Scales all the encrypted votes on the contest by the same factor.
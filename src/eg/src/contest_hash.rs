@@ -6,6 +6,7 @@
 #![deny(clippy::manual_assert)]
 
 use crate::{
+    contest_data::ContestDataCiphertext,
     election_manifest::ContestIndex,
     // contest_selection::ContestSelectionCiphertext,
     election_record::PreVotingData,
@@ -15,16 +16,19 @@ use crate::{
 
 /// Contest hash for encrypted ballots (Equation 58)
 ///
-/// χl = H(H_E;23,Λ_l,K,α_1,β_1,α_2,β_2 ...,α_m,β_m),
+/// χl = H(H_E;23,Λ_l,K,α_1,β_1,α_2,β_2 ...,α_m,β_m,C_0,C_1,C_2),
 ///
+/// where `(C_0, C_1, C_2)` is the encrypted contest data, if any.
 pub fn contest_hash(
     header: &PreVotingData,
     contest_index: ContestIndex,
     vote: &[Ciphertext],
+    contest_data: Option<&ContestDataCiphertext>,
 ) -> HValue {
     let group = &header.parameters.fixed_parameters.group;
 
     // B1 = 0x23 | b(one_based_index, 4) | b(K, 512) | b(alpha_1, 512) | · · · | b(beta_m, 512)
+    //      | b(C_0, 512) | C_1 | C_2
     let mut v = vec![0x23];
     v.extend_from_slice(&contest_index.get_one_based_u32().to_be_bytes());
     v.extend_from_slice(
@@ -38,6 +42,9 @@ pub fn contest_hash(
         v.extend_from_slice(vote_i.alpha.to_be_bytes_left_pad(group).as_slice());
         v.extend_from_slice(vote_i.beta.to_be_bytes_left_pad(group).as_slice());
     });
+    if let Some(contest_data) = contest_data {
+        v.extend_from_slice(contest_data.to_hash_bytes(header).as_slice());
+    }
 
     eg_h(&header.hashes_ext.h_e, &v)
 }
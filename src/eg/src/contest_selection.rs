@@ -41,6 +41,10 @@ pub type ContestSelectionIndex = Index<ContestSelection>;
 pub struct ContestSelection {
     /// Vector used to represent the selection
     pub vote: Vec<ContestSelectionPlaintext>,
+
+    /// The write-in text of the voter, which is recorded in the encrypted contest data
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub write_ins: Vec<String>,
 }
 
 impl HasIndexType for ContestSelection {
@@ -48,27 +52,54 @@ impl HasIndexType for ContestSelection {
 }
 
 impl ContestSelection {
+    /// Picks a random selection for a contest in which every option may be selected at most
+    /// once. See [`ContestSelection::new_pick_random_with_option_limits`].
     pub fn new_pick_random(
         csprng: &mut Csprng,
         selection_limit: usize,
         num_options: usize,
     ) -> Self {
-        let mut vote = vec![0; num_options];
+        Self::new_pick_random_with_option_limits(csprng, selection_limit, &vec![1; num_options])
+    }
+
+    /// Picks a random selection for a contest.
+    ///
+    /// The total count of votes is chosen uniformly from `0` up to the smaller of the
+    /// `selection_limit` and the sum of the `option_limits`. Each vote is then applied to an
+    /// option chosen uniformly among those that have not reached their limit.
+    pub fn new_pick_random_with_option_limits(
+        csprng: &mut Csprng,
+        selection_limit: usize,
+        option_limits: &[ContestSelectionPlaintext],
+    ) -> Self {
+        let mut vote = vec![0; option_limits.len()];
 
-        let selection_limit = csprng.next_u64() as usize % (selection_limit + 1);
-        let mut changed = 0;
+        let max_votes: usize = option_limits.iter().map(|&limit| limit as usize).sum();
+        let cnt_votes = csprng.next_u64_lt(selection_limit.min(max_votes) as u64 + 1);
 
-        while changed < selection_limit {
-            //TODO: a tiny bit of bias in this selection method. Better to put a proper `next_u64_lt()` on csprng.
-            let idx = csprng.next_u64() as usize % vote.len();
+        for _ in 0..cnt_votes {
+            let open_options: Vec<usize> = (0..vote.len())
+                .filter(|&ix| vote[ix] < option_limits[ix])
+                .collect();
+            let ix = open_options[csprng.next_u64_lt(open_options.len() as u64) as usize];
+            vote[ix] += 1;
+        }
 
-            if vote[idx] == 0u8 {
-                vote[idx] = 1u8;
-                changed += 1;
-            }
+        Self {
+            vote,
+            write_ins: Vec::new(),
         }
+    }
 
-        Self { vote }
+    /// Picks a random selection for the given contest, respecting its selection limit and the
+    /// vote limits of its options.
+    pub fn new_pick_random_for_contest(csprng: &mut Csprng, contest: &Contest) -> Self {
+        let option_limits: Vec<ContestSelectionPlaintext> = contest
+            .option_vote_limits()
+            .into_iter()
+            .map(|limit| limit.min(ContestSelectionPlaintext::MAX as usize) as u8)
+            .collect();
+        Self::new_pick_random_with_option_limits(csprng, contest.selection_limit, &option_limits)
     }
 
     // Choices are 1-indexed
@@ -95,12 +126,36 @@ impl Ciphertext {
         selected: bool,
         nonce: &Nonce,
     ) -> ProofRange {
-        ProofRange::new(header, csprng, self, nonce, selected as usize, 1)
+        self.proof_option_limit(header, csprng, selected as usize, nonce, 1)
+    }
+
+    /// Proves that the ciphertext is an encryption of a vote between `0` and the option's vote
+    /// limit.
+    pub fn proof_option_limit(
+        &self,
+        header: &PreVotingData,
+        csprng: &mut Csprng,
+        vote: usize,
+        nonce: &Nonce,
+        option_limit: usize,
+    ) -> ProofRange {
+        ProofRange::new(header, csprng, self, nonce, vote, option_limit)
     }
 
     /// Verify the proof that the cipher text is an encryption of 0 or 1.
     pub fn verify_ballot_correctness(&self, header: &PreVotingData, proof: &ProofRange) -> bool {
-        proof.verify(header, self, 1)
+        self.verify_option_limit(header, proof, 1)
+    }
+
+    /// Verify the proof that the cipher text is an encryption of a vote between `0` and the
+    /// option's vote limit.
+    pub fn verify_option_limit(
+        &self,
+        header: &PreVotingData,
+        proof: &ProofRange,
+        option_limit: usize,
+    ) -> bool {
+        proof.verify(header, self, option_limit)
     }
 }
 
@@ -128,3 +183,41 @@ impl Ciphertext {
 //         }
 //     }
 // }
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_new_pick_random_with_option_limits() {
+        let mut csprng = Csprng::new(b"test_new_pick_random_with_option_limits");
+        let option_limits = [1, 3, 0, 2];
+        let mut seen_totals = [false; 6];
+        for _ in 0..200 {
+            let selection = ContestSelection::new_pick_random_with_option_limits(
+                &mut csprng,
+                5,
+                &option_limits,
+            );
+            assert!(selection
+                .vote
+                .iter()
+                .zip(option_limits)
+                .all(|(&v, limit)| v <= limit));
+            let total: usize = selection.vote.iter().map(|&v| v as usize).sum();
+            seen_totals[total] = true;
+        }
+        assert!(seen_totals.iter().all(|&seen| seen));
+
+        // The selection limit caps the total.
+        for _ in 0..50 {
+            let selection = ContestSelection::new_pick_random_with_option_limits(
+                &mut csprng,
+                2,
+                &option_limits,
+            );
+            assert!(selection.vote.iter().map(|&v| v as usize).sum::<usize>() <= 2);
+        }
+    }
+}
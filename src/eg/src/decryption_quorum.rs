@@ -0,0 +1,384 @@
+// Copyright (C) Microsoft Corporation. All rights reserved.
+
+#![deny(clippy::unwrap_used)]
+#![deny(clippy::expect_used)]
+#![deny(clippy::panic)]
+#![deny(clippy::manual_assert)]
+
+//! This module provides the [`DecryptionQuorum`], i.e., the set of guardians that take part in a
+//! decryption when some of the `n` guardians are missing. For more details see Section `3.6.2` of
+//! the Electionguard specification `2.0.0`.
+//!
+//! A quorum consists of exactly `k` of the present guardians. The decryption shares of the
+//! participating guardians are weighted with the Lagrange coefficients `w_i` of Equation `67` and
+//! multiplied to form the combined decryption share `M` of Equation `68`.
+
+use std::collections::BTreeSet;
+
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use util::algebra::{FieldElement, Group, GroupElement};
+use util::algebra_utils::get_single_coefficient_at_zero;
+
+use crate::{
+    election_parameters::ElectionParameters,
+    fixed_parameters::FixedParameters,
+    guardian::GuardianIndex,
+    verifiable_decryption::{CombinedDecryptionShare, DecryptionShare},
+};
+
+/// Represents errors occurring while selecting or using a [`DecryptionQuorum`].
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum QuorumError {
+    /// Occurs if a guardian index is out of bounds.
+    #[error("Guardian {i} has an index bigger than {n}.")]
+    InvalidGuardian { i: GuardianIndex, n: GuardianIndex },
+    /// Occurs if fewer than `k` guardians are present.
+    #[error(
+        "Only {l} guardians are present, but at least {k} are required. Absent guardians: {absent:?}"
+    )]
+    NotEnoughGuardians {
+        l: usize,
+        k: usize,
+        absent: Vec<GuardianIndex>,
+    },
+    /// Occurs if a guardian is not a participant of the quorum.
+    #[error("Guardian {0} is not a participant of the decryption quorum.")]
+    NotInQuorum(GuardianIndex),
+    /// Occurs if a participant of the quorum is represented more than once.
+    #[error("Guardian {0} is represented more than once.")]
+    DuplicateGuardian(GuardianIndex),
+    /// Occurs if a participant of the quorum is not represented.
+    #[error("The decryption share of guardian {0} is missing.")]
+    MissingGuardian(GuardianIndex),
+    /// Occurs if the quorum itself is malformed, e.g., after deserialization.
+    #[error("The decryption quorum is malformed: {0}")]
+    Malformed(String),
+}
+
+/// The guardians that take part in a decryption.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DecryptionQuorum {
+    /// The `k` participating guardians, in increasing order
+    pub participants: Vec<GuardianIndex>,
+    /// The guardians that were not present, in increasing order
+    pub absent: Vec<GuardianIndex>,
+}
+
+/// A decryption share weighted with the guardian's Lagrange coefficient.
+///
+/// This corresponds to `M_i^{w_i}` in Equation `68`.
+#[derive(Debug, Clone)]
+pub struct WeightedDecryptionShare {
+    /// The guardian's index
+    pub i: GuardianIndex,
+    /// The Lagrange coefficient `w_i`
+    pub w_i: FieldElement,
+    /// The weighted share `M_i^{w_i}`
+    pub m_i_w: GroupElement,
+}
+
+impl DecryptionQuorum {
+    /// Selects a quorum from the guardians that are present.
+    ///
+    /// The quorum consists of the `k` present guardians with the smallest indices. If fewer than
+    /// `k` guardians are present, this fails before any decryption work is done and reports the
+    /// absent guardians.
+    ///
+    /// The arguments are
+    /// - `election_parameters` - the election parameters
+    /// - `present` - the indices of the present guardians, in any order and possibly repeated
+    pub fn select<I>(
+        election_parameters: &ElectionParameters,
+        present: I,
+    ) -> Result<DecryptionQuorum, QuorumError>
+    where
+        I: IntoIterator<Item = GuardianIndex>,
+    {
+        let varying_parameters = &election_parameters.varying_parameters;
+        let k = varying_parameters.k.as_quantity();
+
+        let mut present_set = BTreeSet::new();
+        for i in present {
+            if !varying_parameters.is_valid_guardian_i(i.get_one_based_u32()) {
+                return Err(QuorumError::InvalidGuardian {
+                    i,
+                    n: varying_parameters.n,
+                });
+            }
+            present_set.insert(i);
+        }
+
+        let absent: Vec<GuardianIndex> = varying_parameters
+            .each_guardian_i()
+            .filter(|i| !present_set.contains(i))
+            .collect();
+
+        if present_set.len() < k {
+            return Err(QuorumError::NotEnoughGuardians {
+                l: present_set.len(),
+                k,
+                absent,
+            });
+        }
+
+        Ok(DecryptionQuorum {
+            participants: present_set.into_iter().take(k).collect(),
+            absent,
+        })
+    }
+
+    /// Verifies that the quorum consists of exactly `k` distinct valid guardians in increasing
+    /// order, and that no participant is listed as absent.
+    pub fn validate(&self, election_parameters: &ElectionParameters) -> Result<(), QuorumError> {
+        let varying_parameters = &election_parameters.varying_parameters;
+        let k = varying_parameters.k.as_quantity();
+
+        for &i in self.participants.iter().chain(&self.absent) {
+            if !varying_parameters.is_valid_guardian_i(i.get_one_based_u32()) {
+                return Err(QuorumError::InvalidGuardian {
+                    i,
+                    n: varying_parameters.n,
+                });
+            }
+        }
+        if self.participants.len() != k {
+            return Err(QuorumError::Malformed(format!(
+                "{} participants, but k = {k}",
+                self.participants.len()
+            )));
+        }
+        let increasing = |v: &[GuardianIndex]| v.windows(2).all(|w| w[0] < w[1]);
+        if !increasing(&self.participants) || !increasing(&self.absent) {
+            return Err(QuorumError::Malformed(
+                "guardian indices are not strictly increasing".to_string(),
+            ));
+        }
+        if let Some(&i) = self.absent.iter().find(|i| self.contains(**i)) {
+            return Err(QuorumError::Malformed(format!(
+                "guardian {i} is both participating and absent"
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// Returns true if guardian `i` is a participant of the quorum.
+    pub fn contains(&self, i: GuardianIndex) -> bool {
+        self.participants.binary_search(&i).is_ok()
+    }
+
+    /// Computes the Lagrange coefficient `w_i` of a participant as in Equation `67`.
+    pub fn lagrange_coefficient(
+        &self,
+        fixed_parameters: &FixedParameters,
+        i: GuardianIndex,
+    ) -> Result<FieldElement, QuorumError> {
+        let field = &fixed_parameters.field;
+        if !self.contains(i) {
+            return Err(QuorumError::NotInQuorum(i));
+        }
+
+        let xs: Vec<FieldElement> = self
+            .participants
+            .iter()
+            .map(|l| FieldElement::from(l.get_one_based_u32(), field))
+            .collect();
+        let x_i = FieldElement::from(i.get_one_based_u32(), field);
+
+        get_single_coefficient_at_zero(&xs, &x_i, field)
+            .ok_or_else(|| QuorumError::Malformed("guardian indices are not distinct".to_string()))
+    }
+
+    /// Weights the decryption share of a participant with its Lagrange coefficient.
+    pub fn weighted_share(
+        &self,
+        fixed_parameters: &FixedParameters,
+        share: &DecryptionShare,
+    ) -> Result<WeightedDecryptionShare, QuorumError> {
+        let w_i = self.lagrange_coefficient(fixed_parameters, share.i)?;
+        let m_i_w = share.m_i.exp(&w_i, &fixed_parameters.group);
+        Ok(WeightedDecryptionShare {
+            i: share.i,
+            w_i,
+            m_i_w,
+        })
+    }
+
+    /// Combines the decryption shares of exactly the participants into a
+    /// [`CombinedDecryptionShare`] as in Equation `68`.
+    ///
+    /// Fails if a share is from a guardian outside the quorum, or if a participant's share is
+    /// missing or given more than once.
+    pub fn combine<'a, I>(
+        &self,
+        fixed_parameters: &FixedParameters,
+        decryption_shares: I,
+    ) -> Result<CombinedDecryptionShare, QuorumError>
+    where
+        I: IntoIterator<Item = &'a DecryptionShare>,
+    {
+        let group = &fixed_parameters.group;
+
+        let mut seen = BTreeSet::new();
+        let mut m = Group::one();
+        for share in decryption_shares {
+            let weighted = self.weighted_share(fixed_parameters, share)?;
+            if !seen.insert(share.i) {
+                return Err(QuorumError::DuplicateGuardian(share.i));
+            }
+            m = m.mul(&weighted.m_i_w, group);
+        }
+        if let Some(&i) = self.participants.iter().find(|i| !seen.contains(*i)) {
+            return Err(QuorumError::MissingGuardian(i));
+        }
+
+        Ok(CombinedDecryptionShare(m))
+    }
+
+    /// Reads a `DecryptionQuorum` from a `std::io::Read` and validates it.
+    pub fn from_stdioread_validated(
+        stdioread: &mut dyn std::io::Read,
+        election_parameters: &ElectionParameters,
+    ) -> anyhow::Result<Self> {
+        let self_: Self = serde_json::from_reader(stdioread).context("Reading DecryptionQuorum")?;
+
+        self_.validate(election_parameters)?;
+
+        Ok(self_)
+    }
+
+    /// Writes a `DecryptionQuorum` to a `std::io::Write`.
+    pub fn to_stdiowrite(&self, stdiowrite: &mut dyn std::io::Write) -> anyhow::Result<()> {
+        let mut ser = serde_json::Serializer::pretty(stdiowrite);
+
+        self.serialize(&mut ser)
+            .map_err(Into::<anyhow::Error>::into)
+            .and_then(|_| ser.into_inner().write_all(b"\n").map_err(Into::into))
+            .context("Writing DecryptionQuorum")
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod test {
+    use util::csprng::Csprng;
+
+    use super::*;
+    use crate::{
+        example_election_parameters::example_election_parameters,
+        guardian_secret_key::GuardianSecretKey, guardian_share::GuardianEncryptedShare,
+        guardian_share::GuardianSecretKeyShare,
+    };
+
+    fn g(i: u32) -> GuardianIndex {
+        GuardianIndex::from_one_based_index(i).unwrap()
+    }
+
+    #[test]
+    fn test_quorum_selection() {
+        let election_parameters = example_election_parameters();
+        let n = election_parameters.varying_parameters.n.get_one_based_u32();
+        let k = election_parameters.varying_parameters.k.as_quantity();
+
+        // All but the first guardian are present.
+        let quorum = DecryptionQuorum::select(&election_parameters, (2..=n).rev().map(g)).unwrap();
+        quorum.validate(&election_parameters).unwrap();
+        assert_eq!(
+            quorum.participants,
+            (2..2 + k as u32).map(g).collect::<Vec<_>>()
+        );
+        assert_eq!(quorum.absent, vec![g(1)]);
+
+        // Too few guardians are reported up front, with the absent ones.
+        let present = (1..k as u32).map(g).collect::<Vec<_>>();
+        assert_eq!(
+            DecryptionQuorum::select(&election_parameters, present),
+            Err(QuorumError::NotEnoughGuardians {
+                l: k - 1,
+                k,
+                absent: (k as u32..=n).map(g).collect(),
+            })
+        );
+
+        assert!(matches!(
+            DecryptionQuorum::select(&election_parameters, [g(n + 1)]),
+            Err(QuorumError::InvalidGuardian { .. })
+        ));
+    }
+
+    #[test]
+    fn test_quorum_combine() {
+        let mut csprng = Csprng::new(b"test_quorum_combine");
+        let election_parameters = example_election_parameters();
+        let fixed_parameters = &election_parameters.fixed_parameters;
+        let n = election_parameters.varying_parameters.n.get_one_based_u32();
+
+        let guardian_secret_keys = election_parameters
+            .varying_parameters
+            .each_guardian_i()
+            .map(|i| GuardianSecretKey::generate(&mut csprng, &election_parameters, i, None))
+            .collect::<Vec<_>>();
+        let guardian_public_keys = guardian_secret_keys
+            .iter()
+            .map(|sk| sk.make_public_key())
+            .collect::<Vec<_>>();
+
+        // The last guardian is missing.
+        let quorum = DecryptionQuorum::select(&election_parameters, (1..n).map(g)).unwrap();
+        let key_shares = quorum
+            .participants
+            .iter()
+            .map(|i| {
+                let sk = &guardian_secret_keys[i.get_zero_based_usize()];
+                let shares = guardian_secret_keys
+                    .iter()
+                    .map(|dealer_sk| {
+                        GuardianEncryptedShare::encrypt(
+                            &mut csprng,
+                            &election_parameters,
+                            dealer_sk,
+                            &sk.make_public_key(),
+                        )
+                        .ciphertext
+                    })
+                    .collect::<Vec<_>>();
+                GuardianSecretKeyShare::compute(
+                    &election_parameters,
+                    &guardian_public_keys,
+                    &shares,
+                    sk,
+                )
+                .unwrap()
+            })
+            .collect::<Vec<_>>();
+
+        let joint_key = crate::joint_election_public_key::JointElectionPublicKey::compute(
+            &election_parameters,
+            &guardian_public_keys,
+        )
+        .unwrap();
+        let nonce = fixed_parameters.field.random_field_elem(&mut csprng);
+        let ct = joint_key.encrypt_with(fixed_parameters, &nonce, 7);
+
+        let shares = key_shares
+            .iter()
+            .map(|ks| DecryptionShare::from(fixed_parameters, ks, &ct))
+            .collect::<Vec<_>>();
+
+        let m = quorum.combine(fixed_parameters, &shares).unwrap();
+        let m_expected = CombinedDecryptionShare::combine(&election_parameters, &shares).unwrap();
+        assert_eq!(m.0, m_expected.0);
+
+        assert_eq!(
+            quorum.combine(fixed_parameters, &shares[1..]).unwrap_err(),
+            QuorumError::MissingGuardian(quorum.participants[0])
+        );
+        let duplicated = [&shares[..], &shares[..1]].concat();
+        assert_eq!(
+            quorum.combine(fixed_parameters, &duplicated).unwrap_err(),
+            QuorumError::DuplicateGuardian(quorum.participants[0])
+        );
+    }
+}
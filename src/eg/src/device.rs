@@ -5,7 +5,27 @@
 #![deny(clippy::panic)]
 #![deny(clippy::manual_assert)]
 
-use crate::election_record::PreVotingData;
+//! This module provides the encryption device. A [`Device`] is the minimal context needed to
+//! encrypt ballots. An [`EncryptionDevice`] additionally identifies the device by its
+//! [`DeviceInformation`], assigns ballot IDs and keeps the device's [`BallotChain`]. For more
+//! details see Section `3.4.4` of the Electionguard specification `2.0.0`.
+
+use std::collections::BTreeMap;
+
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use util::csprng::Csprng;
+
+use crate::{
+    ballot::{BallotEncrypted, BallotEncryptionError},
+    ballot_chain::{BallotChain, BallotChainClosing, BallotChainError},
+    contest_selection::ContestSelection,
+    election_manifest::ContestIndex,
+    election_record::PreVotingData,
+    hash::{eg_h, HValue},
+    varying_parameters::BallotChaining,
+};
 
 pub struct Device {
     /// Unique identifier of the device
@@ -27,3 +47,261 @@ impl Device {
         &self.uuid
     }
 }
+
+/// The information identifying an encryption device and its session.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DeviceInformation {
+    /// The identifier of the device
+    pub device_id: u64,
+    /// The identifier of the session, e.g., one per day of voting
+    pub session_id: u64,
+    /// The launch code the device was started with
+    pub launch_code: u64,
+    /// The location of the device, e.g., the precinct
+    pub location: String,
+}
+
+impl DeviceInformation {
+    /// Computes the device information hash `H_DI`.
+    ///
+    /// H_DI = H(H_E; 2A, b(device ID, 8), b(session ID, 8), b(launch code, 8), len(location), location)
+    pub fn hash(&self, header: &PreVotingData) -> HValue {
+        let mut v = vec![0x2A];
+        v.extend_from_slice(&self.device_id.to_be_bytes());
+        v.extend_from_slice(&self.session_id.to_be_bytes());
+        v.extend_from_slice(&self.launch_code.to_be_bytes());
+        v.extend_from_slice(&(self.location.len() as u64).to_be_bytes());
+        v.extend_from_slice(self.location.as_bytes());
+        eg_h(&header.hashes_ext.h_e, &v)
+    }
+
+    /// The identifier under which ballots of this device and session are recorded, i.e., the
+    /// device information hash.
+    pub fn device_label(&self, header: &PreVotingData) -> String {
+        self.hash(header).to_string()
+    }
+}
+
+/// Represents errors occurring while operating an [`EncryptionDevice`].
+#[derive(Error, Debug, PartialEq)]
+pub enum EncryptionDeviceError {
+    /// Occurs if the persisted state belongs to a different device or session.
+    #[error("The persisted state belongs to device {0:?}, not to this device.")]
+    StateMismatch(String),
+    /// Occurs if the ballot IDs of the device are exhausted.
+    #[error("The ballot IDs of the device are exhausted.")]
+    BallotIdsExhausted,
+    /// Occurs if the ballot chain could not be created.
+    #[error(transparent)]
+    BallotChain(#[from] BallotChainError),
+    /// Occurs if the ballot could not be encrypted or the ballot chain could not be extended.
+    #[error(transparent)]
+    BallotEncryption(#[from] BallotEncryptionError),
+}
+
+/// The state of an [`EncryptionDevice`] that must be persisted between ballots, so that a
+/// restarted device continues its sequence of ballot IDs and its ballot chain.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EncryptionDeviceState {
+    /// The information identifying the device and its session
+    pub info: DeviceInformation,
+    /// The ID of the next ballot
+    pub next_ballot_id: u64,
+    /// The ballot chain, if ballots are chained
+    pub chain: Option<BallotChain>,
+}
+
+/// An encryption device, identified by its [`DeviceInformation`].
+///
+/// Every ballot encrypted by the device is recorded under the device information hash and gets
+/// the next ballot ID. Unless ballot chaining is prohibited, the ballots are chained.
+pub struct EncryptionDevice {
+    device: Device,
+    state: EncryptionDeviceState,
+}
+
+impl EncryptionDeviceState {
+    /// Reads an `EncryptionDeviceState` from a `std::io::Read`.
+    pub fn from_stdioread(stdioread: &mut dyn std::io::Read) -> anyhow::Result<Self> {
+        serde_json::from_reader(stdioread).context("Reading EncryptionDeviceState")
+    }
+
+    /// Writes an `EncryptionDeviceState` to a `std::io::Write`.
+    pub fn to_stdiowrite(&self, stdiowrite: &mut dyn std::io::Write) -> anyhow::Result<()> {
+        let mut ser = serde_json::Serializer::pretty(stdiowrite);
+
+        self.serialize(&mut ser)
+            .map_err(Into::<anyhow::Error>::into)
+            .and_then(|_| ser.into_inner().write_all(b"\n").map_err(Into::into))
+            .context("Writing EncryptionDeviceState")
+    }
+}
+
+impl EncryptionDevice {
+    /// Launches a new device session. Ballot IDs start at `1`.
+    pub fn new(
+        header: PreVotingData,
+        info: DeviceInformation,
+    ) -> Result<Self, EncryptionDeviceError> {
+        let label = info.device_label(&header);
+        let chain = match header.parameters.varying_parameters.ballot_chaining {
+            BallotChaining::Prohibited => None,
+            BallotChaining::Allowed | BallotChaining::Required => {
+                Some(BallotChain::new(&header, &label)?)
+            }
+        };
+
+        Ok(EncryptionDevice {
+            device: Device::new(&label, header),
+            state: EncryptionDeviceState {
+                info,
+                next_ballot_id: 1,
+                chain,
+            },
+        })
+    }
+
+    /// Restarts a device from its persisted state.
+    ///
+    /// Fails if the ballot chain in the state does not belong to the device.
+    pub fn resume(
+        header: PreVotingData,
+        state: EncryptionDeviceState,
+    ) -> Result<Self, EncryptionDeviceError> {
+        let label = state.info.device_label(&header);
+        if let Some(chain) = &state.chain {
+            if chain.device != label {
+                return Err(EncryptionDeviceError::StateMismatch(chain.device.clone()));
+            }
+        }
+
+        Ok(EncryptionDevice {
+            device: Device::new(&label, header),
+            state,
+        })
+    }
+
+    /// The device context for encryption.
+    pub fn device(&self) -> &Device {
+        &self.device
+    }
+
+    /// The state to be persisted.
+    pub fn state(&self) -> &EncryptionDeviceState {
+        &self.state
+    }
+
+    /// The device information hash `H_DI`.
+    pub fn device_info_hash(&self) -> HValue {
+        self.state.info.hash(&self.device.header)
+    }
+
+    /// Encrypts a ballot, assigning it the next ballot ID and appending it to the ballot chain.
+    pub fn encrypt_ballot(
+        &mut self,
+        csprng: &mut Csprng,
+        primary_nonce: &[u8],
+        selections: &BTreeMap<ContestIndex, ContestSelection>,
+    ) -> Result<BallotEncrypted, EncryptionDeviceError> {
+        let ballot_id = self.state.next_ballot_id;
+        let next_ballot_id = ballot_id
+            .checked_add(1)
+            .ok_or(EncryptionDeviceError::BallotIdsExhausted)?;
+
+        let mut ballot = match &mut self.state.chain {
+            Some(chain) => BallotEncrypted::new_from_selections_chained(
+                &self.device,
+                csprng,
+                primary_nonce,
+                selections,
+                chain,
+            )?,
+            None => BallotEncrypted::new_from_selections(
+                &self.device,
+                csprng,
+                primary_nonce,
+                selections,
+            )?,
+        };
+        ballot.ballot_id = Some(ballot_id);
+
+        self.state.next_ballot_id = next_ballot_id;
+        Ok(ballot)
+    }
+
+    /// Closes the ballot chain of the device, if ballots are chained.
+    pub fn close(&mut self) -> Option<BallotChainClosing> {
+        let header = &self.device.header;
+        self.state.chain.as_mut().map(|chain| chain.close(header))
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod test {
+    use super::*;
+    use crate::{
+        ballot_chain::verify_ballot_chain, example_election::ExampleElection,
+        example_election_parameters::example_election_parameters,
+    };
+
+    #[test]
+    fn test_encryption_device_resume() {
+        let mut csprng = Csprng::new(b"test_encryption_device_resume");
+        let mut election_parameters = example_election_parameters();
+        election_parameters.varying_parameters.ballot_chaining = BallotChaining::Required;
+
+        let example_election = ExampleElection::new(&mut csprng, election_parameters);
+        let pre_voting_data = &example_election.pre_voting_data;
+
+        let info = DeviceInformation {
+            device_id: 7,
+            session_id: 1,
+            launch_code: 1234,
+            location: "Precinct 12".to_string(),
+        };
+        let selections = example_election.selections(0);
+
+        let mut device = EncryptionDevice::new(pre_voting_data.clone(), info.clone()).unwrap();
+        let mut ballots = vec![
+            device
+                .encrypt_ballot(&mut csprng, &[1], &selections)
+                .unwrap(),
+            device
+                .encrypt_ballot(&mut csprng, &[2], &selections)
+                .unwrap(),
+        ];
+
+        // Persist the state and restart the device.
+        let mut buf = Vec::new();
+        device.state().to_stdiowrite(&mut buf).unwrap();
+        let state = EncryptionDeviceState::from_stdioread(&mut buf.as_slice()).unwrap();
+        let mut device = EncryptionDevice::resume(pre_voting_data.clone(), state).unwrap();
+        ballots.push(
+            device
+                .encrypt_ballot(&mut csprng, &[3], &selections)
+                .unwrap(),
+        );
+
+        let ids: Vec<_> = ballots.iter().map(|b| b.ballot_id).collect();
+        assert_eq!(ids, vec![Some(1), Some(2), Some(3)]);
+        let label = device.device_info_hash().to_string();
+        assert!(ballots.iter().all(|b| b.device == label));
+
+        let closing = device.close().unwrap();
+        verify_ballot_chain(pre_voting_data, &label, &ballots, Some(&closing)).unwrap();
+
+        // A different session is a different device.
+        let other_info = DeviceInformation {
+            session_id: 2,
+            ..info
+        };
+        assert_ne!(other_info.hash(pre_voting_data), device.device_info_hash());
+        let mut state = device.state().clone();
+        state.info = other_info;
+        assert!(matches!(
+            EncryptionDevice::resume(pre_voting_data.clone(), state),
+            Err(EncryptionDeviceError::StateMismatch(_))
+        ));
+    }
+}
@@ -5,14 +5,19 @@
 #![deny(clippy::panic)]
 #![deny(clippy::manual_assert)]
 
+use std::collections::BTreeSet;
 use std::io::Cursor;
+use std::num::NonZeroU32;
 
-use anyhow::{Context, Result};
+use anyhow::{bail, ensure, Context, Result};
 use serde::{Deserialize, Serialize};
 
 use crate::ballot_style::BallotStyle;
+use crate::contest_selection::ContestSelectionPlaintext;
 use crate::index::Index;
+use crate::label::{normalize_label, validate_label_collection, LabelIssue, LabelPolicy};
 use crate::vec1::{HasIndexTypeMarker, Vec1};
+use crate::vendor_data::VendorData;
 
 /// The election manifest.
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -23,13 +28,12 @@ pub struct ElectionManifest {
     /// All the [`Contest`]s in the election.
     pub contests: Vec1<Contest>,
 
-    // / Additional data pertaining to the election.
-    // / This is opaque to ElectionGuard.
-    //pub additional_data: Vec<u8>,
-    //? TODO
-    //
     /// All the [`BallotStyle`]s of the election.
     pub ballot_styles: Vec1<BallotStyle>,
+
+    /// Additional data pertaining to the election, opaque to ElectionGuard.
+    #[serde(default, skip_serializing_if = "VendorData::is_empty")]
+    pub vendor_data: VendorData,
 }
 
 impl ElectionManifest {
@@ -43,10 +47,157 @@ impl ElectionManifest {
         Ok(self_)
     }
 
+    /// Reads an [`ElectionManifest`] from a [`std::io::Read`], validates it and checks its
+    /// labels against the [label policy](crate::label).
+    ///
+    /// With [`LabelPolicy::Strict`] any violation of the label policy is an error. With
+    /// [`LabelPolicy::WarnOnly`] the violations are returned for the caller to report.
+    pub fn from_stdioread_validated_with_label_policy(
+        stdioread: &mut dyn std::io::Read,
+        label_policy: LabelPolicy,
+    ) -> Result<(Self, Vec<LabelIssue>)> {
+        let self_ = Self::from_stdioread_validated(stdioread)?;
+
+        let label_issues = self_.validate_labels(label_policy)?;
+
+        Ok((self_, label_issues))
+    }
+
+    /// Checks the labels of the contests, contest options and ballot styles against the
+    /// [label policy](crate::label).
+    ///
+    /// With [`LabelPolicy::Strict`] any violation is an error, otherwise the violations are
+    /// returned.
+    pub fn validate_labels(&self, label_policy: LabelPolicy) -> Result<Vec<LabelIssue>> {
+        let label_issues = self.label_issues();
+
+        if label_policy == LabelPolicy::Strict && !label_issues.is_empty() {
+            let issues: Vec<String> = label_issues.iter().map(ToString::to_string).collect();
+            bail!(
+                "The election manifest violates the label policy:\n{}",
+                issues.join("\n")
+            );
+        }
+
+        Ok(label_issues)
+    }
+
+    /// Returns every violation of the [label policy](crate::label) by the labels of the
+    /// contests, contest options and ballot styles.
+    pub fn label_issues(&self) -> Vec<LabelIssue> {
+        let contests: Vec<_> = self
+            .contests
+            .indices()
+            .filter_map(|contest_index| self.contests.get(contest_index))
+            .collect();
+        let mut label_issues = validate_label_collection(
+            contests.iter().map(|contest| contest.label.as_str()),
+            |ix| format!("contest {}", ix + 1),
+        );
+
+        for (contest_ix, contest) in contests.iter().enumerate() {
+            let options = contest
+                .options
+                .indices()
+                .filter_map(|option_index| contest.options.get(option_index));
+            label_issues.extend(validate_label_collection(
+                options.map(|option| option.label.as_str()),
+                |ix| format!("contest {}, option {}", contest_ix + 1, ix + 1),
+            ));
+        }
+
+        let ballot_styles = self
+            .ballot_styles
+            .indices()
+            .filter_map(|ballot_style_index| self.ballot_styles.get(ballot_style_index));
+        label_issues.extend(validate_label_collection(
+            ballot_styles.map(|ballot_style| ballot_style.label.as_str()),
+            |ix| format!("ballot style {}", ix + 1),
+        ));
+
+        label_issues
+    }
+
+    /// Normalizes the labels of the contests, contest options and ballot styles to Unicode
+    /// Normalization Form C.
+    pub fn normalize_labels(&mut self) {
+        for contest_index in self.contests.indices() {
+            let Some(contest) = self.contests.get_mut(contest_index) else {
+                continue;
+            };
+            contest.label = normalize_label(&contest.label);
+            for option_index in contest.options.indices() {
+                if let Some(option) = contest.options.get_mut(option_index) {
+                    option.label = normalize_label(&option.label);
+                }
+            }
+        }
+        for ballot_style_index in self.ballot_styles.indices() {
+            if let Some(ballot_style) = self.ballot_styles.get_mut(ballot_style_index) {
+                ballot_style.label = normalize_label(&ballot_style.label);
+            }
+        }
+    }
+
     /// Validates that the [`ElectionManifest`] is well-formed.
     /// Useful after deserialization.
+    ///
+    /// This does *not* check the labels against the [label policy](crate::label), see
+    /// [`ElectionManifest::validate_labels`] for that.
+    ///
+    /// This checks that
+    /// - the labels of the contests, of the options of each contest, and of the ballot styles
+    ///   are unique,
+    /// - every contest has a selection limit of at least `1` and at most the total of its option
+    ///   vote limits, i.e., its count of options unless options allow more than one vote,
+    /// - every contest of a ballot style exists, and
+    /// - every contest appears on at least one ballot style.
     pub fn validate(&self) -> Result<()> {
-        // We currently have no validation rules for this type.
+        let mut contest_labels = BTreeSet::new();
+        for contest_index in self.contests.indices() {
+            let Some(contest) = self.contests.get(contest_index) else {
+                bail!("Contest {contest_index} is missing");
+            };
+            ensure!(
+                contest_labels.insert(contest.label.as_str()),
+                "The label {:?} of contest {contest_index} is not unique",
+                contest.label
+            );
+            contest
+                .validate()
+                .with_context(|| format!("Invalid contest {contest_index}"))?;
+        }
+
+        let mut ballot_style_labels = BTreeSet::new();
+        let mut referenced_contests = BTreeSet::new();
+        for ballot_style_index in self.ballot_styles.indices() {
+            let Some(ballot_style) = self.ballot_styles.get(ballot_style_index) else {
+                bail!("Ballot style {ballot_style_index} is missing");
+            };
+            ensure!(
+                ballot_style_labels.insert(ballot_style.label.as_str()),
+                "The label {:?} of ballot style {ballot_style_index} is not unique",
+                ballot_style.label
+            );
+            for &contest_index in &ballot_style.contests {
+                ensure!(
+                    self.contests.get(contest_index).is_some(),
+                    "Ballot style {ballot_style_index} refers to contest {contest_index}, which does not exist"
+                );
+                referenced_contests.insert(contest_index);
+            }
+        }
+
+        let unreferenced_contests: Vec<_> = self
+            .contests
+            .indices()
+            .filter(|contest_index| !referenced_contests.contains(contest_index))
+            .collect();
+        ensure!(
+            unreferenced_contests.is_empty(),
+            "The contests {unreferenced_contests:?} do not appear on any ballot style"
+        );
+
         Ok(())
     }
 
@@ -88,6 +239,76 @@ pub struct Contest {
     /// The candidates/options.
     /// The order of options matches the virtual ballot.
     pub options: Vec1<ContestOption>,
+
+    /// Additional data pertaining to this `Contest`, opaque to ElectionGuard.
+    #[serde(default, skip_serializing_if = "VendorData::is_empty")]
+    pub vendor_data: VendorData,
+}
+
+impl Contest {
+    /// The effective vote limit of every option, in order, see
+    /// [`ContestOption::effective_vote_limit`].
+    pub fn option_vote_limits(&self) -> Vec<usize> {
+        self.options
+            .indices()
+            .filter_map(|option_index| self.options.get(option_index))
+            .map(|option| option.effective_vote_limit(self.selection_limit))
+            .collect()
+    }
+
+    /// Returns whether a voter may cast the given votes, i.e., one vote per option that does not
+    /// exceed the option's vote limit, and no more votes in total than the selection limit.
+    /// Otherwise, the contest is overvoted.
+    pub fn admits(&self, votes: &[ContestSelectionPlaintext]) -> bool {
+        let option_limits = self.option_vote_limits();
+        votes.len() == option_limits.len()
+            && votes
+                .iter()
+                .zip(&option_limits)
+                .all(|(&vote, &limit)| vote as usize <= limit)
+            && votes.iter().map(|&vote| vote as usize).sum::<usize>() <= self.selection_limit
+    }
+
+    /// Validates that the [`Contest`] is well-formed, i.e., that the labels of its options are
+    /// unique, that its selection limit is at least `1` and at most the total of the options'
+    /// vote limits (with the traditional limit of `1`, the count of options), and that no
+    /// effective option vote limit exceeds the largest possible vote.
+    pub fn validate(&self) -> Result<()> {
+        ensure!(
+            self.selection_limit != 0,
+            "The selection limit must be at least 1"
+        );
+
+        let mut option_labels = BTreeSet::new();
+        let mut total_vote_limit = 0u64;
+        for option_index in self.options.indices() {
+            let Some(option) = self.options.get(option_index) else {
+                bail!("Option {option_index} is missing");
+            };
+            ensure!(
+                option_labels.insert(option.label.as_str()),
+                "The label {:?} of option {option_index} is not unique",
+                option.label
+            );
+            ensure!(
+                option.effective_vote_limit(self.selection_limit)
+                    <= ContestSelectionPlaintext::MAX as usize,
+                "The vote limit of option {option_index} exceeds {}",
+                ContestSelectionPlaintext::MAX
+            );
+            total_vote_limit += option
+                .opt_vote_limit
+                .map_or(1, |limit| u64::from(limit.get()));
+        }
+
+        ensure!(
+            self.selection_limit as u64 <= total_vote_limit,
+            "The selection limit {} exceeds the total of the option vote limits {total_vote_limit}",
+            self.selection_limit
+        );
+
+        Ok(())
+    }
 }
 
 impl HasIndexTypeMarker for Contest {}
@@ -100,18 +321,29 @@ pub type ContestIndex = Index<Contest>;
 pub struct ContestOption {
     /// The label for this `ContestOption`.
     pub label: String,
-    /*
+
     /// The maximum count of votes that a voter can apply to this option.
-    /// In the traditional election style, will use `Some(1)` to indicate that a voter may select the option 0 or 1 times.
-    /// `None` indicates that there is no limit.
+    /// `None` indicates the traditional election style, in which a voter may select the option
+    /// 0 or 1 times. E.g., `Some(3)` allows cumulative voting with up to 3 votes on this option.
     /// In all cases, the [`Contest::selection_limit`] will still apply.
     ///
-    #[serde(
-        rename = "",
-        skip_serializing_if = "Option::is_none"
-    )]
+    /// The limit is part of the canonical manifest and therefore of the manifest hash `H_M`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub opt_vote_limit: Option<NonZeroU32>,
-     */
+
+    /// Additional data pertaining to this `ContestOption`, e.g., a party affiliation, opaque to
+    /// ElectionGuard.
+    #[serde(default, skip_serializing_if = "VendorData::is_empty")]
+    pub vendor_data: VendorData,
+}
+
+impl ContestOption {
+    /// The effective vote limit of this option in a contest with the given selection limit,
+    /// i.e., the smaller of the option's vote limit and the selection limit.
+    pub fn effective_vote_limit(&self, selection_limit: usize) -> usize {
+        let opt_vote_limit = self.opt_vote_limit.map_or(1, |limit| limit.get() as usize);
+        opt_vote_limit.min(selection_limit)
+    }
 }
 
 impl HasIndexTypeMarker for ContestOption {}
@@ -125,7 +357,10 @@ pub type ContestOptionIndex = Index<ContestOption>;
 #[allow(clippy::unwrap_used)]
 pub mod test {
     use super::*;
-    use crate::example_election_manifest::example_election_manifest;
+    use crate::{
+        ballot_style::BallotStyleIndex, example_election_manifest::example_election_manifest,
+        example_election_parameters::example_election_parameters, hashes::Hashes,
+    };
 
     #[test]
     fn test_election_manifest() -> Result<()> {
@@ -156,4 +391,121 @@ pub mod test {
 
         Ok(())
     }
+
+    #[test]
+    fn test_election_manifest_validate() {
+        let election_manifest = example_election_manifest();
+        election_manifest.validate().unwrap();
+
+        let contest_index = ContestIndex::from_one_based_index(1).unwrap();
+        let option_index = ContestOptionIndex::from_one_based_index(2).unwrap();
+
+        let mut manifest = election_manifest.clone();
+        let contest = manifest.contests.get_mut(contest_index).unwrap();
+        contest.options.get_mut(option_index).unwrap().label = contest
+            .options
+            .get(Index::from_one_based_index(1).unwrap())
+            .unwrap()
+            .label
+            .clone();
+        assert!(manifest.validate().is_err());
+
+        let mut manifest = election_manifest.clone();
+        manifest.contests.get_mut(contest_index).unwrap().label = manifest
+            .contests
+            .get(ContestIndex::from_one_based_index(2).unwrap())
+            .unwrap()
+            .label
+            .clone();
+        assert!(manifest.validate().is_err());
+
+        for selection_limit in [0, 100] {
+            let mut manifest = election_manifest.clone();
+            manifest
+                .contests
+                .get_mut(contest_index)
+                .unwrap()
+                .selection_limit = selection_limit;
+            assert!(manifest.validate().is_err());
+        }
+
+        let mut manifest = election_manifest.clone();
+        let ballot_style_index = BallotStyleIndex::from_one_based_index(1).unwrap();
+        manifest
+            .ballot_styles
+            .get_mut(ballot_style_index)
+            .unwrap()
+            .contests
+            .insert(ContestIndex::from_one_based_index(100).unwrap());
+        assert!(manifest.validate().is_err());
+
+        // Contest 10 only appears on the first ballot style.
+        let mut manifest = election_manifest;
+        manifest
+            .ballot_styles
+            .get_mut(ballot_style_index)
+            .unwrap()
+            .contests
+            .remove(&ContestIndex::from_one_based_index(10).unwrap());
+        assert!(manifest.validate().is_err());
+    }
+
+    #[test]
+    fn test_election_manifest_labels() {
+        let election_manifest = example_election_manifest();
+        assert!(election_manifest.label_issues().is_empty());
+
+        let contest_index = ContestIndex::from_one_based_index(1).unwrap();
+        let mut manifest = election_manifest.clone();
+        manifest.contests.get_mut(contest_index).unwrap().label = "Ballot  Measure".to_string();
+        assert!(manifest.validate_labels(LabelPolicy::Strict).is_err());
+        let label_issues = manifest.validate_labels(LabelPolicy::WarnOnly).unwrap();
+        assert_eq!(label_issues.len(), 1);
+        assert_eq!(label_issues[0].location, "contest 1");
+
+        // A label in Normalization Form D is normalized.
+        let mut manifest = election_manifest.clone();
+        manifest.contests.get_mut(contest_index).unwrap().label =
+            "Silve\u{308}rspi\u{302}re".to_string();
+        assert_eq!(manifest.label_issues().len(), 1);
+        manifest.normalize_labels();
+        assert!(manifest.label_issues().is_empty());
+    }
+
+    #[test]
+    fn test_election_manifest_vendor_data() {
+        let election_parameters = example_election_parameters();
+        let election_manifest = example_election_manifest();
+        let h_m = Hashes::compute(&election_parameters, &election_manifest)
+            .unwrap()
+            .h_m;
+
+        // Empty vendor data is not serialized and does not change `H_M`.
+        let canonical_bytes = election_manifest.to_canonical_bytes().unwrap();
+        assert!(!String::from_utf8(canonical_bytes)
+            .unwrap()
+            .contains("vendor_data"));
+
+        let contest_index = ContestIndex::from_one_based_index(1).unwrap();
+        let option_index = ContestOptionIndex::from_one_based_index(2).unwrap();
+        let mut manifest = election_manifest.clone();
+        manifest
+            .contests
+            .get_mut(contest_index)
+            .unwrap()
+            .options
+            .get_mut(option_index)
+            .unwrap()
+            .vendor_data = VendorData::new(b"party=Stormhaven".to_vec()).unwrap();
+
+        let canonical_bytes = manifest.to_canonical_bytes().unwrap();
+        let manifest_from_canonical_bytes =
+            ElectionManifest::from_stdioread_validated(&mut Cursor::new(canonical_bytes)).unwrap();
+        assert_eq!(manifest, manifest_from_canonical_bytes);
+
+        let h_m_vendor_data = Hashes::compute(&election_parameters, &manifest)
+            .unwrap()
+            .h_m;
+        assert_ne!(h_m, h_m_vendor_data);
+    }
 }
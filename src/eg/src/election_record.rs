@@ -5,17 +5,85 @@
 #![deny(clippy::panic)]
 #![deny(clippy::manual_assert)]
 
-use std::collections::HashMap;
+//! This module provides the [`ElectionRecord`], i.e., all data published for an election.
+//!
+//! The election record is serialized as a single JSON object with the following layout:
+//!
+//! - `prevoting` - the [`PreVotingData`], i.e., the election manifest, the election parameters,
+//!   the hashes `H_P`, `H_M`, `H_B` and `H_E`, and the joint election public key.
+//! - `guardian_public_keys` - the [`GuardianPublicKey`] of every guardian, in order of the
+//!   guardian index.
+//! - `share_disputes` - every [`ShareDispute`] raised during the key ceremony, with its verdict.
+//! - `body` - the [`ElectionRecordBody`], consisting of
+//!   - `all_ballots` - every encrypted ballot, whether cast or challenged,
+//!   - `cast_ballots` - the confirmation codes of the cast ballots,
+//!   - `challenged_ballots` - the confirmation codes of the challenged ballots,
+//!   - `ballots_by_device` - for every device the confirmation codes of the ballots it encrypted,
+//!     in the order of encryption,
+//!   - `devices` - the [`DeviceInformation`] of every encryption device session, by device
+//!     information hash,
+//!   - `ballot_chain_closings` - for every device that chains its ballots the
+//!     [`BallotChainClosing`] of its ballot chain,
+//!   - `ballot_box_entries` - the [`BallotBoxEntry`] of every ballot accepted by the ballot box,
+//!     in order of submission, if the ballots were submitted to a
+//!     [`BallotBox`](crate::ballot_box::BallotBox),
+//!   - `challenged_ballot_decryptions` - the [`ChallengedBallotDecryption`] of every challenged
+//!     ballot,
+//!   - `encrypted_tally` - the [`EncryptedTally`] of all cast ballots (optional),
+//!   - `tally_decryption_quorum` - the [`DecryptionQuorum`] of guardians that took part in the
+//!     decryption of the tally, if some guardians were absent (optional),
+//!   - `tally_decryption_shares` - the decryption shares and proof response shares of the
+//!     guardians that took part in the decryption of the tally (optional),
+//!   - `decrypted_tally` - the [`DecryptedTally`] with a proof of correct decryption for every
+//!     option (optional).
 
-use anyhow::{anyhow, Context, Result};
+use std::collections::{BTreeMap, BTreeSet};
+
+use anyhow::{anyhow, bail, ensure, Context, Result};
 use serde::{Deserialize, Serialize};
+use util::{algebra::FieldElement, csprng::Csprng};
 
 use crate::{
-    ballot::BallotEncrypted, election_manifest::ElectionManifest,
-    election_parameters::ElectionParameters, guardian_public_key::GuardianPublicKey,
-    hashes::Hashes, hashes_ext::HashesExt, joint_election_public_key::JointElectionPublicKey,
+    ballot::{BallotEncrypted, BallotState, BallotTallyBuilder},
+    ballot_box::BallotBoxEntry,
+    ballot_chain::BallotChainClosing,
+    challenged_ballot::ChallengedBallotDecryption,
+    decryption_quorum::DecryptionQuorum,
+    device::DeviceInformation,
+    election_manifest::{ContestIndex, ElectionManifest},
+    election_parameters::ElectionParameters,
+    guardian_public_key::GuardianPublicKey,
+    hash::HValue,
+    hashes::Hashes,
+    hashes_ext::HashesExt,
+    joint_election_public_key::{Ciphertext, JointElectionPublicKey},
+    share_dispute::ShareDispute,
+    verifiable_decryption::{
+        DecryptionProofResponseShare, DecryptionShareResult, VerifiableDecryption,
+    },
 };
 
+/// The encrypted tally, i.e., for every contest one encrypted tally per option.
+pub type EncryptedTally = BTreeMap<ContestIndex, Vec<Ciphertext>>;
+
+/// The decrypted tally, i.e., for every contest one [`VerifiableDecryption`] per option.
+pub type DecryptedTally = BTreeMap<ContestIndex, Vec<VerifiableDecryption>>;
+
+/// The messages of the guardians that took part in the decryption of a single option of the
+/// encrypted tally.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OptionDecryptionShares {
+    /// The decryption share and proof commitment share of every participating guardian.
+    pub shares: Vec<DecryptionShareResult>,
+
+    /// The proof response share of every participating guardian, in the same order as `shares`.
+    pub responses: Vec<DecryptionProofResponseShare>,
+}
+
+/// The decryption shares of the tally, i.e., for every contest one [`OptionDecryptionShares`]
+/// per option.
+pub type TallyDecryptionShares = BTreeMap<ContestIndex, Vec<OptionDecryptionShares>>;
+
 /// The header of the election record, generated before the election begins.
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct PreVotingData {
@@ -34,27 +102,401 @@ pub struct PreVotingData {
     /// The joint election public key.
     pub public_key: JointElectionPublicKey,
 }
-#[allow(dead_code)]
+
 /// The body of the election record, generated after the election is complete.
-#[derive(Debug)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct ElectionRecordBody {
     /// Every encrypted ballot prepared in the election (whether cast or challenged)
-    all_ballots: Vec<BallotEncrypted>,
+    pub all_ballots: Vec<BallotEncrypted>,
+
+    /// Confirmation codes of every cast ballot
+    pub cast_ballots: BTreeSet<HValue>,
+
+    /// Confirmation codes of every challenged ballot
+    pub challenged_ballots: BTreeSet<HValue>,
+
+    /// Ordered lists of confirmation codes of the ballots encrypted by each device
+    pub ballots_by_device: BTreeMap<String, Vec<HValue>>,
+
+    /// The information of the encryption device sessions, by device information hash
+    #[serde(default)]
+    pub devices: BTreeMap<String, DeviceInformation>,
+
+    /// The records closing the ballot chains, by device
+    #[serde(default)]
+    pub ballot_chain_closings: BTreeMap<String, BallotChainClosing>,
+
+    /// The entries of the ballot box, in order of submission
+    #[serde(default)]
+    pub ballot_box_entries: Vec<BallotBoxEntry>,
 
-    /// Every challenged ballot
-    // challenged_ballots: Vec<BallotSelections>,
+    /// Decryptions of the challenged ballots
+    #[serde(default)]
+    pub challenged_ballot_decryptions: Vec<ChallengedBallotDecryption>,
 
     /// Tally of all cast ballots
+    pub encrypted_tally: Option<EncryptedTally>,
 
-    /// Ordered lists of ballots encrypted by each device
-    ballots_by_device: HashMap<String, String>,
+    /// The guardians that took part in the decryption of the tally, if it was restricted to a
+    /// quorum
+    #[serde(default)]
+    pub tally_decryption_quorum: Option<DecryptionQuorum>,
+
+    /// Decryption shares and proof response shares for every option of the encrypted tally
+    pub tally_decryption_shares: Option<TallyDecryptionShares>,
+
+    /// Verifiable decryption of every option of the encrypted tally
+    pub decrypted_tally: Option<DecryptedTally>,
 }
-#[allow(dead_code)]
+
 /// The election record.
-#[derive(Debug)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct ElectionRecord {
-    prevoting: PreVotingData,
-    body: ElectionRecordBody,
+    /// The data published before the election.
+    pub prevoting: PreVotingData,
+
+    /// The public keys of all guardians.
+    pub guardian_public_keys: Vec<GuardianPublicKey>,
+
+    /// The adjudicated disputes about encrypted shares from the key ceremony.
+    #[serde(default)]
+    pub share_disputes: Vec<ShareDispute>,
+
+    /// The data published after the election.
+    pub body: ElectionRecordBody,
+}
+
+impl ElectionRecordBody {
+    /// Creates the body of an election record from all encrypted ballots.
+    ///
+    /// The sets of cast and challenged ballots are taken from the ballots' states, and the
+    /// per-device lists follow the order of `all_ballots`. The ballot box entries and the tally
+    /// are left empty.
+    pub fn new(all_ballots: Vec<BallotEncrypted>) -> ElectionRecordBody {
+        let mut cast_ballots = BTreeSet::new();
+        let mut challenged_ballots = BTreeSet::new();
+        let mut ballots_by_device = BTreeMap::<String, Vec<HValue>>::new();
+
+        for ballot in &all_ballots {
+            match ballot.state() {
+                BallotState::Cast => {
+                    cast_ballots.insert(ballot.confirmation_code);
+                }
+                BallotState::Challenged => {
+                    challenged_ballots.insert(ballot.confirmation_code);
+                }
+                BallotState::Uncast => {}
+            }
+            ballots_by_device
+                .entry(ballot.device.clone())
+                .or_default()
+                .push(ballot.confirmation_code);
+        }
+
+        ElectionRecordBody {
+            all_ballots,
+            cast_ballots,
+            challenged_ballots,
+            ballots_by_device,
+            devices: BTreeMap::new(),
+            ballot_chain_closings: BTreeMap::new(),
+            ballot_box_entries: Vec::new(),
+            challenged_ballot_decryptions: Vec::new(),
+            encrypted_tally: None,
+            tally_decryption_quorum: None,
+            tally_decryption_shares: None,
+            decrypted_tally: None,
+        }
+    }
+
+    /// Returns the ballot with the given confirmation code, if any.
+    ///
+    /// This searches `all_ballots` linearly. To look up many ballots, use
+    /// [`ElectionRecordBody::ballots_by_code`] instead.
+    pub fn get_ballot(&self, confirmation_code: &HValue) -> Option<&BallotEncrypted> {
+        self.all_ballots
+            .iter()
+            .find(|ballot| &ballot.confirmation_code == confirmation_code)
+    }
+
+    /// Returns an index of `all_ballots` by confirmation code. If several ballots have the same
+    /// confirmation code, the first one is indexed, as with [`ElectionRecordBody::get_ballot`].
+    pub fn ballots_by_code(&self) -> BTreeMap<HValue, &BallotEncrypted> {
+        let mut ballots_by_code = BTreeMap::new();
+        for ballot in &self.all_ballots {
+            ballots_by_code
+                .entry(ballot.confirmation_code)
+                .or_insert(ballot);
+        }
+        ballots_by_code
+    }
+
+    /// Returns an iterator over the cast ballots.
+    pub fn cast_ballots(&self) -> impl Iterator<Item = &BallotEncrypted> {
+        self.all_ballots
+            .iter()
+            .filter(|ballot| self.cast_ballots.contains(&ballot.confirmation_code))
+    }
+
+    /// Returns an iterator over the challenged ballots.
+    pub fn challenged_ballots(&self) -> impl Iterator<Item = &BallotEncrypted> {
+        self.all_ballots
+            .iter()
+            .filter(|ballot| self.challenged_ballots.contains(&ballot.confirmation_code))
+    }
+
+    /// Verifies that the `ElectionRecordBody` is internally consistent.
+    ///
+    /// This checks that the sets of cast and challenged ballots and the per-device lists refer
+    /// to ballots in `all_ballots` with matching state and device, that the ballot IDs of each
+    /// device strictly increase, that there is at most one
+    /// decryption per challenged ballot and none of other ballots, and that the tally decryption
+    /// is only present together with the encrypted tally, with shares from exactly the guardians
+    /// of the decryption quorum. It does *not* verify any proofs, see
+    /// [`crate::verifier`] for that.
+    pub fn validate(&self) -> Result<()> {
+        let ballots_by_code = self.ballots_by_code();
+
+        for (codes, state, desc) in [
+            (&self.cast_ballots, BallotState::Cast, "cast"),
+            (
+                &self.challenged_ballots,
+                BallotState::Challenged,
+                "challenged",
+            ),
+        ] {
+            for code in codes {
+                let Some(ballot) = ballots_by_code.get(code) else {
+                    bail!("The {desc} ballot {code} is not in the list of all ballots");
+                };
+                ensure!(
+                    ballot.state() == &state,
+                    "The {desc} ballot {code} has state {:?}",
+                    ballot.state()
+                );
+            }
+            let cnt_ballots_in_state = self
+                .all_ballots
+                .iter()
+                .filter(|ballot| ballot.state() == &state)
+                .count();
+            ensure!(
+                cnt_ballots_in_state == codes.len(),
+                "There are {cnt_ballots_in_state} ballots with state {state:?}, but {} {desc} ballots",
+                codes.len()
+            );
+        }
+
+        let mut cnt_device_ballots = 0;
+        for (device, codes) in &self.ballots_by_device {
+            for code in codes {
+                let Some(ballot) = ballots_by_code.get(code) else {
+                    bail!(
+                        "The ballot {code} of device {device:?} is not in the list of all ballots"
+                    );
+                };
+                ensure!(
+                    &ballot.device == device,
+                    "The ballot {code} is listed for device {device:?}, but was encrypted by {:?}",
+                    ballot.device
+                );
+            }
+            cnt_device_ballots += codes.len();
+
+            let mut last_ballot_id = None;
+            for ballot in codes.iter().filter_map(|code| ballots_by_code.get(code)) {
+                let Some(ballot_id) = ballot.ballot_id else {
+                    continue;
+                };
+                if let Some(last_ballot_id) = last_ballot_id {
+                    ensure!(
+                        last_ballot_id < ballot_id,
+                        "The ballot {} of device {device:?} has ID {ballot_id}, which does not follow ID {last_ballot_id}",
+                        ballot.confirmation_code
+                    );
+                }
+                last_ballot_id = Some(ballot_id);
+            }
+        }
+        ensure!(
+            cnt_device_ballots == self.all_ballots.len(),
+            "The per-device lists contain {cnt_device_ballots} ballots, but there are {} ballots",
+            self.all_ballots.len()
+        );
+
+        let mut decrypted_codes = BTreeSet::new();
+        for decryption in &self.challenged_ballot_decryptions {
+            let code = decryption.confirmation_code;
+            ensure!(
+                self.challenged_ballots.contains(&code),
+                "The decrypted ballot {code} is not a challenged ballot"
+            );
+            ensure!(
+                decrypted_codes.insert(code),
+                "The challenged ballot {code} is decrypted more than once"
+            );
+        }
+
+        if self.encrypted_tally.is_none() {
+            ensure!(
+                self.tally_decryption_shares.is_none() && self.decrypted_tally.is_none(),
+                "The election record has a tally decryption but no encrypted tally"
+            );
+        }
+
+        if let (Some(quorum), Some(tally_decryption_shares)) =
+            (&self.tally_decryption_quorum, &self.tally_decryption_shares)
+        {
+            for (contest_index, options) in tally_decryption_shares {
+                for (ix, option) in options.iter().enumerate() {
+                    let guardians: Vec<_> = option.shares.iter().map(|s| s.share.i).collect();
+                    ensure!(
+                        guardians == quorum.participants,
+                        "The decryption shares of contest {contest_index}, option {} are from guardians {guardians:?}, but the quorum is {:?}",
+                        ix + 1,
+                        quorum.participants
+                    );
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Reads an encrypted tally from a `std::io::Read`.
+    pub fn encrypted_tally_from_stdioread(
+        stdioread: &mut dyn std::io::Read,
+    ) -> Result<EncryptedTally> {
+        serde_json::from_reader(stdioread).context("Reading encrypted tally")
+    }
+
+    /// Reads a decrypted tally from a `std::io::Read`.
+    pub fn decrypted_tally_from_stdioread(
+        stdioread: &mut dyn std::io::Read,
+    ) -> Result<DecryptedTally> {
+        serde_json::from_reader(stdioread).context("Reading decrypted tally")
+    }
+
+    /// Reads the tally decryption shares from a `std::io::Read`.
+    pub fn tally_decryption_shares_from_stdioread(
+        stdioread: &mut dyn std::io::Read,
+    ) -> Result<TallyDecryptionShares> {
+        serde_json::from_reader(stdioread).context("Reading tally decryption shares")
+    }
+
+    /// Writes an encrypted tally to a `std::io::Write`.
+    pub fn encrypted_tally_to_stdiowrite(
+        encrypted_tally: &EncryptedTally,
+        stdiowrite: &mut dyn std::io::Write,
+    ) -> Result<()> {
+        to_stdiowrite_pretty(encrypted_tally, stdiowrite).context("Writing encrypted tally")
+    }
+
+    /// Writes a decrypted tally to a `std::io::Write`.
+    pub fn decrypted_tally_to_stdiowrite(
+        decrypted_tally: &DecryptedTally,
+        stdiowrite: &mut dyn std::io::Write,
+    ) -> Result<()> {
+        to_stdiowrite_pretty(decrypted_tally, stdiowrite).context("Writing decrypted tally")
+    }
+
+    /// Writes the tally decryption shares to a `std::io::Write`.
+    pub fn tally_decryption_shares_to_stdiowrite(
+        tally_decryption_shares: &TallyDecryptionShares,
+        stdiowrite: &mut dyn std::io::Write,
+    ) -> Result<()> {
+        to_stdiowrite_pretty(tally_decryption_shares, stdiowrite)
+            .context("Writing tally decryption shares")
+    }
+
+    /// Computes the encrypted tally of all cast ballots.
+    pub fn compute_encrypted_tally(&self, prevoting: &PreVotingData) -> Result<EncryptedTally> {
+        let fixed_parameters = &prevoting.parameters.fixed_parameters;
+        let one = FieldElement::from(1u8, &fixed_parameters.field);
+
+        let mut tally_builder = BallotTallyBuilder::new(&prevoting.manifest, &prevoting.parameters);
+        for ballot in self.cast_ballots() {
+            ensure!(
+                tally_builder.update(ballot.scale(fixed_parameters, &one)),
+                "Cast ballot {} is not compatible with the election manifest",
+                ballot.confirmation_code
+            );
+        }
+
+        Ok(tally_builder.finalize())
+    }
+}
+
+/// Writes a value as pretty JSON followed by a newline to a `std::io::Write`.
+fn to_stdiowrite_pretty<T: Serialize>(
+    value: &T,
+    stdiowrite: &mut dyn std::io::Write,
+) -> Result<()> {
+    let mut ser = serde_json::Serializer::pretty(stdiowrite);
+
+    value.serialize(&mut ser)?;
+    ser.into_inner().write_all(b"\n")?;
+    Ok(())
+}
+
+impl ElectionRecord {
+    /// Reads an `ElectionRecord` from a `std::io::Read` and validates it.
+    pub fn from_stdioread_validated(
+        stdioread: &mut dyn std::io::Read,
+        csprng: &mut Csprng,
+    ) -> Result<Self> {
+        let self_: Self = serde_json::from_reader(stdioread).context("Reading ElectionRecord")?;
+
+        self_.validate(csprng)?;
+
+        Ok(self_)
+    }
+
+    /// Verifies that the `ElectionRecord` meets some basic validity requirements, i.e., that
+    /// every part is well-formed and the body is consistent.
+    /// Useful after deserialization. It does *not* verify any proofs, see [`crate::verifier`]
+    /// for that.
+    pub fn validate(&self, csprng: &mut Csprng) -> Result<()> {
+        let prevoting = &self.prevoting;
+        prevoting.parameters.validate(csprng)?;
+        prevoting.manifest.validate()?;
+        prevoting.hashes.validate()?;
+        prevoting.hashes_ext.validate()?;
+        prevoting.public_key.validate(&prevoting.parameters)?;
+
+        for guardian_public_key in &self.guardian_public_keys {
+            guardian_public_key
+                .validate(&prevoting.parameters)
+                .with_context(|| {
+                    format!("Invalid public key of guardian {}", guardian_public_key.i)
+                })?;
+        }
+
+        for (device, info) in &self.body.devices {
+            ensure!(
+                &info.device_label(prevoting) == device,
+                "The device information {info:?} does not match the device {device:?}"
+            );
+        }
+
+        if let Some(quorum) = &self.body.tally_decryption_quorum {
+            quorum
+                .validate(&prevoting.parameters)
+                .context("Invalid tally decryption quorum")?;
+        }
+
+        self.body.validate()
+    }
+
+    /// Writes an `ElectionRecord` to a `std::io::Write`.
+    pub fn to_stdiowrite(&self, stdiowrite: &mut dyn std::io::Write) -> Result<()> {
+        let mut ser = serde_json::Serializer::pretty(stdiowrite);
+
+        self.serialize(&mut ser)
+            .map_err(Into::<anyhow::Error>::into)
+            .and_then(|_| ser.into_inner().write_all(b"\n").map_err(Into::into))
+            .context("Writing ElectionRecord")
+    }
 }
 
 impl PreVotingData {
@@ -146,3 +588,120 @@ impl PreVotingData {
             .context("Error writing election record header file")
     }
 }
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod test {
+    use std::iter::zip;
+
+    use util::csprng::Csprng;
+
+    use super::*;
+    use crate::{
+        device::Device,
+        example_election::ExampleElection,
+        example_election_parameters::example_election_parameters,
+        tally_decryption::{
+            GuardianTallyDecryptionResponses, GuardianTallyDecryptionShares,
+            TallyDecryptionCeremony,
+        },
+    };
+
+    #[test]
+    fn test_election_record_roundtrip() {
+        let mut csprng = Csprng::new(b"test_election_record_roundtrip");
+        let example_election = ExampleElection::new(&mut csprng, example_election_parameters());
+        let prevoting = &example_election.pre_voting_data;
+
+        // The second ballot is challenged, the others are cast.
+        let device = Device::new("Some encryption device", prevoting.clone());
+        let all_ballots = (0..3)
+            .map(|n| {
+                let mut ballot = BallotEncrypted::new_from_selections(
+                    &device,
+                    &mut csprng,
+                    &[n as u8],
+                    &example_election.selections(n),
+                )
+                .unwrap();
+                if n == 1 {
+                    ballot.challenge().unwrap();
+                } else {
+                    ballot.cast().unwrap();
+                }
+                ballot
+            })
+            .collect::<Vec<_>>();
+        let challenged_ballot_decryption =
+            ChallengedBallotDecryption::from_primary_nonce(prevoting, &all_ballots[1], &[1u8])
+                .unwrap();
+
+        let mut body = ElectionRecordBody::new(all_ballots);
+        body.challenged_ballot_decryptions = vec![challenged_ballot_decryption];
+        let encrypted_tally = body.compute_encrypted_tally(prevoting).unwrap();
+
+        // The tally is decrypted by a quorum of k guardians.
+        let k = prevoting.parameters.varying_parameters.k.as_quantity();
+        let key_shares =
+            example_election.key_shares(&mut csprng, &example_election.guardian_secret_keys[..k]);
+        let mut ceremony = TallyDecryptionCeremony::new(encrypted_tally.clone());
+        let states = key_shares
+            .iter()
+            .map(|key_share| {
+                let (shares, state) = GuardianTallyDecryptionShares::generate(
+                    &mut csprng,
+                    prevoting,
+                    &encrypted_tally,
+                    key_share,
+                );
+                ceremony.add_shares(shares).unwrap();
+                state
+            })
+            .collect::<Vec<_>>();
+        for (state, key_share) in zip(states, &key_shares) {
+            let responses =
+                GuardianTallyDecryptionResponses::generate(prevoting, &ceremony, state, key_share)
+                    .unwrap();
+            ceremony.add_responses(responses).unwrap();
+        }
+        let (decrypted_tally, tally_decryption_shares) = ceremony
+            .finalize(prevoting, &example_election.guardian_public_keys)
+            .unwrap();
+        body.encrypted_tally = Some(encrypted_tally);
+        body.tally_decryption_shares = Some(tally_decryption_shares);
+        body.decrypted_tally = Some(decrypted_tally);
+
+        let mut election_record = ElectionRecord {
+            prevoting: prevoting.clone(),
+            guardian_public_keys: example_election.guardian_public_keys.clone(),
+            share_disputes: Vec::new(),
+            body,
+        };
+        election_record.validate(&mut csprng).unwrap();
+
+        let mut buf = Vec::new();
+        election_record.to_stdiowrite(&mut buf).unwrap();
+        let election_record2 =
+            ElectionRecord::from_stdioread_validated(&mut buf.as_slice(), &mut csprng).unwrap();
+        let mut buf2 = Vec::new();
+        election_record2.to_stdiowrite(&mut buf2).unwrap();
+        assert_eq!(buf, buf2);
+        assert_eq!(election_record2.body.all_ballots.len(), 3);
+        assert_eq!(election_record2.body.cast_ballots.len(), 2);
+        assert_eq!(election_record2.body.challenged_ballot_decryptions.len(), 1);
+        assert!(election_record2.body.tally_decryption_shares.is_some());
+        assert!(election_record2.body.decrypted_tally.is_some());
+
+        // A cast ballot that is not in the list of all ballots is rejected.
+        let unknown_code = HValue::from([0u8; 32]);
+        election_record.body.cast_ballots.insert(unknown_code);
+        assert!(election_record.body.validate().is_err());
+        election_record.body.cast_ballots.remove(&unknown_code);
+
+        // A tally decryption without encrypted tally is rejected.
+        let encrypted_tally = election_record.body.encrypted_tally.take();
+        assert!(election_record.body.validate().is_err());
+        election_record.body.encrypted_tally = encrypted_tally;
+        election_record.body.validate().unwrap();
+    }
+}
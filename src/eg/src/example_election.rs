@@ -0,0 +1,119 @@
+// Copyright (C) Microsoft Corporation. All rights reserved.
+
+#![allow(clippy::unwrap_used)]
+
+//! Test fixtures for an example election, based on the
+//! [`example_election_manifest`](crate::example_election_manifest::example_election_manifest).
+
+use std::collections::BTreeMap;
+
+use util::csprng::Csprng;
+
+use crate::{
+    contest_selection::ContestSelection,
+    election_manifest::ContestIndex,
+    election_parameters::ElectionParameters,
+    election_record::PreVotingData,
+    example_election_manifest::example_election_manifest,
+    guardian_public_key::GuardianPublicKey,
+    guardian_secret_key::GuardianSecretKey,
+    guardian_share::{GuardianEncryptedShare, GuardianSecretKeyShare},
+};
+
+/// The keys of all guardians and the pre-voting data of the example election.
+pub(crate) struct ExampleElection {
+    pub guardian_secret_keys: Vec<GuardianSecretKey>,
+    pub guardian_public_keys: Vec<GuardianPublicKey>,
+    pub pre_voting_data: PreVotingData,
+}
+
+impl ExampleElection {
+    /// Generates the guardian keys and computes the pre-voting data for the example election
+    /// manifest and the given election parameters.
+    pub fn new(csprng: &mut Csprng, election_parameters: ElectionParameters) -> ExampleElection {
+        let guardian_secret_keys = election_parameters
+            .varying_parameters
+            .each_guardian_i()
+            .map(|i| GuardianSecretKey::generate(csprng, &election_parameters, i, None))
+            .collect::<Vec<_>>();
+        let guardian_public_keys = guardian_secret_keys
+            .iter()
+            .map(|sk| sk.make_public_key())
+            .collect::<Vec<_>>();
+        let pre_voting_data = PreVotingData::compute(
+            example_election_manifest(),
+            election_parameters,
+            &guardian_public_keys,
+        )
+        .unwrap();
+
+        ExampleElection {
+            guardian_secret_keys,
+            guardian_public_keys,
+            pre_voting_data,
+        }
+    }
+
+    /// Computes the secret key shares of the given guardians, after a key ceremony in which
+    /// every guardian shares its secret key with them.
+    pub fn key_shares<'a>(
+        &self,
+        csprng: &mut Csprng,
+        recipients: impl IntoIterator<Item = &'a GuardianSecretKey>,
+    ) -> Vec<GuardianSecretKeyShare> {
+        let election_parameters = &self.pre_voting_data.parameters;
+        recipients
+            .into_iter()
+            .map(|sk| {
+                let shares = self
+                    .guardian_secret_keys
+                    .iter()
+                    .map(|dealer_sk| {
+                        GuardianEncryptedShare::encrypt(
+                            csprng,
+                            election_parameters,
+                            dealer_sk,
+                            &sk.make_public_key(),
+                        )
+                        .ciphertext
+                    })
+                    .collect::<Vec<_>>();
+                GuardianSecretKeyShare::compute(
+                    election_parameters,
+                    &self.guardian_public_keys,
+                    &shares,
+                    sk,
+                )
+                .unwrap()
+            })
+            .collect()
+    }
+
+    /// The first contest of the example election manifest.
+    pub fn contest_index() -> ContestIndex {
+        ContestIndex::from_one_based_index(1).unwrap()
+    }
+
+    /// Selections voting for the option with the given 0-based index (modulo the number of
+    /// options) on [`ExampleElection::contest_index`], and on no other contest.
+    pub fn selections(&self, option: usize) -> BTreeMap<ContestIndex, ContestSelection> {
+        let contest_index = Self::contest_index();
+        let num_options = self
+            .pre_voting_data
+            .manifest
+            .contests
+            .get(contest_index)
+            .unwrap()
+            .options
+            .len();
+        let mut vote = vec![0u8; num_options];
+        vote[option % num_options] = 1;
+        BTreeMap::from([(
+            contest_index,
+            ContestSelection {
+                vote,
+                write_ins: Vec::new(),
+            },
+        )])
+    }
+}
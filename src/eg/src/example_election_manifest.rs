@@ -15,15 +15,20 @@ use crate::{
     ballot_style::BallotStyle,
     election_manifest::{Contest, ContestIndex, ContestOption, ElectionManifest},
     vec1::Vec1,
+    vendor_data::VendorData,
 };
 
 pub fn example_election_manifest() -> ElectionManifest {
     let referendum_options: Vec1<ContestOption> = [
         ContestOption {
             label: "Prō".to_string(),
+            opt_vote_limit: None,
+            vendor_data: VendorData::default(),
         },
         ContestOption {
             label: "Ĉontrá".to_string(),
+            opt_vote_limit: None,
+            vendor_data: VendorData::default(),
         },
     ]
     .try_into()
@@ -38,13 +43,18 @@ pub fn example_election_manifest() -> ElectionManifest {
             options: [
                 ContestOption {
                     label:
-                        "Thündéroak, Vâlêriana D.\nËverbright, Ålistair R. Jr.\n(Ætherwïng)"
+                        "Thündéroak, Vâlêriana D. Ëverbright, Ålistair R. Jr. (Ætherwïng)"
                             .to_string(),
+                    opt_vote_limit: None,
+                    vendor_data: VendorData::default(),
                 },
                 ContestOption {
-                    label: "Stârførge, Cássánder A.\nMøonfire, Célestïa L.\n(Crystâlheärt)".to_string(),
+                    label: "Stârførge, Cássánder A. Møonfire, Célestïa L. (Crystâlheärt)".to_string(),
+                    opt_vote_limit: None,
+                    vendor_data: VendorData::default(),
                 },
             ].try_into().unwrap(),
+            vendor_data: VendorData::default(),
         },
         // Contest index 2:
         Contest {
@@ -52,18 +62,27 @@ pub fn example_election_manifest() -> ElectionManifest {
             selection_limit: 1,
             options: [
                 ContestOption {
-                    label: "Élyria Moonshadow\n(Crystâlheärt)".to_string(),
+                    label: "Élyria Moonshadow (Crystâlheärt)".to_string(),
+                    opt_vote_limit: None,
+                    vendor_data: VendorData::default(),
                 },
                 ContestOption {
-                    label: "Archímedes Darkstone\n(Ætherwïng)".to_string(),
+                    label: "Archímedes Darkstone (Ætherwïng)".to_string(),
+                    opt_vote_limit: None,
+                    vendor_data: VendorData::default(),
                 },
                 ContestOption {
-                    label: "Seraphína Stormbinder\n(Independent)".to_string(),
+                    label: "Seraphína Stormbinder (Independent)".to_string(),
+                    opt_vote_limit: None,
+                    vendor_data: VendorData::default(),
                 },
                 ContestOption {
-                    label: "Gávrïel Runëbørne\n(Stärsky)".to_string(),
+                    label: "Gávrïel Runëbørne (Stärsky)".to_string(),
+                    opt_vote_limit: None,
+                    vendor_data: VendorData::default(),
                 },
             ].try_into().unwrap(),
+            vendor_data: VendorData::default(),
         },
         // Contest index 3:
         Contest {
@@ -71,15 +90,22 @@ pub fn example_election_manifest() -> ElectionManifest {
             selection_limit: 1,
             options: [
                 ContestOption {
-                    label: "Tïtus Stormforge\n(Ætherwïng)".to_string(),
+                    label: "Tïtus Stormforge (Ætherwïng)".to_string(),
+                    opt_vote_limit: None,
+                    vendor_data: VendorData::default(),
                 },
                 ContestOption {
-                    label: "Fæ Willowgrove\n(Crystâlheärt)".to_string(),
+                    label: "Fæ Willowgrove (Crystâlheärt)".to_string(),
+                    opt_vote_limit: None,
+                    vendor_data: VendorData::default(),
                 },
                 ContestOption {
-                    label: "Tèrra Stonebinder\n(Independent)".to_string(),
+                    label: "Tèrra Stonebinder (Independent)".to_string(),
+                    opt_vote_limit: None,
+                    vendor_data: VendorData::default(),
                 },
             ].try_into().unwrap(),
+            vendor_data: VendorData::default(),
         },
         // Contest index 4:
         Contest {
@@ -87,15 +113,22 @@ pub fn example_election_manifest() -> ElectionManifest {
             selection_limit: 1,
             options: [
                 ContestOption {
-                    label: "Äeliana Sunsong\n(Crystâlheärt)".to_string(),
+                    label: "Äeliana Sunsong (Crystâlheärt)".to_string(),
+                    opt_vote_limit: None,
+                    vendor_data: VendorData::default(),
                 },
                 ContestOption {
-                    label: "Thâlia Shadowdance\n(Ætherwïng)".to_string(),
+                    label: "Thâlia Shadowdance (Ætherwïng)".to_string(),
+                    opt_vote_limit: None,
+                    vendor_data: VendorData::default(),
                 },
                 ContestOption {
-                    label: "Jasper Moonstep\n(Stärsky)".to_string(),
+                    label: "Jasper Moonstep (Stärsky)".to_string(),
+                    opt_vote_limit: None,
+                    vendor_data: VendorData::default(),
                 },
             ].try_into().unwrap(),
+            vendor_data: VendorData::default(),
         },
         // Contest index 5:
         Contest {
@@ -103,70 +136,101 @@ pub fn example_election_manifest() -> ElectionManifest {
             selection_limit: 3,
             options: [
                 ContestOption {
-                    label: "Ìgnatius Gearsøul\n(Crystâlheärt)".to_string(),
+                    label: "Ìgnatius Gearsøul (Crystâlheärt)".to_string(),
+                    opt_vote_limit: None,
+                    vendor_data: VendorData::default(),
                 },
                 ContestOption {
-                    label: "Èlena Wîndwhisper\n(Technocrat)".to_string(),
+                    label: "Èlena Wîndwhisper (Technocrat)".to_string(),
+                    opt_vote_limit: None,
+                    vendor_data: VendorData::default(),
                 },
                 ContestOption {
-                    label: "Bërnard Månesworn\n(Ætherwïng)".to_string(),
+                    label: "Bërnard Månesworn (Ætherwïng)".to_string(),
+                    opt_vote_limit: None,
+                    vendor_data: VendorData::default(),
                 },
                 ContestOption {
-                    label: "Èmeline Glîmmerwillow\n(Ætherwïng)".to_string(),
+                    label: "Èmeline Glîmmerwillow (Ætherwïng)".to_string(),
+                    opt_vote_limit: None,
+                    vendor_data: VendorData::default(),
                 },
                 ContestOption {
-                    label: "Nikólai Thunderstrîde\n(Independent)".to_string(),
+                    label: "Nikólai Thunderstrîde (Independent)".to_string(),
+                    opt_vote_limit: None,
+                    vendor_data: VendorData::default(),
                 },
                 ContestOption {
-                    label: "Lïliana Fîrestone\n(Pęacemaker)".to_string(),
+                    label: "Lïliana Fîrestone (Pęacemaker)".to_string(),
+                    opt_vote_limit: None,
+                    vendor_data: VendorData::default(),
                 },
                 ContestOption {
-                    label: "Émeric Crystálgaze\n(Førestmíst)".to_string(),
+                    label: "Émeric Crystálgaze (Førestmíst)".to_string(),
+                    opt_vote_limit: None,
+                    vendor_data: VendorData::default(),
                 },
                 ContestOption {
-                    label: "Séraphine Lùmenwing\n(Stärsky)".to_string(),
+                    label: "Séraphine Lùmenwing (Stärsky)".to_string(),
+                    opt_vote_limit: None,
+                    vendor_data: VendorData::default(),
                 },
                 ContestOption {
-                    label: "Rãfael Stëamheart\n(Ætherwïng)".to_string(),
+                    label: "Rãfael Stëamheart (Ætherwïng)".to_string(),
+                    opt_vote_limit: None,
+                    vendor_data: VendorData::default(),
                 },
                 ContestOption {
-                    label: "Océane Tidecaller\n(Pęacemaker)".to_string(),
+                    label: "Océane Tidecaller (Pęacemaker)".to_string(),
+                    opt_vote_limit: None,
+                    vendor_data: VendorData::default(),
                 },
                 ContestOption {
-                    label: "Elysêa Shadowbinder\n(Independent)".to_string(),
+                    label: "Elysêa Shadowbinder (Independent)".to_string(),
+                    opt_vote_limit: None,
+                    vendor_data: VendorData::default(),
                 },
             ].try_into().unwrap(),
+            vendor_data: VendorData::default(),
         },
         // Contest index 6:
         Contest {
-            label: "Proposed Amendment No. 1\nEqual Representation for Technological and Magical Profeſsions".to_string(),
+            label: "Proposed Amendment No. 1 Equal Representation for Technological and Magical Profeſsions".to_string(),
             selection_limit: 1,
             options: [
                 ContestOption {
                     label: "For".to_string(),
+                    opt_vote_limit: None,
+                    vendor_data: VendorData::default(),
                 },
                 ContestOption {
                     label: "Against".to_string(),
+                    opt_vote_limit: None,
+                    vendor_data: VendorData::default(),
                 },
             ].try_into().unwrap(),
+            vendor_data: VendorData::default(),
         },
         // Contest index 7:
         Contest {
             label: "Privacy Protection in Techno-Magical Communications Act".to_string(),
             selection_limit: 1,
             options: referendum_options.clone(),
+            vendor_data: VendorData::default(),
         },
         // Contest index 8:
         Contest {
             label: "Public Transport Modernization and Enchantment Proposal".to_string(),
             selection_limit: 1,
             options: referendum_options.clone(),
+            vendor_data: VendorData::default(),
         },
         // Contest index 9:
         Contest {
             label: "Renewable Ætherwind Infrastructure Initiative".to_string(),
             selection_limit: 1,
             options: referendum_options,
+            vendor_data: VendorData::default(),
         },
         // Contest index 10:
         Contest {
@@ -175,11 +239,16 @@ pub fn example_election_manifest() -> ElectionManifest {
             options: [
                 ContestOption {
                     label: "Élise Planetes".to_string(),
+                    opt_vote_limit: None,
+                    vendor_data: VendorData::default(),
                 },
                 ContestOption {
                     label: "Théodoric Inkdrifter".to_string(),
+                    opt_vote_limit: None,
+                    vendor_data: VendorData::default(),
                 },
             ].try_into().unwrap(),
+            vendor_data: VendorData::default(),
         },
         // Contest index 11:
         Contest {
@@ -189,11 +258,16 @@ pub fn example_election_manifest() -> ElectionManifest {
             options: [
                 ContestOption {
                     label: "Retain".to_string(),
+                    opt_vote_limit: None,
+                    vendor_data: VendorData::default(),
                 },
                 ContestOption {
                     label: "Remove".to_string(),
+                    opt_vote_limit: None,
+                    vendor_data: VendorData::default(),
                 },
             ].try_into().unwrap(),
+            vendor_data: VendorData::default(),
         },
     ].try_into().unwrap();
 
@@ -207,6 +281,7 @@ pub fn example_election_manifest() -> ElectionManifest {
                 ]
                 .map(|ix1| ContestIndex::from_one_based_index(ix1).unwrap()),
             ),
+            vendor_data: VendorData::default(),
         },
         // Ballot style index 2:
         BallotStyle {
@@ -217,6 +292,7 @@ pub fn example_election_manifest() -> ElectionManifest {
                 ]
                 .map(|ix1| ContestIndex::from_one_based_index(ix1).unwrap()),
             ),
+            vendor_data: VendorData::default(),
         },
     ]
     .try_into()
@@ -226,5 +302,6 @@ pub fn example_election_manifest() -> ElectionManifest {
         label: "General Election - The United Realms of Imaginaria".to_string(),
         contests,
         ballot_styles,
+        vendor_data: VendorData::default(),
     }
 }
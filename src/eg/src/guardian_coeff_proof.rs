@@ -96,7 +96,7 @@ impl CoefficientProof {
         let field = &fixed_parameters.field;
         // Compute commit message
         let u = field.random_field_elem(csprng);
-        let h = fixed_parameters.group.g_exp(&u);
+        let h = fixed_parameters.group.g_exp_secret(&u);
         // Compute challenge
         let c = Self::challenge(fixed_parameters, i, j, commitment, &h);
         // Compute response
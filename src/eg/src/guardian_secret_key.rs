@@ -13,6 +13,7 @@ use util::{
     algebra::{FieldElement, GroupElement},
     csprng::Csprng,
 };
+use zeroize::{Zeroize, ZeroizeOnDrop};
 
 use crate::{
     election_parameters::ElectionParameters,
@@ -28,15 +29,24 @@ use crate::{
 /// A polynomial coefficient used to define a secret key sharing.
 ///
 /// This corresponds to the `a_{i,j}` in Equation `9`.
-#[derive(Clone, Debug, Serialize, Deserialize)]
+///
+/// The coefficient is zeroized on drop and redacted in its `Debug` output. It is not `Clone`, so
+/// that no copies of it are left behind.
+#[derive(Serialize, Deserialize, Zeroize, ZeroizeOnDrop)]
 pub struct SecretCoefficient(pub FieldElement);
 
+impl std::fmt::Debug for SecretCoefficient {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("SecretCoefficient(<redacted>)")
+    }
+}
+
 /// A vector of [`SecretCoefficient`]s defining a sharing of the guardian's secret key.
 ///
 /// "Each guardian G_i in an election with a decryption threshold of k generates k secret
 /// polynomial coefficients a_i,j, for 0 ≤ j < k, by sampling them uniformly, at random in
 /// the range 0 ≤ a_i,j < q.
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct SecretCoefficients(pub Vec<SecretCoefficient>);
 
 impl SecretCoefficients {
@@ -92,7 +102,9 @@ impl CoefficientCommitments {
                 .0
                 .iter()
                 .map(|secret_coefficient| {
-                    CoefficientCommitment(fixed_parameters.group.g_exp(&secret_coefficient.0))
+                    CoefficientCommitment(
+                        fixed_parameters.group.g_exp_secret(&secret_coefficient.0),
+                    )
                 })
                 .collect(),
         )
@@ -102,7 +114,9 @@ impl CoefficientCommitments {
 /// The secret key for a guardian.
 ///
 /// See Section `3.2.2` for details on the generation of secret keys.
-#[derive(Clone, Debug, Serialize, Deserialize)]
+///
+/// The [`SecretCoefficients`] are zeroized on drop and redacted in the `Debug` output.
+#[derive(Debug, Serialize, Deserialize)]
 pub struct GuardianSecretKey {
     /// Guardian index, 1 <= i <= [`n`](crate::varying_parameters::VaryingParameters::n).
     pub i: GuardianIndex,
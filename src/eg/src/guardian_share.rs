@@ -7,6 +7,7 @@
 //!
 //! For more details see Section `3.2.2` of the Electionguard specification `2.0.0`.
 
+use anyhow::Context;
 use serde::{Deserialize, Serialize};
 use std::iter::zip;
 use thiserror::Error;
@@ -15,6 +16,7 @@ use util::{
     bitwise::xor,
     csprng::Csprng,
 };
+use zeroize::{Zeroize, ZeroizeOnDrop};
 
 use crate::{
     election_parameters::ElectionParameters,
@@ -39,15 +41,38 @@ pub struct GuardianEncryptedShare {
     pub c1: HValue,
     /// Third ciphertext part, corresponds to `C_{i,l,2}` in Equation `19`.
     pub c2: HValue,
+    /// The dealer's signature on the ciphertext
+    pub signature: ShareSignature,
+}
+
+/// A Schnorr signature by the dealer of a [`GuardianEncryptedShare`] using its public key `K_{i,0}`.
+///
+/// The signature binds the ciphertext to the dealer, so that in a
+/// [`ShareDispute`](crate::share_dispute::ShareDispute) the complainer cannot present a share
+/// other than the one it received.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ShareSignature {
+    /// Challenge
+    pub challenge: FieldElement,
+    /// Response
+    pub response: FieldElement,
 }
 
 /// The secret input used to generate a [`GuardianEncryptedShare`].
 ///
-/// This object is used in case there is a dispute about the validity of a given [`GuardianEncryptedShare`].
+/// This object is used in case there is a dispute about the validity of a given [`GuardianEncryptedShare`],
+/// see [`ShareDispute`](crate::share_dispute::ShareDispute).
+///
+/// The share and nonce are zeroized on drop and redacted in the `Debug` output. Unlike the
+/// other secrets this is `Clone`, as it becomes part of the published [`ShareDispute`](crate::share_dispute::ShareDispute)
+/// when the dealer reveals it.
+#[derive(Clone, Serialize, Deserialize, Zeroize, ZeroizeOnDrop)]
 pub struct GuardianEncryptionSecret {
     /// The sender of the share
+    #[zeroize(skip)]
     pub dealer: GuardianIndex,
     /// The recipient of the share
+    #[zeroize(skip)]
     pub recipient: GuardianIndex,
     /// The share in plain
     pub share: FieldElement,
@@ -55,6 +80,15 @@ pub struct GuardianEncryptionSecret {
     pub nonce: FieldElement,
 }
 
+impl std::fmt::Debug for GuardianEncryptionSecret {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("GuardianEncryptionSecret")
+            .field("dealer", &self.dealer)
+            .field("recipient", &self.recipient)
+            .finish_non_exhaustive()
+    }
+}
+
 /// A tuple consisting of a [`GuardianEncryptedShare`] and the corresponding [`GuardianEncryptionSecret`].
 pub struct ShareEncryptionResult {
     // The encrypted share
@@ -78,6 +112,9 @@ pub enum DecryptionError {
     /// Occurs if the decrypted share is invalid with respect to the dealer's public key.
     #[error("The share does not validate against the dealer's public key.")]
     InvalidShare,
+    /// Occurs if the dealer's signature on the ciphertext does not verify.
+    #[error("The dealer's signature on the share does not verify.")]
+    InvalidSignature,
 }
 
 impl GuardianEncryptedShare {
@@ -155,7 +192,8 @@ impl GuardianEncryptedShare {
         eg_hmac(&k0, &v)
     }
 
-    /// This function computes a [`GuardianEncryptedShare`] of a share for a given recipient and nonce.
+    /// This function computes the ciphertext parts `(C_{i,l,0}, C_{i,l,1}, C_{i,l,2})` of a share
+    /// for a given recipient and nonce.
     ///
     /// The arguments are
     /// - `fixed_parameters` - the fixed parameters
@@ -165,13 +203,13 @@ impl GuardianEncryptedShare {
     /// - `recipient_public_key` - the recipient's [`GuardianPublicKey`]    
     ///
     /// This function is deterministic.
-    fn new(
+    fn ciphertext(
         fixed_parameters: &FixedParameters,
         dealer: &GuardianIndex,
         nonce: &FieldElement,
         share: &FieldElement,
         recipient_public_key: &GuardianPublicKey,
-    ) -> Self {
+    ) -> (GroupElement, HValue, HValue) {
         let group = &fixed_parameters.group;
 
         let i = dealer.get_one_based_u32();
@@ -179,8 +217,8 @@ impl GuardianEncryptedShare {
         let capital_k = recipient_public_key.public_key_k_i_0();
 
         //Generate alpha and beta (Equation 14)
-        let alpha = group.g_exp(nonce);
-        let beta = capital_k.exp(nonce, group);
+        let alpha = group.g_exp_secret(nonce);
+        let beta = capital_k.exp_secret(nonce, group);
 
         let k_i_l = Self::secret_key(fixed_parameters, i, l, capital_k, &alpha, &beta);
         let (k0, k1) = Self::mac_and_encryption_key(i, l, &k_i_l);
@@ -192,13 +230,94 @@ impl GuardianEncryptedShare {
         let c1 = HValue(c1[0..32].try_into().unwrap());
         let c2 = Self::share_mac(k0, alpha.to_be_bytes_left_pad(group).as_slice(), &c1);
 
-        GuardianEncryptedShare {
-            dealer: *dealer,
-            recipient: recipient_public_key.i,
-            c0: alpha,
-            c1,
-            c2,
+        (alpha, c1, c2)
+    }
+
+    /// This function computes the challenge of the dealer's signature.
+    ///
+    /// The arguments are
+    /// - `self` - the encrypted share, only the indices and ciphertext parts are used
+    /// - `fixed_parameters` - the fixed parameters
+    /// - `capital_k` - the dealer's public key `K_{i,0}`
+    /// - `h` - the commit message
+    fn signature_challenge(
+        &self,
+        fixed_parameters: &FixedParameters,
+        capital_k: &GroupElement,
+        h: &GroupElement,
+    ) -> FieldElement {
+        let h_p = ParameterBaseHash::compute(fixed_parameters).h_p;
+        let group = &fixed_parameters.group;
+        // v = b("share_signature",15) | b(i,4) | b(l,4) | b(C_{i,l,0},512) | C_{i,l,1}
+        //     | C_{i,l,2} | b(K_i,512) | b(h,512)
+        let mut v = b"share_signature".to_vec();
+        v.extend_from_slice(self.dealer.get_one_based_u32().to_be_bytes().as_slice());
+        v.extend_from_slice(self.recipient.get_one_based_u32().to_be_bytes().as_slice());
+        v.extend_from_slice(self.c0.to_be_bytes_left_pad(group).as_slice());
+        v.extend_from_slice(self.c1.0.as_slice());
+        v.extend_from_slice(self.c2.0.as_slice());
+        v.extend_from_slice(capital_k.to_be_bytes_left_pad(group).as_slice());
+        v.extend_from_slice(h.to_be_bytes_left_pad(group).as_slice());
+        let c_bytes = eg_h(&h_p, &v);
+        FieldElement::from_bytes_be(c_bytes.0.as_slice(), &fixed_parameters.field)
+    }
+
+    /// This function signs the ciphertext with the dealer's secret key.
+    ///
+    /// [`GuardianEncryptedShare::encrypt`] already signs the share, so this is only needed after
+    /// the ciphertext has been changed.
+    ///
+    /// The arguments are
+    /// - `self` - the encrypted share
+    /// - `csprng` - secure randomness generator
+    /// - `fixed_parameters` - the fixed parameters
+    /// - `dealer_private_key` - the dealer's [`GuardianSecretKey`]
+    pub fn sign(
+        &mut self,
+        csprng: &mut Csprng,
+        fixed_parameters: &FixedParameters,
+        dealer_private_key: &GuardianSecretKey,
+    ) {
+        let field = &fixed_parameters.field;
+        let capital_k = dealer_private_key.coefficient_commitments.0[0].0.clone();
+
+        let u = field.random_field_elem(csprng);
+        let h = fixed_parameters.group.g_exp_secret(&u);
+        let c = self.signature_challenge(fixed_parameters, &capital_k, &h);
+        let v = u.sub(&c.mul(dealer_private_key.secret_s(), field), field);
+
+        self.signature = ShareSignature {
+            challenge: c,
+            response: v,
+        };
+    }
+
+    /// This function verifies the dealer's signature on the ciphertext.
+    ///
+    /// The arguments are
+    /// - `self` - the encrypted share
+    /// - `fixed_parameters` - the fixed parameters
+    /// - `dealer_public_key` - the dealer's [`GuardianPublicKey`]
+    pub fn verify_signature(
+        &self,
+        fixed_parameters: &FixedParameters,
+        dealer_public_key: &GuardianPublicKey,
+    ) -> bool {
+        if self.dealer != dealer_public_key.i {
+            return false;
         }
+
+        let group = &fixed_parameters.group;
+        let capital_k = dealer_public_key.public_key_k_i_0();
+        let signature = &self.signature;
+
+        if !signature.response.is_valid(&fixed_parameters.field) {
+            return false;
+        }
+        let h = group
+            .g_exp(&signature.response)
+            .mul(&capital_k.exp(&signature.challenge, group), group);
+        self.signature_challenge(fixed_parameters, capital_k, &h) == signature.challenge
     }
 
     /// This function creates a new [`ShareEncryptionResult`] given the dealer's secret key for a given recipient.
@@ -228,14 +347,26 @@ impl GuardianEncryptedShare {
 
         //Generate a fresh nonce
         let nonce = field.random_field_elem(csprng);
-        // Encrypt the share
-        let ciphertext = Self::new(
+        // Encrypt and sign the share
+        let (c0, c1, c2) = Self::ciphertext(
             fixed_parameters,
             &dealer_private_key.i,
             &nonce,
             &p_l,
             recipient_public_key,
         );
+        let mut ciphertext = GuardianEncryptedShare {
+            dealer: dealer_private_key.i,
+            recipient: recipient_public_key.i,
+            c0,
+            c1,
+            c2,
+            signature: ShareSignature {
+                challenge: ScalarField::zero(),
+                response: ScalarField::zero(),
+            },
+        };
+        ciphertext.sign(csprng, fixed_parameters, dealer_private_key);
         let secret = GuardianEncryptionSecret {
             dealer: dealer_private_key.i,
             recipient: recipient_public_key.i,
@@ -246,6 +377,21 @@ impl GuardianEncryptedShare {
         ShareEncryptionResult { ciphertext, secret }
     }
 
+    /// Reads a [`GuardianEncryptedShare`] from a [`std::io::Read`].
+    pub fn from_stdioread(stdioread: &mut dyn std::io::Read) -> anyhow::Result<Self> {
+        serde_json::from_reader(stdioread).context("Reading GuardianEncryptedShare")
+    }
+
+    /// Writes a [`GuardianEncryptedShare`] to a [`std::io::Write`].
+    pub fn to_stdiowrite(&self, stdiowrite: &mut dyn std::io::Write) -> anyhow::Result<()> {
+        let mut ser = serde_json::Serializer::pretty(stdiowrite);
+
+        self.serialize(&mut ser)
+            .map_err(Into::<anyhow::Error>::into)
+            .and_then(|_| ser.into_inner().write_all(b"\n").map_err(Into::into))
+            .context("Writing GuardianEncryptedShare")
+    }
+
     /// This function decrypts and validates a [`GuardianEncryptedShare`].
     ///
     /// The arguments are
@@ -276,12 +422,16 @@ impl GuardianEncryptedShare {
         let field = &fixed_parameters.field;
         let group = &fixed_parameters.group;
 
+        if !self.verify_signature(fixed_parameters, dealer_public_key) {
+            return Err(DecryptionError::InvalidSignature);
+        }
+
         let i = self.dealer.get_one_based_u32();
         let l = self.recipient.get_one_based_u32();
 
         let capital_k = &recipient_secret_key.coefficient_commitments.0[0].0;
         let alpha = &self.c0;
-        let beta = alpha.exp(recipient_secret_key.secret_s(), group);
+        let beta = alpha.exp_secret(recipient_secret_key.secret_s(), group);
         let k_i_l = Self::secret_key(fixed_parameters, i, l, capital_k, alpha, &beta);
 
         let (k0, k1) = Self::mac_and_encryption_key(i, l, &k_i_l);
@@ -296,7 +446,7 @@ impl GuardianEncryptedShare {
         let p_l = FieldElement::from_bytes_be(p_l_bytes.as_slice(), field);
 
         // Share validity check
-        let g_p_l = group.g_exp(&p_l);
+        let g_p_l = group.g_exp_secret(&p_l);
         // RHS of Equation `21`
         let l = FieldElement::from(l, field);
         let vec_k_i_j = &dealer_public_key.coefficient_commitments.0;
@@ -340,14 +490,14 @@ impl GuardianEncryptedShare {
         let group = &fixed_parameters.group;
 
         // Check that the ciphertext was computed correctly
-        let expected_ciphertext = Self::new(
+        let (c0, c1, c2) = Self::ciphertext(
             fixed_parameters,
             &dealer_public_key.i,
             &secret.nonce,
             &secret.share,
             recipient_public_key,
         );
-        if *self != expected_ciphertext {
+        if self.c0 != c0 || self.c1 != c1 || self.c2 != c2 {
             return false;
         }
 
@@ -375,14 +525,25 @@ impl GuardianEncryptedShare {
 /// A guardian's share of the joint secret key, it corresponds to `P(i)` in Equation `22`.
 ///
 /// The corresponding public key is never computed explicitly.
-#[derive(Clone, Debug, Serialize, Deserialize)]
+///
+/// The share is zeroized on drop and redacted in the `Debug` output.
+#[derive(Serialize, Deserialize, Zeroize, ZeroizeOnDrop)]
 pub struct GuardianSecretKeyShare {
     /// Guardian index, 1 <= i <= [`n`](crate::varying_parameters::VaryingParameters::n).
+    #[zeroize(skip)]
     pub i: GuardianIndex,
     /// Secret key share
     pub p_i: FieldElement,
 }
 
+impl std::fmt::Debug for GuardianSecretKeyShare {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("GuardianSecretKeyShare")
+            .field("i", &self.i)
+            .finish_non_exhaustive()
+    }
+}
+
 /// Represents errors occurring while combining shares to compute a [`GuardianSecretKeyShare`].
 #[derive(Error, Debug)]
 pub enum ShareCombinationError {
@@ -490,6 +651,50 @@ impl GuardianSecretKeyShare {
             p_i: key,
         })
     }
+
+    /// Verifies that the [`GuardianSecretKeyShare`] is well-formed, i.e., that the guardian index
+    /// is at most `n` and that the key share is a valid field element.
+    pub fn validate(&self, election_parameters: &ElectionParameters) -> anyhow::Result<()> {
+        let varying_parameters = &election_parameters.varying_parameters;
+        let field = &election_parameters.fixed_parameters.field;
+
+        anyhow::ensure!(
+            self.i <= varying_parameters.n,
+            "Guardian number {} is larger than the number of guardians {}",
+            self.i,
+            varying_parameters.n
+        );
+        anyhow::ensure!(
+            self.p_i.is_valid(field),
+            "The secret key share of guardian {} is not a valid field element",
+            self.i
+        );
+
+        Ok(())
+    }
+
+    /// Reads a [`GuardianSecretKeyShare`] from a [`std::io::Read`] and validates it.
+    pub fn from_stdioread_validated(
+        stdioread: &mut dyn std::io::Read,
+        election_parameters: &ElectionParameters,
+    ) -> anyhow::Result<Self> {
+        let self_: Self =
+            serde_json::from_reader(stdioread).context("Reading GuardianSecretKeyShare")?;
+
+        self_.validate(election_parameters)?;
+
+        Ok(self_)
+    }
+
+    /// Writes a [`GuardianSecretKeyShare`] to a [`std::io::Write`].
+    pub fn to_stdiowrite(&self, stdiowrite: &mut dyn std::io::Write) -> anyhow::Result<()> {
+        let mut ser = serde_json::Serializer::pretty(stdiowrite);
+
+        self.serialize(&mut ser)
+            .map_err(Into::<anyhow::Error>::into)
+            .and_then(|_| ser.into_inner().write_all(b"\n").map_err(Into::into))
+            .context("Writing GuardianSecretKeyShare")
+    }
 }
 
 #[cfg(test)]
@@ -504,10 +709,10 @@ mod test {
 
     use crate::{
         example_election_parameters::example_election_parameters, guardian::GuardianIndex,
-        guardian_secret_key::GuardianSecretKey,
+        guardian_secret_key::GuardianSecretKey, hash::HValue,
     };
 
-    use super::{GuardianEncryptedShare, GuardianSecretKeyShare};
+    use super::{DecryptionError, GuardianEncryptedShare, GuardianSecretKeyShare};
 
     #[test]
     fn test_text_encoding() {
@@ -539,6 +744,56 @@ mod test {
         );
 
         assert!(result.is_ok(), "The decrypted share should be valid");
+
+        // A share whose ciphertext was changed after signing is rejected.
+        let mut tampered = encrypted_result.ciphertext.clone();
+        tampered.c1 = HValue([0xAB; 32]);
+        assert!(!tampered.verify_signature(&election_parameters.fixed_parameters, &pk_one));
+        assert!(matches!(
+            tampered.decrypt_and_validate(&election_parameters, &pk_one, &sk_two),
+            Err(DecryptionError::InvalidSignature)
+        ));
+
+        // Re-signing only helps the dealer itself.
+        tampered.sign(&mut csprng, &election_parameters.fixed_parameters, &sk_two);
+        assert!(!tampered.verify_signature(&election_parameters.fixed_parameters, &pk_one));
+        tampered.sign(&mut csprng, &election_parameters.fixed_parameters, &sk_one);
+        assert!(tampered.verify_signature(&election_parameters.fixed_parameters, &pk_one));
+    }
+
+    #[test]
+    fn test_secrets_are_redacted() {
+        let mut csprng = Csprng::new(b"test_secrets_are_redacted");
+
+        let election_parameters = example_election_parameters();
+        let index_one = GuardianIndex::from_one_based_index(1).unwrap();
+        let index_two = GuardianIndex::from_one_based_index(2).unwrap();
+        let sk_one =
+            GuardianSecretKey::generate(&mut csprng, &election_parameters, index_one, None);
+        let sk_two =
+            GuardianSecretKey::generate(&mut csprng, &election_parameters, index_two, None);
+
+        let secret = GuardianEncryptedShare::encrypt(
+            &mut csprng,
+            &election_parameters,
+            &sk_one,
+            &sk_two.make_public_key(),
+        )
+        .secret;
+        let share = GuardianSecretKeyShare {
+            i: index_two,
+            p_i: secret.share.clone(),
+        };
+
+        let share_str = format!("{:?}", secret.share);
+        for debug_str in [
+            format!("{sk_one:?}"),
+            format!("{secret:?}"),
+            format!("{share:?}"),
+        ] {
+            assert!(!debug_str.contains(&share_str), "{debug_str}");
+        }
+        assert!(!format!("{sk_one:?}").contains(&format!("{:?}", sk_one.secret_s())));
     }
 
     #[test]
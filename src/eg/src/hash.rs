@@ -122,6 +122,12 @@ impl HValue {
     }
 }
 
+impl zeroize::Zeroize for HValue {
+    fn zeroize(&mut self) {
+        self.0.zeroize();
+    }
+}
+
 impl From<HValueByteArray> for HValue {
     #[inline]
     fn from(value: HValueByteArray) -> Self {
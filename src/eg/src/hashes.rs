@@ -61,6 +61,10 @@ impl Hashes {
         election_parameters: &ElectionParameters,
         election_manifest: &ElectionManifest,
     ) -> Result<Self> {
+        election_manifest
+            .validate()
+            .context("The election manifest is invalid")?;
+
         // Computation of the base parameter hash H_P.
         let h_p = ParameterBaseHash::compute(&election_parameters.fixed_parameters).h_p;
 
@@ -166,6 +170,7 @@ mod test {
         guardian::GuardianIndex,
         standard_parameters::STANDARD_PARAMETERS,
         varying_parameters::{BallotChaining, VaryingParameters},
+        vendor_data::VendorData,
     };
     use hex_literal::hex;
 
@@ -188,13 +193,18 @@ mod test {
                 options: [
                     ContestOption {
                         label: "SelectionA".to_string(),
+                        opt_vote_limit: None,
+                        vendor_data: VendorData::default(),
                     },
                     ContestOption {
                         label: "SelectionB".to_string(),
+                        opt_vote_limit: None,
+                        vendor_data: VendorData::default(),
                     },
                 ]
                 .try_into()
                 .unwrap(),
+                vendor_data: VendorData::default(),
             },
         ]
         .try_into()
@@ -204,6 +214,7 @@ mod test {
             contests: BTreeSet::from(
                 [1u32].map(|ix1| ContestIndex::from_one_based_index(ix1).unwrap()),
             ),
+            vendor_data: VendorData::default(),
         }]
         .try_into()
         .unwrap();
@@ -212,6 +223,7 @@ mod test {
             label: "AElection".to_string(),
             contests,
             ballot_styles,
+            vendor_data: VendorData::default(),
         }
     }
 
@@ -132,7 +132,7 @@ mod test {
             HashesExt::compute(&election_parameters, &hashes, &joint_election_public_key);
 
         let expected_h_e = HValue::from(hex!(
-            "84135D7084DC8EC9A6E593EE0D7DF9E8F0444DEEB0B1C72BBCB0184D8D50C3A2"
+            "1DAFF4AECCA039DD3AA46A16F6392BD05C7A3836AA2B829684950ABEACB2CC31"
         ));
 
         #[cfg(test_hash_mismatch_warn_only)]
@@ -10,7 +10,11 @@
 
 use anyhow::{bail, ensure, Context, Result};
 use serde::{Deserialize, Serialize};
-use util::algebra::{FieldElement, Group, GroupElement, ScalarField};
+use util::{
+    algebra::{FieldElement, Group, GroupElement, ScalarField},
+    fixed_base::LazyFixedBaseTable,
+};
+use zeroize::{Zeroize, ZeroizeOnDrop};
 
 use crate::{
     election_parameters::ElectionParameters, fixed_parameters::FixedParameters,
@@ -21,6 +25,10 @@ use crate::{
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct JointElectionPublicKey {
     pub joint_election_public_key: GroupElement,
+
+    /// Fixed-base table for the joint election public key, computed on first use.
+    #[serde(skip)]
+    fixed_base_table: LazyFixedBaseTable,
 }
 
 /// A 1-based index of a [`Ciphertext`] in the order it is defined in the [`crate::contest_encrypted::ContestEncrypted`].
@@ -35,11 +43,19 @@ pub struct Ciphertext {
 
 /// The encryption nonce used to produce a [`Ciphertext`]
 /// Relevant for producing proofs about the plaintext.
-#[derive(Debug, Clone)]
+///
+/// The nonce is zeroized on drop and redacted in the `Debug` output.
+#[derive(Zeroize, ZeroizeOnDrop)]
 pub struct Nonce {
     pub xi: FieldElement,
 }
 
+impl std::fmt::Debug for Nonce {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("Nonce(<redacted>)")
+    }
+}
+
 impl Nonce {
     pub fn new(xi: FieldElement) -> Nonce {
         Nonce { xi }
@@ -139,6 +155,7 @@ impl JointElectionPublicKey {
 
         Ok(Self {
             joint_election_public_key,
+            fixed_base_table: LazyFixedBaseTable::default(),
         })
     }
 
@@ -151,13 +168,32 @@ impl JointElectionPublicKey {
         let field = &fixed_parameters.field;
         let group = &fixed_parameters.group;
 
-        let alpha = group.g_exp(nonce);
+        let alpha = group.g_exp_secret(nonce);
         let exponent = &nonce.add(&FieldElement::from(vote, field), field);
-        let beta = self.joint_election_public_key.exp(exponent, group);
+        let beta = self.exp_secret(exponent, group);
 
         Ciphertext { alpha, beta }
     }
 
+    /// Returns the joint election public key `K` raised to the power of `x` mod `p`.
+    ///
+    /// This uses a [fixed-base table](util::fixed_base) for `K`, which is computed on first use
+    /// and shared by clones of the key.
+    pub fn exp(&self, x: &FieldElement, group: &Group) -> GroupElement {
+        self.fixed_base_table
+            .exp(&self.joint_election_public_key, x, group)
+    }
+
+    /// Returns the joint election public key `K` raised to the power of a secret `x` mod `p`,
+    /// e.g., of an encryption nonce.
+    ///
+    /// Like [`JointElectionPublicKey::exp`] this uses the fixed-base table for `K`, but the
+    /// running time does not depend on the value of `x`, see [`GroupElement::exp_secret`].
+    pub fn exp_secret(&self, x: &FieldElement, group: &Group) -> GroupElement {
+        self.fixed_base_table
+            .exp_secret(&self.joint_election_public_key, x, group)
+    }
+
     /// Reads a `JointElectionPublicKey` from a `std::io::Read` and validates it.
     pub fn from_stdioread_validated(
         stdioread: &mut dyn std::io::Read,
@@ -0,0 +1,628 @@
+// Copyright (C) Microsoft Corporation. All rights reserved.
+
+#![deny(clippy::unwrap_used)]
+#![deny(clippy::expect_used)]
+#![deny(clippy::panic)]
+#![deny(clippy::manual_assert)]
+
+//! This module provides the [`KeyCeremony`], a coordinator that tracks the rounds of the key
+//! ceremony. For more details see Section `3.2` of the Electionguard specification `2.0.0`.
+//!
+//! The key ceremony proceeds in the following rounds, see [`KeyCeremonyRound`].
+//! 1. Every guardian publishes its [`GuardianPublicKey`], which is validated including the
+//!    coefficient proofs.
+//! 2. Every guardian, as dealer, publishes one [`GuardianEncryptedShare`] for every guardian.
+//! 3. Every guardian, as recipient, decrypts and validates the shares it received and publishes a
+//!    [`ShareValidation`], listing a [`ShareComplaint`] for every share that failed.
+//!
+//! Complaints are resolved by the dealer revealing the [`GuardianEncryptionSecret`], see
+//! [`KeyCeremony::resolve_complaint`]. The ceremony can only be finalized once all `n` guardians
+//! have validated all shares and every complaint has been dismissed.
+//!
+//! The coordinator holds only public data and can be serialized to pause and resume a ceremony.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::{
+    election_parameters::ElectionParameters,
+    guardian::GuardianIndex,
+    guardian_public_key::GuardianPublicKey,
+    guardian_public_key_info::PublicKeyValidationError,
+    guardian_share::{GuardianEncryptedShare, GuardianEncryptionSecret},
+    joint_election_public_key::JointElectionPublicKey,
+    share_dispute::{ComplaintError, DisputeVerdict, ShareComplaint, ShareDispute},
+};
+
+/// The rounds of the [`KeyCeremony`], in order.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum KeyCeremonyRound {
+    /// Guardians publish their public keys.
+    PublicKeys,
+    /// Guardians publish the encrypted shares of their secret keys.
+    EncryptedShares,
+    /// Guardians validate the shares they received.
+    ShareValidation,
+    /// All guardians have validated their shares.
+    Complete,
+}
+
+/// The message of a recipient after decrypting and validating its shares.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ShareValidation {
+    /// The recipient's index
+    pub i: GuardianIndex,
+    /// A complaint for every share that failed to decrypt or validate. Empty if all shares are
+    /// valid.
+    pub complaints: Vec<ShareComplaint>,
+}
+
+/// Represents errors occurring during the [`KeyCeremony`].
+#[derive(Error, Debug)]
+pub enum KeyCeremonyError {
+    /// Occurs if a message is submitted in the wrong round.
+    #[error("A message for round {submitted:?} was submitted, but the ceremony is in round {current:?}.")]
+    WrongRound {
+        submitted: KeyCeremonyRound,
+        current: KeyCeremonyRound,
+    },
+    /// Occurs if a guardian submits a message for the same round twice.
+    #[error("Guardian {i} has already submitted a message for round {round:?}.")]
+    DuplicateSubmission {
+        i: GuardianIndex,
+        round: KeyCeremonyRound,
+    },
+    /// Occurs if the guardian index is out of bounds.
+    #[error("Guardian number {0} is not in the range 1 <= i <= n.")]
+    IndexOutOfRange(GuardianIndex),
+    /// Occurs if a guardian public key is invalid.
+    #[error("The public key of guardian {0} is invalid: {1}")]
+    InvalidPublicKey(GuardianIndex, PublicKeyValidationError),
+    /// Occurs if a dealer does not submit exactly one share per guardian.
+    #[error("Dealer {dealer} must submit exactly one share for every guardian.")]
+    IncompleteShares { dealer: GuardianIndex },
+    /// Occurs if a share is not signed by its dealer.
+    #[error("The share from dealer {dealer} to guardian {recipient} is not signed by the dealer.")]
+    UnsignedShare {
+        dealer: GuardianIndex,
+        recipient: GuardianIndex,
+    },
+    /// Occurs if a complaint is invalid.
+    #[error("The complaint of guardian {complainer} about dealer {dealer} is invalid: {error}")]
+    InvalidComplaint {
+        complainer: GuardianIndex,
+        dealer: GuardianIndex,
+        error: ComplaintError,
+    },
+    /// Occurs if a complaint is about a share that differs from the published one.
+    #[error("The complaint of guardian {complainer} is not about the share published by dealer {dealer}.")]
+    ComplaintShareMismatch {
+        complainer: GuardianIndex,
+        dealer: GuardianIndex,
+    },
+    /// Occurs if a recipient complains about the same dealer more than once.
+    #[error("Guardian {complainer} complains about dealer {dealer} more than once.")]
+    DuplicateComplaint {
+        complainer: GuardianIndex,
+        dealer: GuardianIndex,
+    },
+    /// Occurs if a complaint to be resolved does not exist.
+    #[error("Guardian {complainer} did not complain about dealer {dealer}.")]
+    NoSuchComplaint {
+        complainer: GuardianIndex,
+        dealer: GuardianIndex,
+    },
+    /// Occurs if a complaint has already been resolved. A verdict is final.
+    #[error("The complaint of guardian {complainer} about dealer {dealer} has already been resolved: {verdict:?}")]
+    AlreadyResolved {
+        complainer: GuardianIndex,
+        dealer: GuardianIndex,
+        verdict: DisputeVerdict,
+    },
+    /// Occurs if the ceremony is finalized with an unresolved complaint.
+    #[error("The complaint of guardian {complainer} about dealer {dealer} has not been resolved.")]
+    UnresolvedComplaint {
+        complainer: GuardianIndex,
+        dealer: GuardianIndex,
+    },
+    /// Occurs if the ceremony is finalized, but a dealer was found at fault.
+    #[error("Dealer {0} was found at fault, its shares must be dealt again.")]
+    DealerAtFault(GuardianIndex),
+    /// Occurs if the joint election public key cannot be computed.
+    #[error("Could not compute the joint election public key: {0}")]
+    JointElectionPublicKey(anyhow::Error),
+}
+
+/// The result of a successful [`KeyCeremony`].
+#[derive(Clone, Debug)]
+pub struct KeyCeremonyResult {
+    /// The public keys of all guardians, in order of the guardian index
+    pub guardian_public_keys: Vec<GuardianPublicKey>,
+    /// The joint election public key
+    pub joint_election_public_key: JointElectionPublicKey,
+    /// The adjudicated disputes, to be published in the election record
+    pub share_disputes: Vec<ShareDispute>,
+}
+
+/// A coordinator for the key ceremony. It tracks which guardian submitted which round's messages
+/// and rejects out-of-order or duplicate submissions.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct KeyCeremony {
+    /// The election parameters
+    election_parameters: ElectionParameters,
+    /// The public key of every guardian
+    guardian_public_keys: BTreeMap<GuardianIndex, GuardianPublicKey>,
+    /// The encrypted shares of every dealer, in order of the recipient
+    encrypted_shares: BTreeMap<GuardianIndex, Vec<GuardianEncryptedShare>>,
+    /// The share validation of every recipient
+    share_validations: BTreeMap<GuardianIndex, ShareValidation>,
+    /// The adjudicated disputes
+    share_disputes: Vec<ShareDispute>,
+}
+
+impl KeyCeremony {
+    /// Starts a new key ceremony.
+    pub fn new(election_parameters: ElectionParameters) -> KeyCeremony {
+        KeyCeremony {
+            election_parameters,
+            guardian_public_keys: BTreeMap::new(),
+            encrypted_shares: BTreeMap::new(),
+            share_validations: BTreeMap::new(),
+            share_disputes: Vec::new(),
+        }
+    }
+
+    /// The election parameters of the ceremony.
+    pub fn election_parameters(&self) -> &ElectionParameters {
+        &self.election_parameters
+    }
+
+    /// The current round, i.e., the first round for which not all `n` guardians have submitted
+    /// their messages.
+    pub fn round(&self) -> KeyCeremonyRound {
+        let n = self.election_parameters.varying_parameters.n.as_quantity();
+        if self.guardian_public_keys.len() < n {
+            KeyCeremonyRound::PublicKeys
+        } else if self.encrypted_shares.len() < n {
+            KeyCeremonyRound::EncryptedShares
+        } else if self.share_validations.len() < n {
+            KeyCeremonyRound::ShareValidation
+        } else {
+            KeyCeremonyRound::Complete
+        }
+    }
+
+    /// The guardians that have not yet submitted their message for the current round.
+    pub fn pending_guardians(&self) -> Vec<GuardianIndex> {
+        let varying_parameters = &self.election_parameters.varying_parameters;
+        let round = self.round();
+        varying_parameters
+            .each_guardian_i()
+            .filter(|i| match round {
+                KeyCeremonyRound::PublicKeys => !self.guardian_public_keys.contains_key(i),
+                KeyCeremonyRound::EncryptedShares => !self.encrypted_shares.contains_key(i),
+                KeyCeremonyRound::ShareValidation => !self.share_validations.contains_key(i),
+                KeyCeremonyRound::Complete => false,
+            })
+            .collect()
+    }
+
+    /// Returns the public keys of all guardians, in order of the guardian index.
+    pub fn guardian_public_keys(&self) -> Vec<GuardianPublicKey> {
+        self.guardian_public_keys.values().cloned().collect()
+    }
+
+    /// Returns the encrypted shares for a recipient, in order of the dealer index.
+    pub fn encrypted_shares_for(&self, recipient: GuardianIndex) -> Vec<GuardianEncryptedShare> {
+        self.encrypted_shares
+            .values()
+            .filter_map(|shares| shares.iter().find(|s| s.recipient == recipient).cloned())
+            .collect()
+    }
+
+    /// Returns the adjudicated disputes.
+    pub fn share_disputes(&self) -> &[ShareDispute] {
+        &self.share_disputes
+    }
+
+    fn ensure_round(&self, submitted: KeyCeremonyRound) -> Result<(), KeyCeremonyError> {
+        let current = self.round();
+        if current == submitted {
+            Ok(())
+        } else {
+            Err(KeyCeremonyError::WrongRound { submitted, current })
+        }
+    }
+
+    fn ensure_guardian_i(&self, i: GuardianIndex) -> Result<(), KeyCeremonyError> {
+        if i <= self.election_parameters.varying_parameters.n {
+            Ok(())
+        } else {
+            Err(KeyCeremonyError::IndexOutOfRange(i))
+        }
+    }
+
+    /// Submits the public key of a guardian (round [`KeyCeremonyRound::PublicKeys`]).
+    ///
+    /// The public key is validated, including the coefficient proofs.
+    pub fn submit_public_key(
+        &mut self,
+        guardian_public_key: GuardianPublicKey,
+    ) -> Result<(), KeyCeremonyError> {
+        let round = KeyCeremonyRound::PublicKeys;
+        let i = guardian_public_key.i;
+        self.ensure_round(round)?;
+        self.ensure_guardian_i(i)?;
+        if self.guardian_public_keys.contains_key(&i) {
+            return Err(KeyCeremonyError::DuplicateSubmission { i, round });
+        }
+        guardian_public_key
+            .validate(&self.election_parameters)
+            .map_err(|e| KeyCeremonyError::InvalidPublicKey(i, e))?;
+
+        self.guardian_public_keys.insert(i, guardian_public_key);
+        Ok(())
+    }
+
+    /// Submits the encrypted shares of a dealer (round [`KeyCeremonyRound::EncryptedShares`]).
+    ///
+    /// The dealer must submit exactly one share for every guardian, including itself, and sign
+    /// every share.
+    pub fn submit_encrypted_shares(
+        &mut self,
+        dealer: GuardianIndex,
+        mut encrypted_shares: Vec<GuardianEncryptedShare>,
+    ) -> Result<(), KeyCeremonyError> {
+        let round = KeyCeremonyRound::EncryptedShares;
+        self.ensure_round(round)?;
+        self.ensure_guardian_i(dealer)?;
+        if self.encrypted_shares.contains_key(&dealer) {
+            return Err(KeyCeremonyError::DuplicateSubmission { i: dealer, round });
+        }
+
+        encrypted_shares.sort_by_key(|s| s.recipient);
+        let is_complete = encrypted_shares.iter().all(|s| s.dealer == dealer)
+            && encrypted_shares.iter().map(|s| s.recipient).eq(self
+                .election_parameters
+                .varying_parameters
+                .each_guardian_i());
+        if !is_complete {
+            return Err(KeyCeremonyError::IncompleteShares { dealer });
+        }
+        let fixed_parameters = &self.election_parameters.fixed_parameters;
+        let dealer_public_key = self
+            .guardian_public_keys
+            .get(&dealer)
+            .ok_or(KeyCeremonyError::IndexOutOfRange(dealer))?;
+        if let Some(share) = encrypted_shares
+            .iter()
+            .find(|s| !s.verify_signature(fixed_parameters, dealer_public_key))
+        {
+            return Err(KeyCeremonyError::UnsignedShare {
+                dealer,
+                recipient: share.recipient,
+            });
+        }
+
+        self.encrypted_shares.insert(dealer, encrypted_shares);
+        Ok(())
+    }
+
+    /// Submits the share validation of a recipient (round
+    /// [`KeyCeremonyRound::ShareValidation`]).
+    ///
+    /// Every complaint must be signed by the recipient and refer to a share as published by the
+    /// dealer. A recipient may complain at most once about each dealer.
+    pub fn submit_share_validation(
+        &mut self,
+        share_validation: ShareValidation,
+    ) -> Result<(), KeyCeremonyError> {
+        let round = KeyCeremonyRound::ShareValidation;
+        let i = share_validation.i;
+        self.ensure_round(round)?;
+        self.ensure_guardian_i(i)?;
+        if self.share_validations.contains_key(&i) {
+            return Err(KeyCeremonyError::DuplicateSubmission { i, round });
+        }
+
+        let fixed_parameters = &self.election_parameters.fixed_parameters;
+        let mut dealers = BTreeSet::new();
+        for complaint in &share_validation.complaints {
+            let dealer = complaint.dealer;
+            if !dealers.insert(dealer) {
+                return Err(KeyCeremonyError::DuplicateComplaint {
+                    complainer: i,
+                    dealer,
+                });
+            }
+            let invalid_complaint = |error| KeyCeremonyError::InvalidComplaint {
+                complainer: i,
+                dealer,
+                error,
+            };
+
+            let complainer_public_key = self
+                .guardian_public_keys
+                .get(&i)
+                .ok_or_else(|| invalid_complaint(ComplaintError::MissingPublicKey(i)))?;
+            if complaint.complainer != i {
+                return Err(invalid_complaint(ComplaintError::NotRecipient {
+                    complainer: complaint.complainer,
+                    recipient: i,
+                }));
+            }
+            complaint
+                .validate(fixed_parameters, complainer_public_key)
+                .map_err(invalid_complaint)?;
+
+            let is_published = self
+                .encrypted_shares
+                .get(&dealer)
+                .and_then(|shares| shares.iter().find(|s| s.recipient == i))
+                .is_some_and(|s| *s == complaint.encrypted_share);
+            if !is_published {
+                return Err(KeyCeremonyError::ComplaintShareMismatch {
+                    complainer: i,
+                    dealer,
+                });
+            }
+        }
+
+        self.share_validations.insert(i, share_validation);
+        Ok(())
+    }
+
+    /// Resolves a complaint given the [`GuardianEncryptionSecret`] revealed by the dealer, or
+    /// `None` if the dealer did not answer. The adjudicated dispute is recorded and its verdict
+    /// returned. Each complaint can only be resolved once.
+    pub fn resolve_complaint(
+        &mut self,
+        complainer: GuardianIndex,
+        dealer: GuardianIndex,
+        revealed_secret: Option<GuardianEncryptionSecret>,
+    ) -> Result<DisputeVerdict, KeyCeremonyError> {
+        let Some(complaint) = self
+            .share_validations
+            .get(&complainer)
+            .and_then(|v| v.complaints.iter().find(|c| c.dealer == dealer))
+        else {
+            return Err(KeyCeremonyError::NoSuchComplaint { complainer, dealer });
+        };
+        if let Some(dispute) = self
+            .share_disputes
+            .iter()
+            .find(|d| d.complaint.complainer == complainer && d.complaint.dealer == dealer)
+        {
+            return Err(KeyCeremonyError::AlreadyResolved {
+                complainer,
+                dealer,
+                verdict: dispute.verdict,
+            });
+        }
+
+        let guardian_public_keys = self.guardian_public_keys();
+        let dispute = ShareDispute::adjudicate(
+            &self.election_parameters,
+            &guardian_public_keys,
+            complaint.clone(),
+            revealed_secret,
+        )
+        .map_err(|error| KeyCeremonyError::InvalidComplaint {
+            complainer,
+            dealer,
+            error,
+        })?;
+        let verdict = dispute.verdict;
+
+        self.share_disputes.push(dispute);
+        Ok(verdict)
+    }
+
+    /// Finalizes the ceremony and computes the joint election public key.
+    ///
+    /// Fails unless all `n` guardians have validated all shares and every complaint has been
+    /// resolved in favor of the dealer.
+    pub fn finalize(&self) -> Result<KeyCeremonyResult, KeyCeremonyError> {
+        self.ensure_round(KeyCeremonyRound::Complete)?;
+
+        for validation in self.share_validations.values() {
+            for complaint in &validation.complaints {
+                let opt_dispute = self.share_disputes.iter().find(|d| {
+                    d.complaint.complainer == complaint.complainer
+                        && d.complaint.dealer == complaint.dealer
+                });
+                match opt_dispute {
+                    None => {
+                        return Err(KeyCeremonyError::UnresolvedComplaint {
+                            complainer: complaint.complainer,
+                            dealer: complaint.dealer,
+                        })
+                    }
+                    Some(d) if d.verdict == DisputeVerdict::DealerAtFault => {
+                        return Err(KeyCeremonyError::DealerAtFault(complaint.dealer))
+                    }
+                    Some(_) => {}
+                }
+            }
+        }
+
+        let guardian_public_keys = self.guardian_public_keys();
+        let joint_election_public_key =
+            JointElectionPublicKey::compute(&self.election_parameters, &guardian_public_keys)
+                .map_err(KeyCeremonyError::JointElectionPublicKey)?;
+
+        Ok(KeyCeremonyResult {
+            guardian_public_keys,
+            joint_election_public_key,
+            share_disputes: self.share_disputes.clone(),
+        })
+    }
+
+    /// Reads a `KeyCeremony` from a `std::io::Read`.
+    pub fn from_stdioread(stdioread: &mut dyn std::io::Read) -> Result<Self> {
+        serde_json::from_reader(stdioread).context("Reading KeyCeremony")
+    }
+
+    /// Writes a `KeyCeremony` to a `std::io::Write`.
+    pub fn to_stdiowrite(&self, stdiowrite: &mut dyn std::io::Write) -> Result<()> {
+        let mut ser = serde_json::Serializer::pretty(stdiowrite);
+
+        self.serialize(&mut ser)
+            .map_err(Into::<anyhow::Error>::into)
+            .and_then(|_| ser.into_inner().write_all(b"\n").map_err(Into::into))
+            .context("Writing KeyCeremony")
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod test {
+    use util::csprng::Csprng;
+
+    use super::*;
+    use crate::{
+        example_election_parameters::example_election_parameters,
+        guardian_secret_key::GuardianSecretKey, guardian_share::GuardianEncryptedShare,
+        share_dispute::ShareComplaint,
+    };
+
+    #[test]
+    fn test_key_ceremony() {
+        let mut csprng = Csprng::new(b"test_key_ceremony");
+        let election_parameters = example_election_parameters();
+
+        let guardian_secret_keys = election_parameters
+            .varying_parameters
+            .each_guardian_i()
+            .map(|i| GuardianSecretKey::generate(&mut csprng, &election_parameters, i, None))
+            .collect::<Vec<_>>();
+
+        let mut ceremony = KeyCeremony::new(election_parameters.clone());
+        assert_eq!(ceremony.round(), KeyCeremonyRound::PublicKeys);
+
+        // Round 1
+        for sk in &guardian_secret_keys {
+            ceremony.submit_public_key(sk.make_public_key()).unwrap();
+        }
+        assert!(matches!(
+            ceremony.submit_public_key(guardian_secret_keys[0].make_public_key()),
+            Err(KeyCeremonyError::WrongRound { .. })
+        ));
+        assert_eq!(ceremony.round(), KeyCeremonyRound::EncryptedShares);
+
+        // Round 2, with a pause and resume in between.
+        let guardian_public_keys = ceremony.guardian_public_keys();
+        let mut secrets = BTreeMap::new();
+        for (ix, dealer_sk) in guardian_secret_keys.iter().enumerate() {
+            let mut shares = vec![];
+            for pk in &guardian_public_keys {
+                let res = GuardianEncryptedShare::encrypt(
+                    &mut csprng,
+                    &election_parameters,
+                    dealer_sk,
+                    pk,
+                );
+                secrets.insert((dealer_sk.i, pk.i), res.secret);
+                shares.push(res.ciphertext);
+            }
+            if ix == 0 {
+                assert!(matches!(
+                    ceremony.submit_encrypted_shares(dealer_sk.i, shares[1..].to_vec()),
+                    Err(KeyCeremonyError::IncompleteShares { .. })
+                ));
+                let mut unsigned = shares.clone();
+                unsigned[1].c2 = unsigned[0].c2;
+                assert!(matches!(
+                    ceremony.submit_encrypted_shares(dealer_sk.i, unsigned),
+                    Err(KeyCeremonyError::UnsignedShare { .. })
+                ));
+            }
+            ceremony
+                .submit_encrypted_shares(dealer_sk.i, shares.clone())
+                .unwrap();
+            if ix == 0 {
+                assert!(matches!(
+                    ceremony.submit_encrypted_shares(dealer_sk.i, shares),
+                    Err(KeyCeremonyError::DuplicateSubmission { .. })
+                ));
+                let mut buf = Vec::new();
+                ceremony.to_stdiowrite(&mut buf).unwrap();
+                ceremony = KeyCeremony::from_stdioread(&mut buf.as_slice()).unwrap();
+            }
+        }
+        assert_eq!(ceremony.round(), KeyCeremonyRound::ShareValidation);
+        assert!(matches!(
+            ceremony.finalize(),
+            Err(KeyCeremonyError::WrongRound { .. })
+        ));
+
+        // Round 3. The last guardian wrongly complains about the first guardian's share.
+        let last_sk = guardian_secret_keys.last().unwrap();
+        let first_i = guardian_secret_keys[0].i;
+        for sk in &guardian_secret_keys {
+            let shares = ceremony.encrypted_shares_for(sk.i);
+            for (share, pk) in shares.iter().zip(&guardian_public_keys) {
+                share
+                    .decrypt_and_validate(&election_parameters, pk, sk)
+                    .unwrap();
+            }
+            let complaints = if sk.i == last_sk.i {
+                let complaint = ShareComplaint::new(
+                    &mut csprng,
+                    &election_parameters,
+                    sk,
+                    &shares[0],
+                    crate::guardian_share::DecryptionError::InvalidShare,
+                )
+                .unwrap();
+
+                // A second complaint about the same dealer is rejected.
+                assert!(matches!(
+                    ceremony.submit_share_validation(ShareValidation {
+                        i: sk.i,
+                        complaints: vec![complaint.clone(), complaint.clone()],
+                    }),
+                    Err(KeyCeremonyError::DuplicateComplaint { .. })
+                ));
+                vec![complaint]
+            } else {
+                vec![]
+            };
+            ceremony
+                .submit_share_validation(ShareValidation {
+                    i: sk.i,
+                    complaints,
+                })
+                .unwrap();
+        }
+        assert_eq!(ceremony.round(), KeyCeremonyRound::Complete);
+        assert!(matches!(
+            ceremony.finalize(),
+            Err(KeyCeremonyError::UnresolvedComplaint { .. })
+        ));
+
+        let verdict = ceremony
+            .resolve_complaint(last_sk.i, first_i, secrets.remove(&(first_i, last_sk.i)))
+            .unwrap();
+        assert_eq!(verdict, DisputeVerdict::ComplainerAtFault);
+
+        // The verdict is final, even if the dealer does not answer a second time.
+        assert!(matches!(
+            ceremony.resolve_complaint(last_sk.i, first_i, None),
+            Err(KeyCeremonyError::AlreadyResolved {
+                verdict: DisputeVerdict::ComplainerAtFault,
+                ..
+            })
+        ));
+
+        let result = ceremony.finalize().unwrap();
+        assert_eq!(result.share_disputes.len(), 1);
+        assert_eq!(
+            result.joint_election_public_key,
+            JointElectionPublicKey::compute(&election_parameters, &guardian_public_keys).unwrap()
+        );
+    }
+}
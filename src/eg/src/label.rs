@@ -0,0 +1,195 @@
+// Copyright (C) Microsoft Corporation. All rights reserved.
+
+#![deny(clippy::unwrap_used)]
+#![deny(clippy::expect_used)]
+#![deny(clippy::panic)]
+#![deny(clippy::manual_assert)]
+
+//! This module provides the policy for the labels of an
+//! [`ElectionManifest`](crate::election_manifest::ElectionManifest), i.e., the labels of its
+//! contests, contest options and ballot styles. It follows the label rules of the
+//! Electionguard implementation guide.
+//!
+//! A label
+//! - is in Unicode Normalization Form C (NFC), see [`normalize_label`],
+//! - is not empty,
+//! - has no leading or trailing whitespace,
+//! - has no whitespace other than single spaces `U+0020` between words, and
+//! - has no control characters.
+//!
+//! Moreover, no two labels of the same collection may be confusable, i.e., have the same
+//! skeleton according to Unicode Technical Standard #39, such as a Latin `a` and a Cyrillic `а`.
+
+use std::collections::BTreeMap;
+
+use thiserror::Error;
+use unicode_normalization::{is_nfc, UnicodeNormalization};
+use unicode_security::skeleton;
+
+/// Whether violations of the label policy are errors or only warnings.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LabelPolicy {
+    /// Every violation of the label policy is an error.
+    #[default]
+    Strict,
+    /// Violations of the label policy are reported, but do not fail validation.
+    WarnOnly,
+}
+
+/// Represents violations of the label policy.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum LabelError {
+    /// Occurs if the label is empty.
+    #[error("The label is empty.")]
+    Empty,
+    /// Occurs if the label is not in Unicode Normalization Form C.
+    #[error("The label {0:?} is not in Unicode Normalization Form C.")]
+    NotNormalized(String),
+    /// Occurs if the label starts or ends with whitespace.
+    #[error("The label {0:?} has leading or trailing whitespace.")]
+    LeadingOrTrailingWhitespace(String),
+    /// Occurs if the label has consecutive whitespace or whitespace other than `U+0020`.
+    #[error("The label {0:?} has repeated whitespace or whitespace other than a single space.")]
+    InvalidWhitespace(String),
+    /// Occurs if the label contains a control character.
+    #[error("The label {0:?} contains the control character {1:?}.")]
+    ControlCharacter(String, char),
+    /// Occurs if two labels of the same collection are confusable.
+    #[error("The label {0:?} is confusable with the label {1:?}.")]
+    Confusable(String, String),
+}
+
+/// A violation of the label policy, together with the location of the label.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LabelIssue {
+    /// The location of the label, e.g., `contest 2, option 3`
+    pub location: String,
+    /// The violation
+    pub error: LabelError,
+}
+
+impl std::fmt::Display for LabelIssue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.location, self.error)
+    }
+}
+
+/// Returns the label in Unicode Normalization Form C.
+pub fn normalize_label(label: &str) -> String {
+    label.nfc().collect()
+}
+
+/// Validates a single label against the label policy.
+pub fn validate_label(label: &str) -> Result<(), LabelError> {
+    if label.is_empty() {
+        return Err(LabelError::Empty);
+    }
+    if !is_nfc(label) {
+        return Err(LabelError::NotNormalized(label.to_string()));
+    }
+    if label.starts_with(char::is_whitespace) || label.ends_with(char::is_whitespace) {
+        return Err(LabelError::LeadingOrTrailingWhitespace(label.to_string()));
+    }
+    if let Some(c) = label.chars().find(|c| c.is_control()) {
+        return Err(LabelError::ControlCharacter(label.to_string(), c));
+    }
+
+    let mut prev_is_whitespace = false;
+    for c in label.chars() {
+        let is_whitespace = c.is_whitespace();
+        if is_whitespace && (c != ' ' || prev_is_whitespace) {
+            return Err(LabelError::InvalidWhitespace(label.to_string()));
+        }
+        prev_is_whitespace = is_whitespace;
+    }
+
+    Ok(())
+}
+
+/// Validates a collection of labels, e.g., the labels of all options of a contest.
+///
+/// Every label is validated with [`validate_label`], and every pair of distinct but
+/// confusable labels is reported. The `location` closure names the label with the given
+/// position in the collection.
+pub fn validate_label_collection<'a>(
+    labels: impl IntoIterator<Item = &'a str>,
+    location: impl Fn(usize) -> String,
+) -> Vec<LabelIssue> {
+    let mut issues = Vec::new();
+    let mut skeletons = BTreeMap::<String, &str>::new();
+
+    for (ix, label) in labels.into_iter().enumerate() {
+        if let Err(error) = validate_label(label) {
+            issues.push(LabelIssue {
+                location: location(ix),
+                error,
+            });
+        }
+
+        let label_skeleton: String = skeleton(label).collect();
+        match skeletons.get(&label_skeleton) {
+            Some(&other) if other != label => issues.push(LabelIssue {
+                location: location(ix),
+                error: LabelError::Confusable(label.to_string(), other.to_string()),
+            }),
+            Some(_) => {}
+            None => {
+                skeletons.insert(label_skeleton, label);
+            }
+        }
+    }
+
+    issues
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_validate_label() {
+        validate_label("Silvërspîre County Ballot").unwrap();
+
+        assert_eq!(validate_label(""), Err(LabelError::Empty));
+        let decomposed = "Silve\u{308}rspi\u{302}re";
+        assert!(matches!(
+            validate_label(decomposed),
+            Err(LabelError::NotNormalized(_))
+        ));
+        validate_label(&normalize_label(decomposed)).unwrap();
+        assert!(matches!(
+            validate_label(" Mayor"),
+            Err(LabelError::LeadingOrTrailingWhitespace(_))
+        ));
+        assert!(matches!(
+            validate_label("Mayor\u{a0}"),
+            Err(LabelError::LeadingOrTrailingWhitespace(_))
+        ));
+        assert!(matches!(
+            validate_label("City  Mayor"),
+            Err(LabelError::InvalidWhitespace(_))
+        ));
+        assert!(matches!(
+            validate_label("City\tMayor"),
+            Err(LabelError::ControlCharacter(_, '\t'))
+        ));
+        assert!(matches!(
+            validate_label("City\u{2003}Mayor"),
+            Err(LabelError::InvalidWhitespace(_))
+        ));
+    }
+
+    #[test]
+    fn test_validate_label_collection() {
+        let location = |ix: usize| format!("option {}", ix + 1);
+
+        assert!(validate_label_collection(["Alice", "Bob"], location).is_empty());
+
+        // A Cyrillic `о` instead of a Latin `o`.
+        let issues = validate_label_collection(["Alice", "Bob", "B\u{43e}b"], location);
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].location, "option 3");
+        assert!(matches!(issues[0].error, LabelError::Confusable(_, _)));
+    }
+}
@@ -59,17 +59,37 @@
 //!   after the joint election public key is known.
 //!
 //! - [VerifiableDecryption](crate::verifiable_decryption::VerifiableDecryption) A decrypted plain-text with a [proof of correct decryption](crate::verifiable_decryption::DecryptionProof)
+//!
+//! - [BallotBox](crate::ballot_box::BallotBox) Records whether each encrypted ballot is cast or
+//!   challenged, accepting every confirmation code at most once.
+//!
+//! - [TallyDecryptionCeremony](crate::tally_decryption::TallyDecryptionCeremony) Drives the
+//!   decryption of the encrypted tally by a quorum of guardians.
+//!
+//! - [DecryptionQuorum](crate::decryption_quorum::DecryptionQuorum) The `k` guardians selected from
+//!   the present guardians to decrypt when some guardians are missing.
+//!
+//! - [ElectionRecord](crate::election_record::ElectionRecord) The election record, which can be
+//!   checked with [verify_election_record](crate::verifier::verify_election_record), producing a
+//!   [VerificationReport](crate::verification_report::VerificationReport).
 
 pub mod ballot;
+pub mod ballot_box;
+pub mod ballot_chain;
 pub mod ballot_style;
+pub mod challenged_ballot;
 pub mod confirmation_code;
+pub mod contest_data;
 pub mod contest_encrypted;
 pub mod contest_hash;
 pub mod contest_selection;
+pub mod decryption_quorum;
 pub mod device;
 pub mod election_manifest;
 pub mod election_parameters;
 pub mod election_record;
+#[cfg(test)]
+mod example_election;
 pub mod example_election_manifest;
 pub mod example_election_parameters;
 pub mod fixed_parameters;
@@ -84,9 +104,17 @@ pub mod hashes;
 pub mod hashes_ext;
 pub mod index;
 pub mod joint_election_public_key;
+pub mod key_ceremony;
+pub mod label;
+pub mod nist_cdf;
 pub mod nonce;
+pub mod share_dispute;
 pub mod standard_parameters;
+pub mod tally_decryption;
 pub mod varying_parameters;
 pub mod vec1;
+pub mod vendor_data;
 pub mod verifiable_decryption;
+pub mod verification_report;
+pub mod verifier;
 pub mod zk;
@@ -0,0 +1,698 @@
+// Copyright (C) Microsoft Corporation. All rights reserved.
+
+#![deny(clippy::unwrap_used)]
+#![deny(clippy::expect_used)]
+#![deny(clippy::panic)]
+#![deny(clippy::manual_assert)]
+
+//! This module imports an election definition in the JSON format of the NIST SP 1500-100
+//! Election Results Common Data Format (CDF), version 2, as an
+//! [`ElectionManifest`] with its [`BallotStyle`]s.
+//!
+//! The importer maps
+//! - the first `Election` of the `ElectionReport` to the manifest,
+//! - every `CandidateContest`, `BallotMeasureContest`, `RetentionContest` and `PartyContest`
+//!   to a [`Contest`] whose options are the contest's selections, in order,
+//! - every `BallotStyle` to a [`BallotStyle`] with the contests of its `OrderedContent`.
+//!
+//! The CDF `@id`s of the contests and their selections are kept as the [`VendorData`] of the
+//! corresponding contests and options, so that results can be related to the CDF objects.
+//! The labels are normalized to Unicode Normalization Form C.
+//!
+//! Constructs that cannot be represented in an [`ElectionManifest`], e.g., ranked-choice
+//! contests, are not imported. Every such construct is reported as an [`ImportIssue`].
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::num::NonZeroU32;
+
+use anyhow::{bail, ensure, Context, Result};
+use serde::Deserialize;
+use thiserror::Error;
+
+use crate::ballot_style::BallotStyle;
+use crate::election_manifest::{Contest, ContestIndex, ContestOption, ElectionManifest};
+use crate::vendor_data::VendorData;
+
+/// A construct of the election definition that is not imported.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum ImportIssue {
+    /// Occurs if the election report contains more than one election.
+    #[error("The election report contains {0} elections, only the first one is imported.")]
+    AdditionalElections(usize),
+    /// Occurs if a contest has a vote variation other than plurality, n-of-m, majority,
+    /// super-majority, approval or cumulative, e.g., ranked-choice (`rcv`).
+    #[error("The contest {contest:?} with vote variation {vote_variation:?} is not supported and is not imported.")]
+    UnsupportedVoteVariation {
+        /// The `@id` of the contest
+        contest: String,
+        /// The vote variation of the contest
+        vote_variation: String,
+    },
+    /// Occurs if a contest is of an unknown type.
+    #[error(
+        "The contest {contest:?} of type {contest_type:?} is not supported and is not imported."
+    )]
+    UnsupportedContestType {
+        /// The `@id` of the contest
+        contest: String,
+        /// The `@type` of the contest
+        contest_type: String,
+    },
+    /// Occurs if a ballot style specifies an order of the selections of a contest other than the
+    /// contest's own order, e.g., for ballot rotation. The options of a contest always have the order of the manifest.
+    #[error("The selection order of contest {contest:?} on ballot style {ballot_style:?} is not imported.")]
+    SelectionOrderIgnored {
+        /// The label of the ballot style
+        ballot_style: String,
+        /// The `@id` of the contest
+        contest: String,
+    },
+    /// Occurs if the election has no ballot styles. A single ballot style with all contests is
+    /// created instead.
+    #[error(
+        "The election has no ballot styles, a single ballot style with all contests is created."
+    )]
+    NoBallotStyles,
+}
+
+/// Imports the election definition from a NIST SP 1500-100 `ElectionReport` in JSON format.
+///
+/// Text is taken in the given language, e.g., `en`, if available, and in the first language
+/// given otherwise. Returns the validated [`ElectionManifest`] together with the constructs
+/// that are not imported.
+pub fn import_election_report(
+    stdioread: &mut dyn std::io::Read,
+    language: &str,
+) -> Result<(ElectionManifest, Vec<ImportIssue>)> {
+    let election_report: CdfElectionReport =
+        serde_json::from_reader(stdioread).context("Reading NIST SP 1500-100 ElectionReport")?;
+    election_report.import(language)
+}
+
+/// `InternationalizedText`
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct CdfText {
+    #[serde(default)]
+    text: Vec<CdfLanguageString>,
+}
+
+/// `LanguageString`
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct CdfLanguageString {
+    content: String,
+    language: String,
+}
+
+impl CdfText {
+    fn in_language(&self, language: &str) -> Option<&str> {
+        self.text
+            .iter()
+            .find(|s| s.language == language)
+            .or(self.text.first())
+            .map(|s| s.content.as_str())
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct CdfElectionReport {
+    #[serde(default)]
+    election: Vec<CdfElection>,
+    #[serde(default)]
+    gp_unit: Vec<CdfGpUnit>,
+    #[serde(default)]
+    party: Vec<CdfParty>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct CdfElection {
+    name: CdfText,
+    #[serde(default)]
+    contest: Vec<CdfContest>,
+    #[serde(default)]
+    ballot_style: Vec<CdfBallotStyle>,
+    #[serde(default)]
+    candidate: Vec<CdfCandidate>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct CdfGpUnit {
+    #[serde(rename = "@id")]
+    id: String,
+    #[serde(default)]
+    name: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct CdfParty {
+    #[serde(rename = "@id")]
+    id: String,
+    name: CdfText,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct CdfCandidate {
+    #[serde(rename = "@id")]
+    id: String,
+    ballot_name: CdfText,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct CdfContest {
+    #[serde(rename = "@id")]
+    id: String,
+    #[serde(rename = "@type")]
+    type_: String,
+    name: String,
+    #[serde(default)]
+    ballot_title: Option<CdfText>,
+    #[serde(default)]
+    votes_allowed: Option<u32>,
+    #[serde(default)]
+    vote_variation: Option<String>,
+    #[serde(default)]
+    other_vote_variation: Option<String>,
+    #[serde(default)]
+    contest_selection: Vec<CdfContestSelection>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct CdfContestSelection {
+    #[serde(rename = "@id")]
+    id: String,
+    #[serde(default)]
+    candidate_ids: Vec<String>,
+    #[serde(default)]
+    is_write_in: bool,
+    #[serde(default)]
+    selection: Option<CdfText>,
+    #[serde(default)]
+    party_ids: Vec<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct CdfBallotStyle {
+    #[serde(default)]
+    gp_unit_ids: Vec<String>,
+    #[serde(default)]
+    party_ids: Vec<String>,
+    #[serde(default)]
+    external_identifier: Vec<CdfExternalIdentifier>,
+    #[serde(default)]
+    ordered_content: Vec<CdfOrderedContent>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct CdfExternalIdentifier {
+    value: String,
+}
+
+/// `OrderedContest` or `OrderedHeader`
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct CdfOrderedContent {
+    #[serde(default)]
+    contest_id: Option<String>,
+    #[serde(default)]
+    ordered_contest_selection_ids: Vec<String>,
+    #[serde(default)]
+    ordered_content: Vec<CdfOrderedContent>,
+}
+
+impl CdfOrderedContent {
+    /// Collects the contests in this content and its nested content, i.e., those of headers.
+    fn collect_contests<'a>(&'a self, contests: &mut Vec<&'a CdfOrderedContent>) {
+        if self.contest_id.is_some() {
+            contests.push(self);
+        }
+        for content in &self.ordered_content {
+            content.collect_contests(contests);
+        }
+    }
+}
+
+impl CdfElectionReport {
+    fn import(&self, language: &str) -> Result<(ElectionManifest, Vec<ImportIssue>)> {
+        let mut issues = Vec::new();
+
+        let Some(election) = self.election.first() else {
+            bail!("The election report contains no election");
+        };
+        if self.election.len() > 1 {
+            issues.push(ImportIssue::AdditionalElections(self.election.len()));
+        }
+
+        let text = |text: &CdfText, what: &str| -> Result<String> {
+            match text.in_language(language) {
+                Some(s) => Ok(s.to_string()),
+                None => bail!("The {what} has no text"),
+            }
+        };
+
+        let candidate_names = election
+            .candidate
+            .iter()
+            .map(|c| Ok((c.id.as_str(), text(&c.ballot_name, "candidate name")?)))
+            .collect::<Result<BTreeMap<_, _>>>()?;
+        let party_names = self
+            .party
+            .iter()
+            .map(|p| Ok((p.id.as_str(), text(&p.name, "party name")?)))
+            .collect::<Result<BTreeMap<_, _>>>()?;
+
+        // The contests, and the indices of the imported contests by `@id`.
+        let mut contests = Vec::new();
+        let mut contest_indices = BTreeMap::new();
+        let mut skipped_contests = BTreeSet::new();
+        for cdf_contest in &election.contest {
+            let contest = Self::import_contest(cdf_contest, &candidate_names, &party_names, &text)
+                .with_context(|| format!("Importing contest {:?}", cdf_contest.id))?;
+            match contest {
+                Ok(contest) => {
+                    contests.push(contest);
+                    let contest_index = ContestIndex::from_one_based_index(contests.len() as u32)?;
+                    ensure!(
+                        contest_indices
+                            .insert(cdf_contest.id.as_str(), contest_index)
+                            .is_none(),
+                        "The contest id {:?} is not unique",
+                        cdf_contest.id
+                    );
+                }
+                Err(issue) => {
+                    issues.push(issue);
+                    skipped_contests.insert(cdf_contest.id.as_str());
+                }
+            }
+        }
+
+        let mut ballot_styles = Vec::new();
+        for (ix, cdf_ballot_style) in election.ballot_style.iter().enumerate() {
+            let label = self.ballot_style_label(cdf_ballot_style, &party_names, ix + 1);
+
+            let mut ordered_contests = Vec::new();
+            for content in &cdf_ballot_style.ordered_content {
+                content.collect_contests(&mut ordered_contests);
+            }
+
+            let mut ballot_style_contests = BTreeSet::new();
+            for ordered_contest in ordered_contests {
+                let Some(contest_id) = ordered_contest.contest_id.as_deref() else {
+                    continue;
+                };
+                if skipped_contests.contains(contest_id) {
+                    continue;
+                }
+                let Some(&contest_index) = contest_indices.get(contest_id) else {
+                    bail!(
+                        "The ballot style {label:?} refers to the unknown contest {contest_id:?}"
+                    );
+                };
+                ballot_style_contests.insert(contest_index);
+
+                let selection_ids = election
+                    .contest
+                    .iter()
+                    .filter(|cdf_contest| cdf_contest.id == contest_id)
+                    .flat_map(|cdf_contest| &cdf_contest.contest_selection)
+                    .map(|selection| &selection.id);
+                let ordered_selection_ids = &ordered_contest.ordered_contest_selection_ids;
+                if !ordered_selection_ids.is_empty()
+                    && !ordered_selection_ids.iter().eq(selection_ids)
+                {
+                    issues.push(ImportIssue::SelectionOrderIgnored {
+                        ballot_style: label.clone(),
+                        contest: contest_id.to_string(),
+                    });
+                }
+            }
+
+            ballot_styles.push(BallotStyle {
+                label,
+                contests: ballot_style_contests,
+                vendor_data: VendorData::default(),
+            });
+        }
+        if ballot_styles.is_empty() {
+            issues.push(ImportIssue::NoBallotStyles);
+            ballot_styles.push(BallotStyle {
+                label: "All contests".to_string(),
+                contests: contest_indices.values().copied().collect(),
+                vendor_data: VendorData::default(),
+            });
+        }
+
+        let mut election_manifest = ElectionManifest {
+            label: text(&election.name, "election name")?,
+            contests: contests.try_into()?,
+            ballot_styles: ballot_styles.try_into()?,
+            vendor_data: VendorData::default(),
+        };
+        election_manifest.normalize_labels();
+        election_manifest.label = crate::label::normalize_label(&election_manifest.label);
+        election_manifest
+            .validate()
+            .context("The imported election manifest is invalid")?;
+
+        Ok((election_manifest, issues))
+    }
+
+    /// Imports a contest. Returns an [`ImportIssue`] for an unsupported contest.
+    fn import_contest(
+        cdf_contest: &CdfContest,
+        candidate_names: &BTreeMap<&str, String>,
+        party_names: &BTreeMap<&str, String>,
+        text: &dyn Fn(&CdfText, &str) -> Result<String>,
+    ) -> Result<Result<Contest, ImportIssue>> {
+        let unsupported_vote_variation = |vote_variation: &str| {
+            Ok(Err(ImportIssue::UnsupportedVoteVariation {
+                contest: cdf_contest.id.clone(),
+                vote_variation: vote_variation.to_string(),
+            }))
+        };
+
+        let label = match &cdf_contest.ballot_title {
+            Some(ballot_title) => text(ballot_title, "ballot title")?,
+            None => cdf_contest.name.clone(),
+        };
+
+        let count_selections = cdf_contest.contest_selection.len();
+        if count_selections == 0 {
+            bail!("The contest has no selections");
+        }
+        let votes_allowed = cdf_contest.votes_allowed.unwrap_or(1) as usize;
+
+        // The selection limit and the vote limit of every option.
+        let (selection_limit, opt_vote_limit) = match cdf_contest.type_.as_str() {
+            "ElectionResults.CandidateContest" | "ElectionResults.PartyContest" => {
+                match cdf_contest.vote_variation.as_deref() {
+                    None | Some("plurality" | "n-of-m" | "majority" | "super-majority") => {
+                        (votes_allowed, None)
+                    }
+                    Some("approval") => (
+                        cdf_contest
+                            .votes_allowed
+                            .map_or(count_selections, |v| v as usize),
+                        None,
+                    ),
+                    Some("cumulative") => {
+                        let vote_limit = NonZeroU32::new(cdf_contest.votes_allowed.unwrap_or(1))
+                            .context("The contest allows no votes")?;
+                        (votes_allowed, Some(vote_limit))
+                    }
+                    Some("other") => {
+                        let other = cdf_contest.other_vote_variation.as_deref();
+                        return unsupported_vote_variation(other.unwrap_or("other"));
+                    }
+                    Some(vote_variation) => return unsupported_vote_variation(vote_variation),
+                }
+            }
+            "ElectionResults.BallotMeasureContest" | "ElectionResults.RetentionContest" => {
+                (1, None)
+            }
+            contest_type => {
+                return Ok(Err(ImportIssue::UnsupportedContestType {
+                    contest: cdf_contest.id.clone(),
+                    contest_type: contest_type.to_string(),
+                }));
+            }
+        };
+
+        let count_write_ins = cdf_contest
+            .contest_selection
+            .iter()
+            .filter(|s| s.is_write_in)
+            .count();
+        let mut write_in_ix = 0;
+
+        let mut options = Vec::with_capacity(count_selections);
+        for selection in &cdf_contest.contest_selection {
+            let option_label = if selection.is_write_in {
+                write_in_ix += 1;
+                if count_write_ins == 1 {
+                    "Write-in".to_string()
+                } else {
+                    format!("Write-in {write_in_ix}")
+                }
+            } else if let Some(selection_text) = &selection.selection {
+                text(selection_text, "ballot measure selection")?
+            } else if !selection.candidate_ids.is_empty() {
+                Self::names(&selection.candidate_ids, candidate_names, "candidate")?
+            } else if !selection.party_ids.is_empty() {
+                Self::names(&selection.party_ids, party_names, "party")?
+            } else {
+                bail!("The selection {:?} has no label", selection.id);
+            };
+
+            options.push(ContestOption {
+                label: option_label,
+                opt_vote_limit,
+                vendor_data: VendorData::new(selection.id.as_bytes())?,
+            });
+        }
+
+        Ok(Ok(Contest {
+            label,
+            selection_limit,
+            options: options.try_into()?,
+            vendor_data: VendorData::new(cdf_contest.id.as_bytes())?,
+        }))
+    }
+
+    /// The names of the candidates or parties with the given ids, e.g., of a ticket.
+    fn names(ids: &[String], names: &BTreeMap<&str, String>, what: &str) -> Result<String> {
+        let names = ids
+            .iter()
+            .map(|id| match names.get(id.as_str()) {
+                Some(name) => Ok(name.as_str()),
+                None => bail!("Unknown {what} {id:?}"),
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok(names.join(" and "))
+    }
+
+    /// The label of a ballot style. This is its external identifier, if any, and otherwise the
+    /// names of its geopolitical units and parties.
+    fn ballot_style_label(
+        &self,
+        cdf_ballot_style: &CdfBallotStyle,
+        party_names: &BTreeMap<&str, String>,
+        ballot_style_ix1: usize,
+    ) -> String {
+        if let Some(external_identifier) = cdf_ballot_style.external_identifier.first() {
+            return external_identifier.value.clone();
+        }
+
+        let gp_unit_names: Vec<&str> = cdf_ballot_style
+            .gp_unit_ids
+            .iter()
+            .map(|id| {
+                self.gp_unit
+                    .iter()
+                    .find(|gp_unit| &gp_unit.id == id)
+                    .and_then(|gp_unit| gp_unit.name.as_deref())
+                    .unwrap_or(id.as_str())
+            })
+            .collect();
+        let mut label = if gp_unit_names.is_empty() {
+            format!("Ballot Style {ballot_style_ix1}")
+        } else {
+            gp_unit_names.join(", ")
+        };
+
+        let party_names: Vec<&str> = cdf_ballot_style
+            .party_ids
+            .iter()
+            .map(|id| party_names.get(id.as_str()).map_or(id.as_str(), |s| s))
+            .collect();
+        if !party_names.is_empty() {
+            label = format!("{label} ({})", party_names.join(", "));
+        }
+
+        label
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod test {
+    use super::*;
+
+    const ELECTION_REPORT: &str = r#"{
+        "@type": "ElectionResults.ElectionReport",
+        "Format": "precinct-level",
+        "GeneratedDate": "2024-03-01T12:00:00Z",
+        "Issuer": "Imaginaria Board of Elections",
+        "IssuerAbbreviation": "IBE",
+        "SequenceStart": 1,
+        "SequenceEnd": 1,
+        "Status": "pre-election",
+        "VendorApplicationId": "example",
+        "GpUnit": [
+            {"@type": "ElectionResults.ReportingUnit", "@id": "gp-silverspire", "Name": "Silvërspîre County", "Type": "county"},
+            {"@type": "ElectionResults.ReportingUnit", "@id": "gp-precinct-1", "Name": "Precinct 1", "Type": "precinct"}
+        ],
+        "Party": [
+            {"@type": "ElectionResults.Party", "@id": "party-stormhaven", "Name": {"@type": "ElectionResults.InternationalizedText", "Text": [{"@type": "ElectionResults.LanguageString", "Content": "Stormhaven Party", "Language": "en"}]}}
+        ],
+        "Election": [{
+            "@type": "ElectionResults.Election",
+            "Name": {"@type": "ElectionResults.InternationalizedText", "Text": [
+                {"@type": "ElectionResults.LanguageString", "Content": "Elección general", "Language": "es"},
+                {"@type": "ElectionResults.LanguageString", "Content": "General Election", "Language": "en"}
+            ]},
+            "ElectionScopeId": "gp-silverspire",
+            "StartDate": "2024-11-05",
+            "EndDate": "2024-11-05",
+            "Type": "general",
+            "Candidate": [
+                {"@type": "ElectionResults.Candidate", "@id": "cand-1", "BallotName": {"@type": "ElectionResults.InternationalizedText", "Text": [{"@type": "ElectionResults.LanguageString", "Content": "Aelin Windrider", "Language": "en"}]}},
+                {"@type": "ElectionResults.Candidate", "@id": "cand-2", "BallotName": {"@type": "ElectionResults.InternationalizedText", "Text": [{"@type": "ElectionResults.LanguageString", "Content": "Borin Stonehand", "Language": "en"}]}},
+                {"@type": "ElectionResults.Candidate", "@id": "cand-3", "BallotName": {"@type": "ElectionResults.InternationalizedText", "Text": [{"@type": "ElectionResults.LanguageString", "Content": "Cyra Moonshadow", "Language": "en"}]}}
+            ],
+            "Contest": [
+                {
+                    "@type": "ElectionResults.CandidateContest",
+                    "@id": "contest-council",
+                    "Name": "Council",
+                    "BallotTitle": {"@type": "ElectionResults.InternationalizedText", "Text": [{"@type": "ElectionResults.LanguageString", "Content": "County Council", "Language": "en"}]},
+                    "ElectionDistrictId": "gp-silverspire",
+                    "VoteVariation": "n-of-m",
+                    "VotesAllowed": 2,
+                    "ContestSelection": [
+                        {"@type": "ElectionResults.CandidateSelection", "@id": "sel-1", "CandidateIds": ["cand-1"]},
+                        {"@type": "ElectionResults.CandidateSelection", "@id": "sel-2", "CandidateIds": ["cand-2"]},
+                        {"@type": "ElectionResults.CandidateSelection", "@id": "sel-3", "CandidateIds": ["cand-3"]},
+                        {"@type": "ElectionResults.CandidateSelection", "@id": "sel-wi", "IsWriteIn": true}
+                    ]
+                },
+                {
+                    "@type": "ElectionResults.CandidateContest",
+                    "@id": "contest-mayor",
+                    "Name": "Mayor",
+                    "ElectionDistrictId": "gp-silverspire",
+                    "VoteVariation": "rcv",
+                    "VotesAllowed": 3,
+                    "ContestSelection": [
+                        {"@type": "ElectionResults.CandidateSelection", "@id": "sel-4", "CandidateIds": ["cand-1"]},
+                        {"@type": "ElectionResults.CandidateSelection", "@id": "sel-5", "CandidateIds": ["cand-2"]}
+                    ]
+                },
+                {
+                    "@type": "ElectionResults.BallotMeasureContest",
+                    "@id": "contest-measure",
+                    "Name": "Measure A",
+                    "ElectionDistrictId": "gp-silverspire",
+                    "ContestSelection": [
+                        {"@type": "ElectionResults.BallotMeasureSelection", "@id": "sel-yes", "Selection": {"@type": "ElectionResults.InternationalizedText", "Text": [{"@type": "ElectionResults.LanguageString", "Content": "Yes", "Language": "en"}]}},
+                        {"@type": "ElectionResults.BallotMeasureSelection", "@id": "sel-no", "Selection": {"@type": "ElectionResults.InternationalizedText", "Text": [{"@type": "ElectionResults.LanguageString", "Content": "No", "Language": "en"}]}}
+                    ]
+                }
+            ],
+            "BallotStyle": [
+                {
+                    "@type": "ElectionResults.BallotStyle",
+                    "GpUnitIds": ["gp-precinct-1"],
+                    "OrderedContent": [
+                        {"@type": "ElectionResults.OrderedContest", "ContestId": "contest-council", "OrderedContestSelectionIds": ["sel-3", "sel-1", "sel-2", "sel-wi"]},
+                        {"@type": "ElectionResults.OrderedHeader", "HeaderId": "header-local", "OrderedContent": [
+                            {"@type": "ElectionResults.OrderedContest", "ContestId": "contest-mayor"},
+                            {"@type": "ElectionResults.OrderedContest", "ContestId": "contest-measure"}
+                        ]}
+                    ]
+                }
+            ]
+        }]
+    }"#;
+
+    #[test]
+    fn test_import_election_report() {
+        let (election_manifest, issues) =
+            import_election_report(&mut ELECTION_REPORT.as_bytes(), "en").unwrap();
+
+        assert_eq!(election_manifest.label, "General Election");
+        assert_eq!(election_manifest.contests.len(), 2);
+
+        let contest = election_manifest
+            .contests
+            .get(ContestIndex::from_one_based_index(1).unwrap())
+            .unwrap();
+        assert_eq!(contest.label, "County Council");
+        assert_eq!(contest.selection_limit, 2);
+        assert_eq!(contest.vendor_data.as_bytes(), b"contest-council");
+        let labels: Vec<_> = contest
+            .options
+            .indices()
+            .map(|ix| contest.options.get(ix).unwrap().label.as_str())
+            .collect();
+        assert_eq!(
+            labels,
+            [
+                "Aelin Windrider",
+                "Borin Stonehand",
+                "Cyra Moonshadow",
+                "Write-in"
+            ]
+        );
+
+        let contest = election_manifest
+            .contests
+            .get(ContestIndex::from_one_based_index(2).unwrap())
+            .unwrap();
+        assert_eq!(contest.label, "Measure A");
+        assert_eq!(contest.selection_limit, 1);
+
+        assert_eq!(election_manifest.ballot_styles.len(), 1);
+        let ballot_style = election_manifest
+            .ballot_styles
+            .indices()
+            .next()
+            .and_then(|ix| election_manifest.ballot_styles.get(ix))
+            .unwrap();
+        assert_eq!(ballot_style.label, "Precinct 1");
+        assert_eq!(ballot_style.contests.len(), 2);
+
+        assert_eq!(
+            issues,
+            [
+                ImportIssue::UnsupportedVoteVariation {
+                    contest: "contest-mayor".to_string(),
+                    vote_variation: "rcv".to_string(),
+                },
+                ImportIssue::SelectionOrderIgnored {
+                    ballot_style: "Precinct 1".to_string(),
+                    contest: "contest-council".to_string(),
+                },
+            ]
+        );
+
+        let (election_manifest, _) =
+            import_election_report(&mut ELECTION_REPORT.as_bytes(), "es").unwrap();
+        assert_eq!(election_manifest.label, "Elección general");
+    }
+
+    #[test]
+    fn test_import_election_report_invalid() {
+        let report = ELECTION_REPORT.replace(
+            r#""CandidateIds": ["cand-3"]"#,
+            r#""CandidateIds": ["cand-9"]"#,
+        );
+        assert!(import_election_report(&mut report.as_bytes(), "en").is_err());
+
+        let report = ELECTION_REPORT.replace(
+            r#""ContestId": "contest-measure""#,
+            r#""ContestId": "contest-sheriff""#,
+        );
+        assert!(import_election_report(&mut report.as_bytes(), "en").is_err());
+    }
+}
@@ -34,3 +34,23 @@ pub fn encrypted(
     let nonce = eg_h(&header.hashes_ext.h_e, &v);
     FieldElement::from_bytes_be(nonce.0.as_slice(), field)
 }
+
+/// Generates the nonce for the contest data of encrypted ballots (Equation 50)
+///
+///  ξ = H(H_E;20,ξ_B,Λ_i,"contest data")
+///
+pub fn contest_data(
+    header: &PreVotingData,
+    primary_nonce: &[u8],
+    label_i: ContestIndex,
+) -> FieldElement {
+    let field = &header.parameters.fixed_parameters.field;
+    let mut v = vec![0x20];
+
+    v.extend_from_slice(primary_nonce);
+    v.extend_from_slice(&label_i.get_one_based_u32().to_be_bytes());
+    v.extend_from_slice(b"contest data");
+
+    let nonce = eg_h(&header.hashes_ext.h_e, &v);
+    FieldElement::from_bytes_be(nonce.0.as_slice(), field)
+}
@@ -0,0 +1,575 @@
+// Copyright (C) Microsoft Corporation. All rights reserved.
+
+#![deny(clippy::unwrap_used)]
+#![deny(clippy::expect_used)]
+#![deny(clippy::panic)]
+#![deny(clippy::manual_assert)]
+
+//! This module provides the complaint and dispute resolution flow for the key ceremony.
+//! For more details see Section `3.2.2` of the Electionguard specification `2.0.0`.
+//!
+//! If a recipient cannot decrypt or validate a [`GuardianEncryptedShare`], it files a signed
+//! [`ShareComplaint`]. The dealer answers by revealing the [`GuardianEncryptionSecret`] used to
+//! encrypt the share. Anybody can then [adjudicate](ShareDispute::adjudicate) the dispute using
+//! only public data, and the resulting [`ShareDispute`] is published in the election record.
+
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use util::{
+    algebra::{FieldElement, GroupElement},
+    csprng::Csprng,
+};
+
+use crate::{
+    election_parameters::ElectionParameters,
+    fixed_parameters::FixedParameters,
+    guardian::GuardianIndex,
+    guardian_public_key::GuardianPublicKey,
+    guardian_secret_key::GuardianSecretKey,
+    guardian_share::{DecryptionError, GuardianEncryptedShare, GuardianEncryptionSecret},
+    hash::eg_h,
+    hashes::ParameterBaseHash,
+};
+
+/// The reason for a [`ShareComplaint`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ComplaintReason {
+    /// The MAC of the encrypted share does not verify.
+    InvalidMAC,
+    /// The decrypted share does not validate against the dealer's public key (Equation `21`).
+    InvalidShare,
+}
+
+impl ComplaintReason {
+    fn to_byte(self) -> u8 {
+        match self {
+            ComplaintReason::InvalidMAC => 0x01,
+            ComplaintReason::InvalidShare => 0x02,
+        }
+    }
+}
+
+/// A Schnorr signature by a guardian using its public key `K_{i,0}`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ComplaintSignature {
+    /// Challenge
+    pub challenge: FieldElement,
+    /// Response
+    pub response: FieldElement,
+}
+
+/// A complaint by the recipient of a [`GuardianEncryptedShare`] about the dealer.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ShareComplaint {
+    /// The recipient of the share, who files the complaint
+    pub complainer: GuardianIndex,
+    /// The dealer of the share
+    pub dealer: GuardianIndex,
+    /// Why the share was rejected
+    pub reason: ComplaintReason,
+    /// The encrypted share as received by the complainer
+    pub encrypted_share: GuardianEncryptedShare,
+    /// The complainer's signature on the complaint
+    pub signature: ComplaintSignature,
+}
+
+/// The outcome of a [`ShareDispute`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DisputeVerdict {
+    /// The dealer did not reveal the encryption secret, or the revealed secret does not match the
+    /// encrypted share or the dealer's public key.
+    DealerAtFault,
+    /// The revealed secret shows that the encrypted share is valid, so the complaint is dismissed.
+    ComplainerAtFault,
+}
+
+/// An adjudicated dispute about a [`GuardianEncryptedShare`], as published in the election record.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ShareDispute {
+    /// The complaint
+    pub complaint: ShareComplaint,
+    /// The encryption secret revealed by the dealer. `None` if the dealer did not answer.
+    pub revealed_secret: Option<GuardianEncryptionSecret>,
+    /// The outcome of the adjudication
+    pub verdict: DisputeVerdict,
+}
+
+/// Represents errors occurring while filing or adjudicating a [`ShareComplaint`].
+#[derive(Error, Debug)]
+pub enum ComplaintError {
+    /// Occurs if a complaint is filed for a decryption error other than an invalid MAC or share.
+    #[error("A complaint can only be filed for an invalid MAC or share, not for: {0}")]
+    NotComplainable(DecryptionError),
+    /// Occurs if the complainer is not the recipient of the encrypted share.
+    #[error("Guardian {complainer} is not the recipient {recipient} of the encrypted share.")]
+    NotRecipient {
+        complainer: GuardianIndex,
+        recipient: GuardianIndex,
+    },
+    /// Occurs if the dealer named in the complaint did not deal the encrypted share.
+    #[error("Guardian {dealer} is not the dealer {share_dealer} of the encrypted share.")]
+    NotDealer {
+        dealer: GuardianIndex,
+        share_dealer: GuardianIndex,
+    },
+    /// Occurs if the public key of a guardian involved in the complaint is missing.
+    #[error("The public key of guardian {0} is missing.")]
+    MissingPublicKey(GuardianIndex),
+    /// Occurs if the signature of the complaint does not verify.
+    #[error("The signature of the complaint by guardian {0} does not verify.")]
+    InvalidSignature(GuardianIndex),
+    /// Occurs if the disputed share does not carry a valid signature of its dealer, i.e., it is
+    /// not the share the dealer sent.
+    #[error("The disputed share is not signed by dealer {0}.")]
+    UnsignedShare(GuardianIndex),
+    /// Occurs if the revealed secret is not for the disputed share.
+    #[error(
+        "The revealed secret is not for the share from guardian {dealer} to guardian {recipient}."
+    )]
+    SecretMismatch {
+        dealer: GuardianIndex,
+        recipient: GuardianIndex,
+    },
+    /// Occurs if the recorded verdict of a dispute differs from the adjudicated one.
+    #[error(
+        "The recorded verdict {recorded:?} differs from the adjudicated verdict {computed:?}."
+    )]
+    VerdictMismatch {
+        recorded: DisputeVerdict,
+        computed: DisputeVerdict,
+    },
+}
+
+impl ShareComplaint {
+    /// This function computes the challenge of the complaint signature.
+    ///
+    /// The arguments are
+    /// - `fixed_parameters` - the fixed parameters
+    /// - `complainer` - the complainer's index
+    /// - `dealer` - the dealer's index
+    /// - `reason` - the reason of the complaint
+    /// - `encrypted_share` - the disputed share
+    /// - `capital_k` - the complainer's public key `K_{l,0}`
+    /// - `h` - the commit message
+    fn challenge(
+        fixed_parameters: &FixedParameters,
+        complainer: GuardianIndex,
+        dealer: GuardianIndex,
+        reason: ComplaintReason,
+        encrypted_share: &GuardianEncryptedShare,
+        capital_k: &GroupElement,
+        h: &GroupElement,
+    ) -> FieldElement {
+        let h_p = ParameterBaseHash::compute(fixed_parameters).h_p;
+        let group = &fixed_parameters.group;
+        // v = b("share_complaint",15) | b(l,4) | b(i,4) | reason | b(C_{i,l,0},512)
+        //     | C_{i,l,1} | C_{i,l,2} | b(K_l,512) | b(h,512)
+        let mut v = b"share_complaint".to_vec();
+        v.extend_from_slice(complainer.get_one_based_u32().to_be_bytes().as_slice());
+        v.extend_from_slice(dealer.get_one_based_u32().to_be_bytes().as_slice());
+        v.push(reason.to_byte());
+        v.extend_from_slice(encrypted_share.c0.to_be_bytes_left_pad(group).as_slice());
+        v.extend_from_slice(encrypted_share.c1.0.as_slice());
+        v.extend_from_slice(encrypted_share.c2.0.as_slice());
+        v.extend_from_slice(capital_k.to_be_bytes_left_pad(group).as_slice());
+        v.extend_from_slice(h.to_be_bytes_left_pad(group).as_slice());
+        let c_bytes = eg_h(&h_p, &v);
+        FieldElement::from_bytes_be(c_bytes.0.as_slice(), &fixed_parameters.field)
+    }
+
+    /// This function files a signed complaint about an encrypted share that failed to decrypt or
+    /// validate.
+    ///
+    /// The arguments are
+    /// - `csprng` - secure randomness generator
+    /// - `election_parameters` - the election parameters
+    /// - `complainer_secret_key` - the recipient's [`GuardianSecretKey`]
+    /// - `encrypted_share` - the share that was rejected
+    /// - `error` - the error returned by [`GuardianEncryptedShare::decrypt_and_validate`]
+    pub fn new(
+        csprng: &mut Csprng,
+        election_parameters: &ElectionParameters,
+        complainer_secret_key: &GuardianSecretKey,
+        encrypted_share: &GuardianEncryptedShare,
+        error: DecryptionError,
+    ) -> Result<Self, ComplaintError> {
+        let reason = match error {
+            DecryptionError::InvalidMAC => ComplaintReason::InvalidMAC,
+            DecryptionError::InvalidShare => ComplaintReason::InvalidShare,
+            e => return Err(ComplaintError::NotComplainable(e)),
+        };
+
+        let complainer = complainer_secret_key.i;
+        if encrypted_share.recipient != complainer {
+            return Err(ComplaintError::NotRecipient {
+                complainer,
+                recipient: encrypted_share.recipient,
+            });
+        }
+
+        let fixed_parameters = &election_parameters.fixed_parameters;
+        let field = &fixed_parameters.field;
+        let dealer = encrypted_share.dealer;
+        let capital_k = &complainer_secret_key.coefficient_commitments.0[0].0;
+
+        let u = field.random_field_elem(csprng);
+        let h = fixed_parameters.group.g_exp_secret(&u);
+        let c = Self::challenge(
+            fixed_parameters,
+            complainer,
+            dealer,
+            reason,
+            encrypted_share,
+            capital_k,
+            &h,
+        );
+        let v = u.sub(&c.mul(complainer_secret_key.secret_s(), field), field);
+
+        Ok(ShareComplaint {
+            complainer,
+            dealer,
+            reason,
+            encrypted_share: encrypted_share.clone(),
+            signature: ComplaintSignature {
+                challenge: c,
+                response: v,
+            },
+        })
+    }
+
+    /// This function verifies that the complaint is consistent and signed by the complainer.
+    ///
+    /// The arguments are
+    /// - `self` - the complaint
+    /// - `fixed_parameters` - the fixed parameters
+    /// - `complainer_public_key` - the complainer's [`GuardianPublicKey`]
+    pub fn validate(
+        &self,
+        fixed_parameters: &FixedParameters,
+        complainer_public_key: &GuardianPublicKey,
+    ) -> Result<(), ComplaintError> {
+        if self.encrypted_share.recipient != self.complainer
+            || complainer_public_key.i != self.complainer
+        {
+            return Err(ComplaintError::NotRecipient {
+                complainer: self.complainer,
+                recipient: self.encrypted_share.recipient,
+            });
+        }
+        if self.encrypted_share.dealer != self.dealer {
+            return Err(ComplaintError::NotDealer {
+                dealer: self.dealer,
+                share_dealer: self.encrypted_share.dealer,
+            });
+        }
+
+        let group = &fixed_parameters.group;
+        let field = &fixed_parameters.field;
+        let capital_k = complainer_public_key.public_key_k_i_0();
+        let signature = &self.signature;
+
+        if !signature.response.is_valid(field) {
+            return Err(ComplaintError::InvalidSignature(self.complainer));
+        }
+        let h = group
+            .g_exp(&signature.response)
+            .mul(&capital_k.exp(&signature.challenge, group), group);
+        let c = Self::challenge(
+            fixed_parameters,
+            self.complainer,
+            self.dealer,
+            self.reason,
+            &self.encrypted_share,
+            capital_k,
+            &h,
+        );
+        if c != signature.challenge {
+            return Err(ComplaintError::InvalidSignature(self.complainer));
+        }
+
+        Ok(())
+    }
+}
+
+impl ShareDispute {
+    /// This function adjudicates a complaint using only public data.
+    ///
+    /// The arguments are
+    /// - `election_parameters` - the election parameters
+    /// - `guardian_public_keys` - the public keys of the guardians
+    /// - `complaint` - the complaint
+    /// - `revealed_secret` - the [`GuardianEncryptionSecret`] revealed by the dealer, `None` if
+    ///   the dealer did not answer the complaint
+    ///
+    /// Returns an error if the complaint itself is invalid, e.g., if its signature or the dealer's
+    /// signature on the disputed share does not verify.
+    /// The dealer is at fault if it did not reveal the secret, or if the revealed secret does not
+    /// reproduce the encrypted share or does not validate against the dealer's public key.
+    /// Otherwise the complaint is dismissed and the complainer is at fault.
+    pub fn adjudicate(
+        election_parameters: &ElectionParameters,
+        guardian_public_keys: &[GuardianPublicKey],
+        complaint: ShareComplaint,
+        revealed_secret: Option<GuardianEncryptionSecret>,
+    ) -> Result<Self, ComplaintError> {
+        let verdict = Self::decide(
+            election_parameters,
+            guardian_public_keys,
+            &complaint,
+            revealed_secret.as_ref(),
+        )?;
+
+        Ok(ShareDispute {
+            complaint,
+            revealed_secret,
+            verdict,
+        })
+    }
+
+    /// This function re-adjudicates a published dispute and checks that the recorded verdict is
+    /// correct.
+    ///
+    /// The arguments are
+    /// - `self` - the dispute
+    /// - `election_parameters` - the election parameters
+    /// - `guardian_public_keys` - the public keys of the guardians
+    pub fn validate(
+        &self,
+        election_parameters: &ElectionParameters,
+        guardian_public_keys: &[GuardianPublicKey],
+    ) -> Result<(), ComplaintError> {
+        let verdict = Self::decide(
+            election_parameters,
+            guardian_public_keys,
+            &self.complaint,
+            self.revealed_secret.as_ref(),
+        )?;
+
+        if verdict == self.verdict {
+            Ok(())
+        } else {
+            Err(ComplaintError::VerdictMismatch {
+                recorded: self.verdict,
+                computed: verdict,
+            })
+        }
+    }
+
+    /// Reads a [`ShareDispute`] from a [`std::io::Read`].
+    pub fn from_stdioread(stdioread: &mut dyn std::io::Read) -> anyhow::Result<Self> {
+        serde_json::from_reader(stdioread).context("Reading ShareDispute")
+    }
+
+    /// Writes a [`ShareDispute`] to a [`std::io::Write`].
+    pub fn to_stdiowrite(&self, stdiowrite: &mut dyn std::io::Write) -> anyhow::Result<()> {
+        let mut ser = serde_json::Serializer::pretty(stdiowrite);
+
+        self.serialize(&mut ser)
+            .map_err(Into::<anyhow::Error>::into)
+            .and_then(|_| ser.into_inner().write_all(b"\n").map_err(Into::into))
+            .context("Writing ShareDispute")
+    }
+
+    fn decide(
+        election_parameters: &ElectionParameters,
+        guardian_public_keys: &[GuardianPublicKey],
+        complaint: &ShareComplaint,
+        revealed_secret: Option<&GuardianEncryptionSecret>,
+    ) -> Result<DisputeVerdict, ComplaintError> {
+        let find_public_key = |i: GuardianIndex| {
+            guardian_public_keys
+                .iter()
+                .find(|pk| pk.i == i)
+                .ok_or(ComplaintError::MissingPublicKey(i))
+        };
+        let complainer_public_key = find_public_key(complaint.complainer)?;
+        let dealer_public_key = find_public_key(complaint.dealer)?;
+
+        let fixed_parameters = &election_parameters.fixed_parameters;
+        complaint.validate(fixed_parameters, complainer_public_key)?;
+        if !complaint
+            .encrypted_share
+            .verify_signature(fixed_parameters, dealer_public_key)
+        {
+            return Err(ComplaintError::UnsignedShare(complaint.dealer));
+        }
+
+        let Some(secret) = revealed_secret else {
+            return Ok(DisputeVerdict::DealerAtFault);
+        };
+        if secret.dealer != complaint.dealer || secret.recipient != complaint.complainer {
+            return Err(ComplaintError::SecretMismatch {
+                dealer: complaint.dealer,
+                recipient: complaint.complainer,
+            });
+        }
+
+        let share_is_valid = complaint.encrypted_share.public_validation(
+            election_parameters,
+            dealer_public_key,
+            complainer_public_key,
+            secret,
+        );
+
+        Ok(if share_is_valid {
+            DisputeVerdict::ComplainerAtFault
+        } else {
+            DisputeVerdict::DealerAtFault
+        })
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod test {
+    use util::csprng::Csprng;
+
+    use super::*;
+    use crate::{
+        example_election_parameters::example_election_parameters,
+        guardian_share::GuardianEncryptedShare, hash::HValue,
+    };
+
+    #[test]
+    fn test_share_dispute() {
+        let mut csprng = Csprng::new(b"test_share_dispute");
+        let election_parameters = example_election_parameters();
+
+        let guardian_secret_keys = election_parameters
+            .varying_parameters
+            .each_guardian_i()
+            .take(2)
+            .map(|i| GuardianSecretKey::generate(&mut csprng, &election_parameters, i, None))
+            .collect::<Vec<_>>();
+        let guardian_public_keys = guardian_secret_keys
+            .iter()
+            .map(|sk| sk.make_public_key())
+            .collect::<Vec<_>>();
+        let (dealer_sk, recipient_sk) = (&guardian_secret_keys[0], &guardian_secret_keys[1]);
+
+        // An honest share cannot be complained about successfully.
+        let honest = GuardianEncryptedShare::encrypt(
+            &mut csprng,
+            &election_parameters,
+            dealer_sk,
+            &guardian_public_keys[1],
+        );
+        let complaint = ShareComplaint::new(
+            &mut csprng,
+            &election_parameters,
+            recipient_sk,
+            &honest.ciphertext,
+            DecryptionError::InvalidShare,
+        )
+        .unwrap();
+        let dispute = ShareDispute::adjudicate(
+            &election_parameters,
+            &guardian_public_keys,
+            complaint.clone(),
+            Some(honest.secret.clone()),
+        )
+        .unwrap();
+        assert_eq!(dispute.verdict, DisputeVerdict::ComplainerAtFault);
+        assert!(dispute
+            .validate(&election_parameters, &guardian_public_keys)
+            .is_ok());
+
+        // A dispute survives writing and reading it as an artifact.
+        let mut buf = Vec::new();
+        dispute.to_stdiowrite(&mut buf).unwrap();
+        let dispute = ShareDispute::from_stdioread(&mut buf.as_slice()).unwrap();
+        assert!(dispute
+            .validate(&election_parameters, &guardian_public_keys)
+            .is_ok());
+
+        // A dealer that does not answer is at fault.
+        let dispute = ShareDispute::adjudicate(
+            &election_parameters,
+            &guardian_public_keys,
+            complaint.clone(),
+            None,
+        )
+        .unwrap();
+        assert_eq!(dispute.verdict, DisputeVerdict::DealerAtFault);
+
+        // A complainer cannot dispute a share other than the one the dealer signed.
+        let mut tampered = honest.ciphertext.clone();
+        tampered.c1 = HValue([0xAB; 32]);
+        let tampered_complaint = ShareComplaint::new(
+            &mut csprng,
+            &election_parameters,
+            recipient_sk,
+            &tampered,
+            DecryptionError::InvalidMAC,
+        )
+        .unwrap();
+        assert!(matches!(
+            ShareDispute::adjudicate(
+                &election_parameters,
+                &guardian_public_keys,
+                tampered_complaint.clone(),
+                Some(honest.secret.clone()),
+            ),
+            Err(ComplaintError::UnsignedShare(_))
+        ));
+        let tampered_dispute = ShareDispute {
+            complaint: tampered_complaint,
+            revealed_secret: Some(honest.secret.clone()),
+            verdict: DisputeVerdict::DealerAtFault,
+        };
+        assert!(matches!(
+            tampered_dispute.validate(&election_parameters, &guardian_public_keys),
+            Err(ComplaintError::UnsignedShare(_))
+        ));
+
+        // A corrupted share signed by a dishonest dealer is detected by the recipient and the
+        // dealer is at fault.
+        let mut corrupted = honest.ciphertext.clone();
+        corrupted.c1 = HValue([0xAB; 32]);
+        corrupted.sign(
+            &mut csprng,
+            &election_parameters.fixed_parameters,
+            dealer_sk,
+        );
+        let error = corrupted
+            .decrypt_and_validate(&election_parameters, &guardian_public_keys[0], recipient_sk)
+            .unwrap_err();
+        assert!(matches!(error, DecryptionError::InvalidMAC));
+        let complaint = ShareComplaint::new(
+            &mut csprng,
+            &election_parameters,
+            recipient_sk,
+            &corrupted,
+            error,
+        )
+        .unwrap();
+        let mut dispute = ShareDispute::adjudicate(
+            &election_parameters,
+            &guardian_public_keys,
+            complaint.clone(),
+            Some(honest.secret.clone()),
+        )
+        .unwrap();
+        assert_eq!(dispute.verdict, DisputeVerdict::DealerAtFault);
+
+        // A wrongly recorded verdict is detected.
+        dispute.verdict = DisputeVerdict::ComplainerAtFault;
+        assert!(dispute
+            .validate(&election_parameters, &guardian_public_keys)
+            .is_err());
+
+        // A complaint with a forged signature is rejected.
+        let mut forged = complaint;
+        forged.reason = ComplaintReason::InvalidShare;
+        assert!(matches!(
+            ShareDispute::adjudicate(
+                &election_parameters,
+                &guardian_public_keys,
+                forged,
+                Some(honest.secret),
+            ),
+            Err(ComplaintError::InvalidSignature(_))
+        ));
+    }
+}
@@ -161,6 +161,23 @@ mod test {
         assert!(fixed_params.validate(&mut csprng).is_ok());
     }
 
+    /// Verify that `(p-1)/(2q)` is a prime larger than `2^64`, on which the soundness of
+    /// [`util::multi_exp::batch_is_valid`] relies.
+    #[cfg(not(debug_assertions))] // This test is too slow without optimizations.
+    #[test]
+    fn standard_parameters_cofactor() {
+        let mut csprng = util::csprng::Csprng::new(b"test::standard_parameters_cofactor");
+
+        let group = &STANDARD_PARAMETERS.group;
+        let p_minus_1 = group.modulus() - 1u8;
+        let two_q = group.order() * 2u8;
+        assert_eq!(&p_minus_1 % &two_q, BigUint::from(0u8));
+
+        let r = p_minus_1 / two_q;
+        assert!(r.bits() > 64);
+        assert!(util::prime::is_prime(&r, &mut csprng));
+    }
+
     /// Verify that `pub static STANDARD_PARAMETERS` reflect the latest version (currently v2.0).
     #[test]
     fn standard_parameters_pub_static() {
@@ -0,0 +1,851 @@
+// Copyright (C) Microsoft Corporation. All rights reserved.
+
+#![deny(clippy::unwrap_used)]
+#![deny(clippy::expect_used)]
+#![deny(clippy::panic)]
+#![deny(clippy::manual_assert)]
+
+//! This module provides the [`TallyDecryptionCeremony`], which drives the verifiable decryption
+//! of a whole [`EncryptedTally`] by a quorum of guardians. For more details see Section `3.6` of
+//! the Electionguard specification `2.0.0`.
+//!
+//! The ceremony consists of two rounds.
+//! 1. Every participating guardian computes, for every option of every contest, a
+//!    [`DecryptionShare`] together with a [`DecryptionProofCommitShare`] and sends them as a
+//!    [`GuardianTallyDecryptionShares`] message. The secret state of the commitments is kept by
+//!    the guardian as a [`GuardianTallyDecryptionState`].
+//! 2. Once the shares of all participating guardians are known, every participating guardian
+//!    computes its response shares and sends them as a [`GuardianTallyDecryptionResponses`]
+//!    message.
+//!
+//! Finally, the ceremony combines all messages into a [`DecryptedTally`]. The same ceremony also
+//! decrypts the contest data of challenged ballots, see
+//! [`TallyDecryptionCeremony::finalize_contest_data`].
+//!
+//! If not all guardians are available, the ceremony can be restricted to a [`DecryptionQuorum`]
+//! selected from the present guardians. Only the participants of the quorum may then take part,
+//! and their decryption shares are combined with the quorum's Lagrange coefficients.
+
+use std::collections::BTreeMap;
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use util::csprng::Csprng;
+
+use crate::{
+    contest_data::ContestDataDecryption,
+    decryption_quorum::{DecryptionQuorum, QuorumError},
+    election_manifest::ContestIndex,
+    election_record::{
+        DecryptedTally, EncryptedTally, OptionDecryptionShares, PreVotingData,
+        TallyDecryptionShares,
+    },
+    guardian::GuardianIndex,
+    guardian_public_key::GuardianPublicKey,
+    guardian_share::GuardianSecretKeyShare,
+    hash::{eg_h, HValue},
+    joint_election_public_key::Ciphertext,
+    verifiable_decryption::{
+        CombineProofError, CombinedDecryptionShare, DecryptionError, DecryptionProof,
+        DecryptionProofCommitShare, DecryptionProofResponseShare, DecryptionProofStateShare,
+        DecryptionShare, DecryptionShareResult, ResponseShareError, ShareCombinationError,
+        VerifiableDecryption,
+    },
+};
+
+/// The first-round message of a guardian in the [`TallyDecryptionCeremony`], i.e., the
+/// decryption share and proof commitment share for every option of every contest.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GuardianTallyDecryptionShares {
+    /// The guardian's index
+    pub i: GuardianIndex,
+    /// For every contest one share per option
+    pub shares: BTreeMap<ContestIndex, Vec<DecryptionShareResult>>,
+}
+
+/// The secret state a guardian keeps between the two rounds of the
+/// [`TallyDecryptionCeremony`], i.e., the commitment state for every option of every contest.
+///
+/// This must not be published, and it must be used for a single second-round message only:
+/// [`GuardianTallyDecryptionResponses::generate`] consumes it. The state is bound to the
+/// guardian's own first-round message, so it cannot be used with a ceremony in which the
+/// guardian's commitments differ. The commit states are zeroized on drop and redacted in the
+/// `Debug` output.
+#[derive(Serialize, Deserialize)]
+pub struct GuardianTallyDecryptionState {
+    /// The guardian's index
+    pub i: GuardianIndex,
+    /// The hash of the guardian's commitments in its first-round message, see
+    /// [`GuardianTallyDecryptionShares::commitments_hash`]
+    pub h_commitments: HValue,
+    /// For every contest one commit state per option
+    pub states: BTreeMap<ContestIndex, Vec<DecryptionProofStateShare>>,
+}
+
+impl std::fmt::Debug for GuardianTallyDecryptionState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("GuardianTallyDecryptionState")
+            .field("i", &self.i)
+            .field("h_commitments", &self.h_commitments)
+            .finish_non_exhaustive()
+    }
+}
+
+/// The second-round message of a guardian in the [`TallyDecryptionCeremony`], i.e., the proof
+/// response share for every option of every contest.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GuardianTallyDecryptionResponses {
+    /// The guardian's index
+    pub i: GuardianIndex,
+    /// For every contest one response share per option
+    pub responses: BTreeMap<ContestIndex, Vec<DecryptionProofResponseShare>>,
+}
+
+/// Represents errors occurring during the [`TallyDecryptionCeremony`].
+#[derive(Error, Debug)]
+pub enum TallyDecryptionError {
+    /// Occurs if a guardian's message does not have one entry per option of every contest of the
+    /// encrypted tally.
+    #[error("The message of guardian {0} does not match the shape of the encrypted tally.")]
+    ShapeMismatch(GuardianIndex),
+    /// Occurs if a guardian submits a message for the same round twice.
+    #[error("Guardian {0} has already submitted a message for this round.")]
+    DuplicateGuardian(GuardianIndex),
+    /// Occurs if the entries of a guardian's message belong to a different guardian.
+    #[error("The message of guardian {0} contains entries of another guardian.")]
+    IndexMismatch(GuardianIndex),
+    /// Occurs if decryption shares are submitted after the second round has started.
+    #[error("Guardian {0} submitted decryption shares after the first round was closed.")]
+    RoundClosed(GuardianIndex),
+    /// Occurs if a guardian that did not submit decryption shares submits responses.
+    #[error("Guardian {0} did not take part in the first round.")]
+    NotParticipating(GuardianIndex),
+    /// Occurs if fewer than `k` guardians take part in the decryption.
+    #[error("Only {l} guardians take part in the decryption, but at least {k} are required.")]
+    NotEnoughGuardians { l: usize, k: usize },
+    /// Occurs if a participating guardian has not submitted its responses yet.
+    #[error("The responses of guardian {0} are missing.")]
+    MissingResponses(GuardianIndex),
+    /// Occurs if a participant of the decryption quorum has not submitted its shares yet.
+    #[error("The decryption shares of guardian {0} are missing.")]
+    MissingShares(GuardianIndex),
+    /// Occurs if a guardian outside the decryption quorum submits a message.
+    #[error("Guardian {0} is not a participant of the decryption quorum.")]
+    NotInQuorum(GuardianIndex),
+    /// Occurs if the decryption shares of an option could not be combined.
+    #[error("Could not combine the decryption shares of contest {0}, option {1}: {2}")]
+    ShareCombination(ContestIndex, usize, ShareCombinationError),
+    /// Occurs if the decryption shares of an option could not be combined by the quorum.
+    #[error("Could not combine the decryption shares of contest {0}, option {1}: {2}")]
+    QuorumCombination(ContestIndex, usize, QuorumError),
+    /// Occurs if a response share could not be computed.
+    #[error("Could not compute the response share for contest {0}, option {1}: {2}")]
+    ResponseShare(ContestIndex, usize, ResponseShareError),
+    /// Occurs if the proof shares of an option could not be combined.
+    #[error("Could not combine the proof of contest {0}, option {1}: {2}")]
+    CombineProof(ContestIndex, usize, CombineProofError),
+    /// Occurs if an option could not be decrypted.
+    #[error("Could not decrypt contest {0}, option {1}: {2}")]
+    Decryption(ContestIndex, usize, DecryptionError),
+    /// Occurs if a guardian's secret state does not belong to its first-round message in the
+    /// ceremony.
+    #[error("The secret state of guardian {0} does not match its commitments in the ceremony.")]
+    StateMismatch(GuardianIndex),
+    /// Occurs if the contest data is decrypted, but a contest does not have exactly one
+    /// ciphertext.
+    #[error("Contest {0} does not have exactly one contest data ciphertext.")]
+    NotContestData(ContestIndex),
+}
+
+/// Drives the decryption of an [`EncryptedTally`] by a quorum of guardians.
+///
+/// The ceremony collects the messages of both rounds and checks that every message matches the
+/// encrypted tally. Unless the ceremony is restricted to a [`DecryptionQuorum`], any set of at
+/// least `k` guardians may take part; all guardians that submit decryption shares in the first
+/// round must also submit responses in the second round.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TallyDecryptionCeremony {
+    /// The encrypted tally to decrypt
+    encrypted_tally: EncryptedTally,
+    /// The quorum the ceremony is restricted to, if any
+    #[serde(default)]
+    quorum: Option<DecryptionQuorum>,
+    /// The first-round messages by guardian
+    shares: BTreeMap<GuardianIndex, GuardianTallyDecryptionShares>,
+    /// The second-round messages by guardian
+    responses: BTreeMap<GuardianIndex, GuardianTallyDecryptionResponses>,
+}
+
+/// Checks that `entries` contains one entry per option of every contest of `encrypted_tally`.
+fn has_tally_shape<T>(
+    encrypted_tally: &EncryptedTally,
+    entries: &BTreeMap<ContestIndex, Vec<T>>,
+) -> bool {
+    encrypted_tally.len() == entries.len()
+        && encrypted_tally.iter().all(|(contest_index, cts)| {
+            entries
+                .get(contest_index)
+                .is_some_and(|contest_entries| contest_entries.len() == cts.len())
+        })
+}
+
+/// Returns an iterator over every option of every contest of `encrypted_tally`, with the 1-based
+/// option number.
+fn each_option(
+    encrypted_tally: &EncryptedTally,
+) -> impl Iterator<Item = (ContestIndex, usize, &Ciphertext)> {
+    encrypted_tally.iter().flat_map(|(&contest_index, cts)| {
+        cts.iter()
+            .enumerate()
+            .map(move |(ix, ct)| (contest_index, ix + 1, ct))
+    })
+}
+
+impl GuardianTallyDecryptionShares {
+    /// Computes the first-round message of a guardian, together with the secret state the
+    /// guardian needs for the second round.
+    ///
+    /// The arguments are
+    /// - `csprng` - secure randomness generator
+    /// - `pre_voting_data` - the pre-voting data
+    /// - `encrypted_tally` - the encrypted tally
+    /// - `secret_key_share` - the guardian's secret key share
+    pub fn generate(
+        csprng: &mut Csprng,
+        pre_voting_data: &PreVotingData,
+        encrypted_tally: &EncryptedTally,
+        secret_key_share: &GuardianSecretKeyShare,
+    ) -> (GuardianTallyDecryptionShares, GuardianTallyDecryptionState) {
+        let fixed_parameters = &pre_voting_data.parameters.fixed_parameters;
+        let i = secret_key_share.i;
+
+        let mut shares = BTreeMap::new();
+        let mut states = BTreeMap::new();
+        for (&contest_index, cts) in encrypted_tally {
+            let (contest_shares, contest_states) = cts
+                .iter()
+                .map(|ct| {
+                    let share = DecryptionShare::from(fixed_parameters, secret_key_share, ct);
+                    let (proof_commit, state) =
+                        DecryptionProof::generate_commit_share(csprng, fixed_parameters, ct, &i);
+                    (
+                        DecryptionShareResult {
+                            share,
+                            proof_commit,
+                        },
+                        state,
+                    )
+                })
+                .unzip();
+            shares.insert(contest_index, contest_shares);
+            states.insert(contest_index, contest_states);
+        }
+
+        let message = GuardianTallyDecryptionShares { i, shares };
+        let state = GuardianTallyDecryptionState {
+            i,
+            h_commitments: message.commitments_hash(pre_voting_data),
+            states,
+        };
+        (message, state)
+    }
+
+    /// Computes the hash of the guardian's proof commitments `(a_i, b_i)` for every option of
+    /// every contest, to which the secret state of the first round is bound.
+    ///
+    /// H = H(H_E; 30, b(i, 4), b(a_i, 512), b(b_i, 512), ...) for standard parameters
+    pub fn commitments_hash(&self, pre_voting_data: &PreVotingData) -> HValue {
+        let group = &pre_voting_data.parameters.fixed_parameters.group;
+
+        let mut v = vec![0x30];
+        v.extend_from_slice(&self.i.get_one_based_u32().to_be_bytes());
+        for share in self.shares.values().flatten() {
+            v.extend_from_slice(&share.proof_commit.a_i.to_be_bytes_left_pad(group));
+            v.extend_from_slice(&share.proof_commit.b_i.to_be_bytes_left_pad(group));
+        }
+        eg_h(&pre_voting_data.hashes_ext.h_e, &v)
+    }
+
+    /// Reads a `GuardianTallyDecryptionShares` from a `std::io::Read`.
+    pub fn from_stdioread(stdioread: &mut dyn std::io::Read) -> Result<Self> {
+        serde_json::from_reader(stdioread).context("Reading GuardianTallyDecryptionShares")
+    }
+
+    /// Writes a `GuardianTallyDecryptionShares` to a `std::io::Write`.
+    pub fn to_stdiowrite(&self, stdiowrite: &mut dyn std::io::Write) -> Result<()> {
+        let mut ser = serde_json::Serializer::pretty(stdiowrite);
+
+        self.serialize(&mut ser)
+            .map_err(Into::<anyhow::Error>::into)
+            .and_then(|_| ser.into_inner().write_all(b"\n").map_err(Into::into))
+            .context("Writing GuardianTallyDecryptionShares")
+    }
+}
+
+impl GuardianTallyDecryptionState {
+    /// Reads a `GuardianTallyDecryptionState` from a `std::io::Read`.
+    pub fn from_stdioread(stdioread: &mut dyn std::io::Read) -> Result<Self> {
+        serde_json::from_reader(stdioread).context("Reading GuardianTallyDecryptionState")
+    }
+
+    /// Writes a `GuardianTallyDecryptionState` to a `std::io::Write`.
+    pub fn to_stdiowrite(&self, stdiowrite: &mut dyn std::io::Write) -> Result<()> {
+        let mut ser = serde_json::Serializer::pretty(stdiowrite);
+
+        self.serialize(&mut ser)
+            .map_err(Into::<anyhow::Error>::into)
+            .and_then(|_| ser.into_inner().write_all(b"\n").map_err(Into::into))
+            .context("Writing GuardianTallyDecryptionState")
+    }
+}
+
+impl GuardianTallyDecryptionResponses {
+    /// Computes the second-round message of a guardian.
+    ///
+    /// The arguments are
+    /// - `pre_voting_data` - the pre-voting data
+    /// - `ceremony` - the ceremony, which must contain the first-round messages of all
+    ///   participating guardians
+    /// - `state` - the guardian's secret state from the first round, which is consumed
+    /// - `secret_key_share` - the guardian's secret key share
+    ///
+    /// Fails if the guardian's first-round message in the ceremony is not the one the state was
+    /// created with.
+    pub fn generate(
+        pre_voting_data: &PreVotingData,
+        ceremony: &TallyDecryptionCeremony,
+        state: GuardianTallyDecryptionState,
+        secret_key_share: &GuardianSecretKeyShare,
+    ) -> Result<GuardianTallyDecryptionResponses, TallyDecryptionError> {
+        let election_parameters = &pre_voting_data.parameters;
+        let i = secret_key_share.i;
+
+        let Some(own_shares) = ceremony.shares.get(&i) else {
+            return Err(TallyDecryptionError::NotParticipating(i));
+        };
+        if state.i != i {
+            return Err(TallyDecryptionError::IndexMismatch(i));
+        }
+        if own_shares.commitments_hash(pre_voting_data) != state.h_commitments {
+            return Err(TallyDecryptionError::StateMismatch(i));
+        }
+        if !has_tally_shape(&ceremony.encrypted_tally, &state.states) {
+            return Err(TallyDecryptionError::ShapeMismatch(i));
+        }
+
+        let mut responses = BTreeMap::<ContestIndex, Vec<_>>::new();
+        for (contest_index, j, ct) in each_option(&ceremony.encrypted_tally) {
+            let option_shares = ceremony.option_shares(contest_index, j);
+
+            let m = ceremony.combine_shares(pre_voting_data, contest_index, j, &option_shares)?;
+
+            let commit_shares: Vec<DecryptionProofCommitShare> = option_shares
+                .iter()
+                .map(|s| s.proof_commit.clone())
+                .collect();
+
+            let Some(option_state) = state
+                .states
+                .get(&contest_index)
+                .and_then(|states| states.get(j - 1))
+            else {
+                return Err(TallyDecryptionError::ShapeMismatch(i));
+            };
+
+            let response = DecryptionProof::generate_response_share(
+                &election_parameters.fixed_parameters,
+                &pre_voting_data.hashes_ext,
+                &pre_voting_data.public_key,
+                ct,
+                &m,
+                &commit_shares,
+                option_state,
+                secret_key_share,
+            )
+            .map_err(|e| TallyDecryptionError::ResponseShare(contest_index, j, e))?;
+
+            responses.entry(contest_index).or_default().push(response);
+        }
+
+        Ok(GuardianTallyDecryptionResponses { i, responses })
+    }
+
+    /// Reads a `GuardianTallyDecryptionResponses` from a `std::io::Read`.
+    pub fn from_stdioread(stdioread: &mut dyn std::io::Read) -> Result<Self> {
+        serde_json::from_reader(stdioread).context("Reading GuardianTallyDecryptionResponses")
+    }
+
+    /// Writes a `GuardianTallyDecryptionResponses` to a `std::io::Write`.
+    pub fn to_stdiowrite(&self, stdiowrite: &mut dyn std::io::Write) -> Result<()> {
+        let mut ser = serde_json::Serializer::pretty(stdiowrite);
+
+        self.serialize(&mut ser)
+            .map_err(Into::<anyhow::Error>::into)
+            .and_then(|_| ser.into_inner().write_all(b"\n").map_err(Into::into))
+            .context("Writing GuardianTallyDecryptionResponses")
+    }
+}
+
+impl TallyDecryptionCeremony {
+    /// Starts the decryption ceremony for an encrypted tally, e.g., the result of
+    /// [`BallotTallyBuilder::finalize`](crate::ballot::BallotTallyBuilder::finalize).
+    pub fn new(encrypted_tally: EncryptedTally) -> TallyDecryptionCeremony {
+        TallyDecryptionCeremony {
+            encrypted_tally,
+            quorum: None,
+            shares: BTreeMap::new(),
+            responses: BTreeMap::new(),
+        }
+    }
+
+    /// Starts the decryption ceremony for an encrypted tally, restricted to the participants of
+    /// a [`DecryptionQuorum`], e.g., the result of [`DecryptionQuorum::select`] on the guardians
+    /// that are present.
+    pub fn with_quorum(
+        encrypted_tally: EncryptedTally,
+        quorum: DecryptionQuorum,
+    ) -> TallyDecryptionCeremony {
+        TallyDecryptionCeremony {
+            quorum: Some(quorum),
+            ..TallyDecryptionCeremony::new(encrypted_tally)
+        }
+    }
+
+    /// The encrypted tally that is decrypted.
+    pub fn encrypted_tally(&self) -> &EncryptedTally {
+        &self.encrypted_tally
+    }
+
+    /// The indices of the guardians that take part in the decryption, i.e., that submitted
+    /// decryption shares.
+    pub fn participating_guardians(&self) -> impl Iterator<Item = GuardianIndex> + '_ {
+        self.shares.keys().copied()
+    }
+
+    /// The quorum the ceremony is restricted to, if any.
+    pub fn quorum(&self) -> Option<&DecryptionQuorum> {
+        self.quorum.as_ref()
+    }
+
+    /// Combines the decryption shares of an option, using the quorum's Lagrange coefficients if
+    /// the ceremony is restricted to a quorum. The option number `j` is 1-based.
+    fn combine_shares(
+        &self,
+        pre_voting_data: &PreVotingData,
+        contest_index: ContestIndex,
+        j: usize,
+        option_shares: &[DecryptionShareResult],
+    ) -> Result<CombinedDecryptionShare, TallyDecryptionError> {
+        let election_parameters = &pre_voting_data.parameters;
+        let shares = option_shares.iter().map(|s| &s.share);
+        match &self.quorum {
+            Some(quorum) => quorum
+                .combine(&election_parameters.fixed_parameters, shares)
+                .map_err(|e| TallyDecryptionError::QuorumCombination(contest_index, j, e)),
+            None => CombinedDecryptionShare::combine(election_parameters, shares)
+                .map_err(|e| TallyDecryptionError::ShareCombination(contest_index, j, e)),
+        }
+    }
+
+    /// Returns the first-round entries of all participating guardians for an option, in order of
+    /// the guardian index. The option number `j` is 1-based.
+    fn option_shares(&self, contest_index: ContestIndex, j: usize) -> Vec<DecryptionShareResult> {
+        self.shares
+            .values()
+            .filter_map(|msg| msg.shares.get(&contest_index)?.get(j - 1).cloned())
+            .collect()
+    }
+
+    /// Returns the second-round entries of all participating guardians for an option, in order
+    /// of the guardian index. The option number `j` is 1-based.
+    fn option_responses(
+        &self,
+        contest_index: ContestIndex,
+        j: usize,
+    ) -> Vec<DecryptionProofResponseShare> {
+        self.responses
+            .values()
+            .filter_map(|msg| msg.responses.get(&contest_index)?.get(j - 1).cloned())
+            .collect()
+    }
+
+    /// Adds the first-round message of a guardian.
+    ///
+    /// Fails if the guardian is not a participant of the quorum, already submitted its shares, if
+    /// the second round has already started, or if the message does not match the encrypted
+    /// tally.
+    pub fn add_shares(
+        &mut self,
+        message: GuardianTallyDecryptionShares,
+    ) -> Result<(), TallyDecryptionError> {
+        let i = message.i;
+        if self
+            .quorum
+            .as_ref()
+            .is_some_and(|quorum| !quorum.contains(i))
+        {
+            return Err(TallyDecryptionError::NotInQuorum(i));
+        }
+        if !self.responses.is_empty() {
+            return Err(TallyDecryptionError::RoundClosed(i));
+        }
+        if self.shares.contains_key(&i) {
+            return Err(TallyDecryptionError::DuplicateGuardian(i));
+        }
+        if !has_tally_shape(&self.encrypted_tally, &message.shares) {
+            return Err(TallyDecryptionError::ShapeMismatch(i));
+        }
+        let all_own = message
+            .shares
+            .values()
+            .flatten()
+            .all(|s| s.share.i == i && s.proof_commit.i == i);
+        if !all_own {
+            return Err(TallyDecryptionError::IndexMismatch(i));
+        }
+
+        self.shares.insert(i, message);
+        Ok(())
+    }
+
+    /// Adds the second-round message of a guardian.
+    ///
+    /// Fails if the guardian did not take part in the first round, already submitted its
+    /// responses, or if the message does not match the encrypted tally.
+    pub fn add_responses(
+        &mut self,
+        message: GuardianTallyDecryptionResponses,
+    ) -> Result<(), TallyDecryptionError> {
+        let i = message.i;
+        if !self.shares.contains_key(&i) {
+            return Err(TallyDecryptionError::NotParticipating(i));
+        }
+        if self.responses.contains_key(&i) {
+            return Err(TallyDecryptionError::DuplicateGuardian(i));
+        }
+        if !has_tally_shape(&self.encrypted_tally, &message.responses) {
+            return Err(TallyDecryptionError::ShapeMismatch(i));
+        }
+        if !message.responses.values().flatten().all(|r| r.i == i) {
+            return Err(TallyDecryptionError::IndexMismatch(i));
+        }
+
+        self.responses.insert(i, message);
+        Ok(())
+    }
+
+    /// Checks that enough guardians take part and that every participating guardian has
+    /// submitted the messages of both rounds.
+    fn check_complete(&self, pre_voting_data: &PreVotingData) -> Result<(), TallyDecryptionError> {
+        if let Some(quorum) = &self.quorum {
+            if let Some(&i) = quorum
+                .participants
+                .iter()
+                .find(|i| !self.shares.contains_key(i))
+            {
+                return Err(TallyDecryptionError::MissingShares(i));
+            }
+        }
+        let k = pre_voting_data
+            .parameters
+            .varying_parameters
+            .k
+            .as_quantity();
+        if self.shares.len() < k {
+            return Err(TallyDecryptionError::NotEnoughGuardians {
+                l: self.shares.len(),
+                k,
+            });
+        }
+        if let Some(&i) = self.shares.keys().find(|i| !self.responses.contains_key(i)) {
+            return Err(TallyDecryptionError::MissingResponses(i));
+        }
+        Ok(())
+    }
+
+    /// Combines the messages of all participating guardians for an option into the combined
+    /// decryption share and the proof of correct decryption. The option number `j` is 1-based.
+    fn combine_option(
+        &self,
+        pre_voting_data: &PreVotingData,
+        guardian_public_keys: &[GuardianPublicKey],
+        contest_index: ContestIndex,
+        j: usize,
+        ct: &Ciphertext,
+    ) -> Result<
+        (
+            CombinedDecryptionShare,
+            DecryptionProof,
+            OptionDecryptionShares,
+        ),
+        TallyDecryptionError,
+    > {
+        let shares = self.option_shares(contest_index, j);
+        let responses = self.option_responses(contest_index, j);
+
+        let m = self.combine_shares(pre_voting_data, contest_index, j, &shares)?;
+
+        let proof = DecryptionProof::combine_proof(
+            &pre_voting_data.parameters,
+            &pre_voting_data.hashes_ext,
+            ct,
+            shares.iter().map(|s| &s.share),
+            shares.iter().map(|s| &s.proof_commit),
+            &responses,
+            guardian_public_keys,
+        )
+        .map_err(|e| TallyDecryptionError::CombineProof(contest_index, j, e))?;
+
+        Ok((m, proof, OptionDecryptionShares { shares, responses }))
+    }
+
+    /// Combines the messages of all participating guardians into the [`DecryptedTally`], with a
+    /// proof of correct decryption for every option. Also returns the guardians' messages in the
+    /// form in which they are published in the election record.
+    ///
+    /// The arguments are
+    /// - `pre_voting_data` - the pre-voting data
+    /// - `guardian_public_keys` - the public keys of all `n` guardians
+    pub fn finalize(
+        &self,
+        pre_voting_data: &PreVotingData,
+        guardian_public_keys: &[GuardianPublicKey],
+    ) -> Result<(DecryptedTally, TallyDecryptionShares), TallyDecryptionError> {
+        let fixed_parameters = &pre_voting_data.parameters.fixed_parameters;
+        self.check_complete(pre_voting_data)?;
+
+        let mut decrypted_tally = DecryptedTally::new();
+        let mut tally_decryption_shares = TallyDecryptionShares::new();
+        for (contest_index, j, ct) in each_option(&self.encrypted_tally) {
+            let (m, proof, option_shares) =
+                self.combine_option(pre_voting_data, guardian_public_keys, contest_index, j, ct)?;
+
+            let decryption = VerifiableDecryption::new(
+                fixed_parameters,
+                &pre_voting_data.public_key,
+                ct,
+                &m,
+                &proof,
+            )
+            .map_err(|e| TallyDecryptionError::Decryption(contest_index, j, e))?;
+
+            decrypted_tally
+                .entry(contest_index)
+                .or_default()
+                .push(decryption);
+            tally_decryption_shares
+                .entry(contest_index)
+                .or_default()
+                .push(option_shares);
+        }
+
+        Ok((decrypted_tally, tally_decryption_shares))
+    }
+
+    /// Combines the messages of all participating guardians into the decryption of the contest
+    /// data of a challenged ballot. The ceremony must decrypt the ciphertexts returned by
+    /// [`ChallengedBallotDecryption::contest_data_ciphertexts`](crate::challenged_ballot::ChallengedBallotDecryption::contest_data_ciphertexts),
+    /// i.e., exactly one ciphertext per contest.
+    ///
+    /// The arguments are
+    /// - `pre_voting_data` - the pre-voting data
+    /// - `guardian_public_keys` - the public keys of all `n` guardians
+    pub fn finalize_contest_data(
+        &self,
+        pre_voting_data: &PreVotingData,
+        guardian_public_keys: &[GuardianPublicKey],
+    ) -> Result<BTreeMap<ContestIndex, ContestDataDecryption>, TallyDecryptionError> {
+        self.check_complete(pre_voting_data)?;
+
+        let mut decryptions = BTreeMap::new();
+        for (&contest_index, cts) in &self.encrypted_tally {
+            let [ct] = cts.as_slice() else {
+                return Err(TallyDecryptionError::NotContestData(contest_index));
+            };
+            let (m, proof, _) =
+                self.combine_option(pre_voting_data, guardian_public_keys, contest_index, 1, ct)?;
+            decryptions.insert(contest_index, ContestDataDecryption::new(&m, proof));
+        }
+
+        Ok(decryptions)
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod test {
+    use std::iter::zip;
+
+    use util::{algebra::FieldElement, csprng::Csprng};
+
+    use super::*;
+    use crate::{
+        election_manifest::ContestIndex, example_election_manifest::example_election_manifest,
+        example_election_parameters::example_election_parameters,
+        guardian_secret_key::GuardianSecretKey, guardian_share::GuardianEncryptedShare,
+    };
+
+    #[test]
+    fn test_tally_decryption_ceremony() {
+        let mut csprng = Csprng::new(b"test_tally_decryption_ceremony");
+        let election_parameters = example_election_parameters();
+        let fixed_parameters = &election_parameters.fixed_parameters;
+        let field = &fixed_parameters.field;
+
+        let guardian_secret_keys = election_parameters
+            .varying_parameters
+            .each_guardian_i()
+            .map(|i| GuardianSecretKey::generate(&mut csprng, &election_parameters, i, None))
+            .collect::<Vec<_>>();
+        let guardian_public_keys = guardian_secret_keys
+            .iter()
+            .map(|sk| sk.make_public_key())
+            .collect::<Vec<_>>();
+
+        let pre_voting_data = PreVotingData::compute(
+            example_election_manifest(),
+            election_parameters.clone(),
+            &guardian_public_keys,
+        )
+        .unwrap();
+
+        // A quorum of k guardians, leaving out the first guardian.
+        let k = election_parameters.varying_parameters.k.as_quantity();
+        let quorum = &guardian_secret_keys[1..=k];
+        let key_shares = quorum
+            .iter()
+            .map(|sk| {
+                let shares = guardian_secret_keys
+                    .iter()
+                    .map(|dealer_sk| {
+                        GuardianEncryptedShare::encrypt(
+                            &mut csprng,
+                            &election_parameters,
+                            dealer_sk,
+                            &sk.make_public_key(),
+                        )
+                        .ciphertext
+                    })
+                    .collect::<Vec<_>>();
+                GuardianSecretKeyShare::compute(
+                    &election_parameters,
+                    &guardian_public_keys,
+                    &shares,
+                    sk,
+                )
+                .unwrap()
+            })
+            .collect::<Vec<_>>();
+
+        let votes = [(1, vec![3, 0]), (2, vec![1, 4, 2])];
+        let encrypted_tally: EncryptedTally = votes
+            .iter()
+            .map(|(c, vote)| {
+                let cts = vote
+                    .iter()
+                    .map(|&v| {
+                        let nonce = field.random_field_elem(&mut csprng);
+                        pre_voting_data
+                            .public_key
+                            .encrypt_with(fixed_parameters, &nonce, v)
+                    })
+                    .collect();
+                (ContestIndex::from_one_based_index(*c).unwrap(), cts)
+            })
+            .collect();
+
+        // The first guardian is absent, so the quorum consists of the next k guardians.
+        let present = election_parameters
+            .varying_parameters
+            .each_guardian_i()
+            .skip(1);
+        let decryption_quorum = DecryptionQuorum::select(&election_parameters, present).unwrap();
+        assert!(zip(&decryption_quorum.participants, &key_shares).all(|(&i, ks)| i == ks.i));
+
+        let mut ceremony =
+            TallyDecryptionCeremony::with_quorum(encrypted_tally.clone(), decryption_quorum);
+        assert!(matches!(
+            ceremony.finalize(&pre_voting_data, &guardian_public_keys),
+            Err(TallyDecryptionError::MissingShares(_))
+        ));
+
+        let mut states = vec![];
+        for key_share in &key_shares {
+            let (shares, state) = GuardianTallyDecryptionShares::generate(
+                &mut csprng,
+                &pre_voting_data,
+                &encrypted_tally,
+                key_share,
+            );
+            ceremony.add_shares(shares.clone()).unwrap();
+            assert!(matches!(
+                ceremony.add_shares(shares),
+                Err(TallyDecryptionError::DuplicateGuardian(_))
+            ));
+            states.push(state);
+        }
+        assert!(matches!(
+            ceremony.finalize(&pre_voting_data, &guardian_public_keys),
+            Err(TallyDecryptionError::MissingResponses(_))
+        ));
+
+        // A state can only be used with the first-round message it was created with.
+        let (_, other_state) = GuardianTallyDecryptionShares::generate(
+            &mut csprng,
+            &pre_voting_data,
+            &encrypted_tally,
+            &key_shares[0],
+        );
+        assert!(matches!(
+            GuardianTallyDecryptionResponses::generate(
+                &pre_voting_data,
+                &ceremony,
+                other_state,
+                &key_shares[0],
+            ),
+            Err(TallyDecryptionError::StateMismatch(_))
+        ));
+        assert_eq!(
+            format!("{:?}", states[0]),
+            format!(
+                "GuardianTallyDecryptionState {{ i: {:?}, h_commitments: {:?}, .. }}",
+                states[0].i, states[0].h_commitments
+            )
+        );
+
+        for (state, key_share) in zip(states, &key_shares) {
+            let responses = GuardianTallyDecryptionResponses::generate(
+                &pre_voting_data,
+                &ceremony,
+                state,
+                key_share,
+            )
+            .unwrap();
+            ceremony.add_responses(responses).unwrap();
+        }
+
+        let (decrypted_tally, tally_decryption_shares) = ceremony
+            .finalize(&pre_voting_data, &guardian_public_keys)
+            .unwrap();
+
+        for ((c, vote), (contest_index, decryptions)) in zip(&votes, &decrypted_tally) {
+            assert_eq!(contest_index.get_one_based_u32(), *c);
+            assert_eq!(decryptions.len(), vote.len());
+            for (&v, (decryption, ct)) in
+                zip(vote, zip(decryptions, &encrypted_tally[contest_index]))
+            {
+                assert_eq!(decryption.plain_text, FieldElement::from(v, field));
+                assert!(decryption.verify(
+                    fixed_parameters,
+                    &pre_voting_data.hashes_ext,
+                    &pre_voting_data.public_key,
+                    ct
+                ));
+            }
+            assert!(tally_decryption_shares[contest_index]
+                .iter()
+                .all(|option| option.shares.len() == k && option.responses.len() == k));
+        }
+    }
+}
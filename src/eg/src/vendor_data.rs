@@ -0,0 +1,105 @@
+// Copyright (C) Microsoft Corporation. All rights reserved.
+
+#![deny(clippy::unwrap_used)]
+#![deny(clippy::expect_used)]
+#![deny(clippy::panic)]
+#![deny(clippy::manual_assert)]
+
+//! This module provides [`VendorData`], opaque data that a vendor can attach to the
+//! [`ElectionManifest`](crate::election_manifest::ElectionManifest), its contests, contest
+//! options and ballot styles, e.g., party affiliations or ballot rotation information.
+//!
+//! ElectionGuard does not interpret vendor data. It is, however, part of the canonical bytes of
+//! the election manifest and is therefore bound to the manifest hash `H_M`. Vendor data is
+//! serialized as a base64 string (RFC 4648) and is at most [`VendorData::MAX_LEN`] bytes long.
+
+use base64::{engine::general_purpose::STANDARD as BASE64_ENGINE, Engine as _};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use thiserror::Error;
+
+/// Opaque vendor data of at most [`VendorData::MAX_LEN`] bytes.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct VendorData(Vec<u8>);
+
+/// Represents errors occurring while creating [`VendorData`].
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum VendorDataError {
+    /// Occurs if the vendor data is too long.
+    #[error("The vendor data is {0} bytes long, but at most {max} bytes are allowed.", max = VendorData::MAX_LEN)]
+    TooLong(usize),
+}
+
+impl VendorData {
+    /// The maximum length of vendor data in bytes.
+    pub const MAX_LEN: usize = 64 * 1024;
+
+    /// Creates vendor data from the given bytes.
+    pub fn new(bytes: impl Into<Vec<u8>>) -> Result<Self, VendorDataError> {
+        let bytes = bytes.into();
+        if bytes.len() > Self::MAX_LEN {
+            return Err(VendorDataError::TooLong(bytes.len()));
+        }
+        Ok(VendorData(bytes))
+    }
+
+    /// The bytes of the vendor data.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.0
+    }
+
+    /// Returns whether there is no vendor data. Empty vendor data is omitted from the
+    /// serialized manifest, so that it does not change the manifest hash.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+impl Serialize for VendorData {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        BASE64_ENGINE.encode(&self.0).serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for VendorData {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        use serde::de::Error;
+
+        let s = String::deserialize(deserializer)?;
+        let bytes = BASE64_ENGINE.decode(s).map_err(D::Error::custom)?;
+        VendorData::new(bytes).map_err(D::Error::custom)
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_vendor_data() {
+        let vendor_data =
+            VendorData::new(b"party=Cr\xc3\xbdstalheart;rotation=3".to_vec()).unwrap();
+        let json = serde_json::to_string(&vendor_data).unwrap();
+        assert_eq!(json, "\"cGFydHk9Q3LDvXN0YWxoZWFydDtyb3RhdGlvbj0z\"");
+        assert_eq!(
+            serde_json::from_str::<VendorData>(&json).unwrap(),
+            vendor_data
+        );
+
+        assert!(serde_json::from_str::<VendorData>("\"not base64!\"").is_err());
+
+        let too_long = vec![0u8; VendorData::MAX_LEN + 1];
+        assert_eq!(
+            VendorData::new(too_long.clone()),
+            Err(VendorDataError::TooLong(VendorData::MAX_LEN + 1))
+        );
+        let json = serde_json::to_string(&BASE64_ENGINE.encode(too_long)).unwrap();
+        assert!(serde_json::from_str::<VendorData>(&json).is_err());
+    }
+}
@@ -27,6 +27,7 @@ use util::{
     algebra_utils::{get_single_coefficient_at_zero, group_lagrange_at_zero, DiscreteLog},
     csprng::Csprng,
 };
+use zeroize::{Zeroize, ZeroizeOnDrop};
 
 /// A decryption share is a guardian's partial decryption of a given ciphertext.
 ///
@@ -54,7 +55,7 @@ impl DecryptionShare {
         ciphertext: &Ciphertext,
     ) -> Self {
         let group = &fixed_parameters.group;
-        let m_i = ciphertext.alpha.exp(&secret_key_share.p_i, group);
+        let m_i = ciphertext.alpha.exp_secret(&secret_key_share.p_i, group);
         DecryptionShare {
             i: secret_key_share.i,
             m_i,
@@ -67,7 +68,7 @@ impl DecryptionShare {
 ///
 /// This corresponds to the `M` in Section `3.6.2`.
 #[derive(Debug)]
-pub struct CombinedDecryptionShare(GroupElement);
+pub struct CombinedDecryptionShare(pub(crate) GroupElement);
 
 /// Represents errors occurring while combining [`DecryptionShare`]s into a
 /// [`CombinedDecryptionShare`].
@@ -165,14 +166,27 @@ pub struct DecryptionProofCommitShare {
 /// [`DecryptionProof`].
 ///
 /// This corresponds to `u_i` as in Equation `69`.
-#[derive(Clone, Debug, Serialize, Deserialize)]
+///
+/// The commit state is zeroized on drop and redacted in the `Debug` output. It must be used for
+/// a single response only, as two responses for different challenges reveal the secret key
+/// share.
+#[derive(Serialize, Deserialize, Zeroize, ZeroizeOnDrop)]
 pub struct DecryptionProofStateShare {
     /// The guardian's index
+    #[zeroize(skip)]
     pub i: GuardianIndex,
     /// The commit state
     pub u_i: FieldElement,
 }
 
+impl std::fmt::Debug for DecryptionProofStateShare {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DecryptionProofStateShare")
+            .field("i", &self.i)
+            .finish_non_exhaustive()
+    }
+}
+
 /// The response share of a single guardian for a [`DecryptionProof`].
 ///
 /// This corresponds to `v_i` as in Equation `73`.
@@ -255,8 +269,8 @@ impl DecryptionProof {
         let field = &fixed_parameters.field;
 
         let u_i = field.random_field_elem(csprng);
-        let a_i = group.g_exp(&u_i);
-        let b_i = ciphertext.alpha.exp(&u_i, group);
+        let a_i = group.g_exp_secret(&u_i);
+        let b_i = ciphertext.alpha.exp_secret(&u_i, group);
         let dcs = DecryptionProofCommitShare { i: *i, a_i, b_i };
         let dss = DecryptionProofStateShare { i: *i, u_i };
         (dcs, dss)
@@ -521,32 +535,44 @@ impl DecryptionProof {
         joint_key: &JointElectionPublicKey,
         ciphertext: &Ciphertext,
         m: &CombinedDecryptionShare,
-    ) -> bool {
+    ) -> Result<(), DecryptionProofValidationError> {
         let group = &fixed_parameters.group;
         let field = &fixed_parameters.field;
 
-        let key = &joint_key.joint_election_public_key;
-
         let a = group
             .g_exp(&self.response)
-            .mul(&key.exp(&self.challenge, group), group);
+            .mul(&joint_key.exp(&self.challenge, group), group);
         let a_v = ciphertext.alpha.exp(&self.response, group);
         let m_c = m.0.exp(&self.challenge, group);
         let b = a_v.mul(&m_c, group);
 
         //Check (9.A)
         if !self.response.is_valid(field) {
-            return false;
+            return Err(DecryptionProofValidationError::ResponseNotInField);
         }
         let c = Self::challenge(fixed_parameters, &h_e.h_e, joint_key, ciphertext, &a, &b, m);
         //Check (9.B)
         if c != self.challenge {
-            return false;
+            return Err(DecryptionProofValidationError::ChallengeMismatch);
         }
-        true
+        Ok(())
     }
 }
 
+/// Represents errors occurring during the validation of a [`DecryptionProof`].
+#[derive(Error, Debug, PartialEq)]
+pub enum DecryptionProofValidationError {
+    /// Occurs if the decryption share implied by the plain-text has no inverse.
+    #[error("The decryption share implied by the plain-text has no inverse.")]
+    NoInverse,
+    /// Occurs if the response is not a valid field element (Check `9.A`).
+    #[error("The proof response is not a valid element in Z_q (9.A).")]
+    ResponseNotInField,
+    /// Occurs if the computed challenge does not match the given one (Check `9.B`).
+    #[error("The computed challenge does not match the given one (9.B).")]
+    ChallengeMismatch,
+}
+
 /// Represents errors occurring during decryption.
 #[derive(Error, Debug)]
 pub enum DecryptionError {
@@ -562,6 +588,7 @@ pub enum DecryptionError {
 /// Represents a "in-the-exponent" plain-text with a [`DecryptionProof`].
 ///
 /// This corresponds to `t` and `(c,v)` as in Section `3.6.3`.
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct VerifiableDecryption {
     /// The decrypted plain-text
     pub plain_text: FieldElement,
@@ -570,7 +597,7 @@ pub struct VerifiableDecryption {
 }
 
 /// Decryption posted by the guardian together with a commitment.
-#[derive(serde::Serialize, serde::Deserialize, Clone)]
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug)]
 pub struct DecryptionShareResult {
     pub share: DecryptionShare,
     pub proof_commit: DecryptionProofCommitShare,
@@ -697,12 +724,31 @@ impl VerifiableDecryption {
         joint_key: &JointElectionPublicKey,
         ciphertext: &Ciphertext,
     ) -> bool {
+        self.validate(fixed_parameters, h_e, joint_key, ciphertext)
+            .is_ok()
+    }
+
+    /// This function checks the correctness of the decryption for given
+    /// ciphertext and joint public key. In contrast to
+    /// [`VerifiableDecryption::verify`] it reports which check failed.
+    ///
+    /// Arguments are
+    /// - `self` - the verifiable decryption
+    /// - `fixed_parameters` - the fixed parameters
+    /// - `h_e` - the extended bash hash
+    /// - `joint_key` - the joint election public key
+    /// - `ciphertext` - the ciphertext
+    pub fn validate(
+        &self,
+        fixed_parameters: &FixedParameters,
+        h_e: &HashesExt,
+        joint_key: &JointElectionPublicKey,
+        ciphertext: &Ciphertext,
+    ) -> Result<(), DecryptionProofValidationError> {
         let group = &fixed_parameters.group;
-        let t = joint_key
-            .joint_election_public_key
-            .exp(&self.plain_text, group);
+        let t = joint_key.exp(&self.plain_text, group);
         let m = match t.inv(group) {
-            None => return false,
+            None => return Err(DecryptionProofValidationError::NoInverse),
             Some(t_inv) => ciphertext.beta.mul(&t_inv, group),
         };
         self.proof.validate(
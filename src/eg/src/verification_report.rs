@@ -0,0 +1,199 @@
+// Copyright (C) Microsoft Corporation. All rights reserved.
+
+#![deny(clippy::unwrap_used)]
+#![deny(clippy::expect_used)]
+#![deny(clippy::panic)]
+#![deny(clippy::manual_assert)]
+
+//! This module provides the [`VerificationReport`], which records every check performed by the
+//! [verifier](crate::verifier) together with its outcome.
+
+use std::fmt::Display;
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+/// The outcome of a single check performed on an election record.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VerificationCheck {
+    /// The number of the verification step in the Electionguard specification `2.0.0`.
+    pub step: u32,
+
+    /// Identifies the checked object, e.g., `ballot <code>, contest 2, option 1`.
+    pub object: String,
+
+    /// Whether the check passed.
+    pub passed: bool,
+
+    /// The reason why the check failed. `None` if the check passed.
+    pub reason: Option<String>,
+}
+
+impl Display for VerificationCheck {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let status = if self.passed { "PASS" } else { "FAIL" };
+        write!(f, "[{status}] Verification {} ({})", self.step, self.object)?;
+        if let Some(reason) = &self.reason {
+            write!(f, ": {reason}")?;
+        }
+        Ok(())
+    }
+}
+
+/// A record of all checks performed when verifying an election record.
+///
+/// The checks are stored in the order in which they were performed.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VerificationReport {
+    /// Every check performed.
+    pub checks: Vec<VerificationCheck>,
+}
+
+impl VerificationReport {
+    /// Creates an empty report.
+    pub fn new() -> VerificationReport {
+        VerificationReport::default()
+    }
+
+    /// Records a passed check.
+    pub fn pass(&mut self, step: u32, object: impl Into<String>) {
+        self.checks.push(VerificationCheck {
+            step,
+            object: object.into(),
+            passed: true,
+            reason: None,
+        });
+    }
+
+    /// Records a failed check.
+    pub fn fail(&mut self, step: u32, object: impl Into<String>, reason: impl Display) {
+        self.checks.push(VerificationCheck {
+            step,
+            object: object.into(),
+            passed: false,
+            reason: Some(reason.to_string()),
+        });
+    }
+
+    /// Records the outcome of a check. An `Err` is recorded as a failure with the error as reason.
+    /// Returns whether the check passed.
+    pub fn record<E: Display>(
+        &mut self,
+        step: u32,
+        object: impl Into<String>,
+        result: Result<(), E>,
+    ) -> bool {
+        match result {
+            Ok(()) => {
+                self.pass(step, object);
+                true
+            }
+            Err(e) => {
+                self.fail(step, object, e);
+                false
+            }
+        }
+    }
+
+    /// Records the outcome of a check given as `bool`, using `reason` in case it failed.
+    /// Returns whether the check passed.
+    pub fn check(
+        &mut self,
+        step: u32,
+        object: impl Into<String>,
+        passed: bool,
+        reason: impl Display,
+    ) -> bool {
+        if passed {
+            self.pass(step, object);
+        } else {
+            self.fail(step, object, reason);
+        }
+        passed
+    }
+
+    /// Returns `true` if no check failed.
+    pub fn is_success(&self) -> bool {
+        self.checks.iter().all(|check| check.passed)
+    }
+
+    /// Returns an iterator over the failed checks.
+    pub fn failures(&self) -> impl Iterator<Item = &VerificationCheck> {
+        self.checks.iter().filter(|check| !check.passed)
+    }
+
+    /// Returns a pretty JSON `String` representation of the `VerificationReport`.
+    /// The final line will end with a newline.
+    pub fn to_json_pretty(&self) -> String {
+        // `unwrap()` is justified here because why would JSON serialization fail?
+        #[allow(clippy::unwrap_used)]
+        let mut s = serde_json::to_string_pretty(self).unwrap();
+        s.push('\n');
+        s
+    }
+
+    /// Writes a `VerificationReport` to a `std::io::Write`.
+    pub fn to_stdiowrite(&self, stdiowrite: &mut dyn std::io::Write) -> Result<()> {
+        let mut ser = serde_json::Serializer::pretty(stdiowrite);
+
+        self.serialize(&mut ser)
+            .context("Error serializing verification report")?;
+
+        ser.into_inner()
+            .write_all(b"\n")
+            .context("Error writing verification report")
+    }
+
+    /// Reads a `VerificationReport` from a `std::io::Read`.
+    pub fn from_stdioread(stdioread: &mut dyn std::io::Read) -> Result<Self> {
+        serde_json::from_reader(stdioread).context("Reading VerificationReport")
+    }
+}
+
+/// Renders the report as human-readable text: one line per check, followed by a summary.
+impl Display for VerificationReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for check in &self.checks {
+            writeln!(f, "{check}")?;
+        }
+        let cnt_failed = self.failures().count();
+        write!(
+            f,
+            "{} checks performed, {} passed, {cnt_failed} failed",
+            self.checks.len(),
+            self.checks.len() - cnt_failed
+        )
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_verification_report() {
+        let mut report = VerificationReport::new();
+        assert!(report.is_success());
+
+        assert!(report.check(1, "fixed parameters", true, "unused"));
+        assert!(!report.record(5, "ballot 1, contest 2, option 3", Err("bad proof")));
+        assert!(!report.is_success());
+
+        let failures: Vec<_> = report.failures().collect();
+        assert_eq!(failures.len(), 1);
+        assert_eq!(failures[0].step, 5);
+        assert_eq!(failures[0].reason.as_deref(), Some("bad proof"));
+
+        assert_eq!(
+            report.to_string(),
+            "[PASS] Verification 1 (fixed parameters)\n\
+             [FAIL] Verification 5 (ballot 1, contest 2, option 3): bad proof\n\
+             2 checks performed, 1 passed, 1 failed"
+        );
+
+        let json = report.to_json_pretty();
+        let report2 = VerificationReport::from_stdioread(&mut json.as_bytes()).unwrap();
+        assert_eq!(report, report2);
+    }
+}
@@ -0,0 +1,1138 @@
+// Copyright (C) Microsoft Corporation. All rights reserved.
+
+#![deny(clippy::unwrap_used)]
+#![deny(clippy::expect_used)]
+#![deny(clippy::panic)]
+#![deny(clippy::manual_assert)]
+
+//! This module provides the verifier for an [`ElectionRecord`]. It performs the numbered
+//! verification steps of the Electionguard specification `2.0.0` on the published data and
+//! records the outcome of every check in a [`VerificationReport`].
+
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+
+use util::{
+    algebra::{FieldElement, Group},
+    csprng::Csprng,
+};
+
+use crate::{
+    ballot::{BallotEncrypted, BallotTallyBuilder},
+    ballot_box,
+    ballot_chain::{verify_ballot_chain, verify_confirmation_code},
+    ballot_style::BallotStyleIndex,
+    contest_encrypted::ContestEncrypted,
+    contest_hash,
+    election_manifest::ContestIndex,
+    election_parameters::ElectionParameters,
+    election_record::{
+        DecryptedTally, ElectionRecord, ElectionRecordBody, EncryptedTally, PreVotingData,
+    },
+    guardian_public_key::GuardianPublicKey,
+    hash::HValue,
+    hashes::Hashes,
+    hashes_ext::HashesExt,
+    index::Index,
+    joint_election_public_key::Ciphertext,
+    share_dispute::ShareDispute,
+    standard_parameters::STANDARD_PARAMETERS,
+    varying_parameters::BallotChaining,
+    verification_report::VerificationReport,
+    zk::ProofRange,
+};
+
+/// Verifies an [`ElectionRecord`].
+///
+/// The checks are performed in the order of the verification steps of the specification.
+/// The tally checks (Verifications `8` to `10`) are only performed if the election record
+/// contains the respective tally.
+///
+/// Returns a [`VerificationReport`] listing every check performed. The election record is valid
+/// if and only if [`VerificationReport::is_success`] holds.
+///
+/// The `csprng` is used for the batch verification of the ballots' range proofs, see
+/// [`verify_ballots`]. It must not be predictable to whoever produced the election record.
+pub fn verify_election_record(
+    election_record: &ElectionRecord,
+    csprng: &mut Csprng,
+) -> VerificationReport {
+    let prevoting = &election_record.prevoting;
+    let body = &election_record.body;
+
+    let mut report = VerificationReport::new();
+
+    verify_parameters(&mut report, &prevoting.parameters);
+    verify_guardian_public_keys(
+        &mut report,
+        &prevoting.parameters,
+        &election_record.guardian_public_keys,
+    );
+    verify_share_disputes(
+        &mut report,
+        &prevoting.parameters,
+        &election_record.guardian_public_keys,
+        &election_record.share_disputes,
+    );
+    verify_joint_election_public_key(
+        &mut report,
+        prevoting,
+        &election_record.guardian_public_keys,
+    );
+    verify_hashes(&mut report, prevoting);
+    verify_ballots(&mut report, prevoting, &body.all_ballots, csprng);
+    verify_ballot_chains(&mut report, prevoting, body);
+    verify_ballot_box(&mut report, prevoting, body);
+    verify_challenged_ballots(&mut report, prevoting, body);
+
+    match (&body.encrypted_tally, &body.decrypted_tally) {
+        (Some(encrypted_tally), opt_decrypted_tally) => {
+            verify_tally_aggregation(&mut report, prevoting, body.cast_ballots(), encrypted_tally);
+            if let Some(decrypted_tally) = opt_decrypted_tally {
+                verify_tally_decryption(&mut report, prevoting, encrypted_tally, decrypted_tally);
+            }
+        }
+        (None, Some(_)) => {
+            report.fail(
+                9,
+                "decrypted tally",
+                "the election record has a decrypted tally but no encrypted tally",
+            );
+        }
+        (None, None) => {}
+    }
+
+    report
+}
+
+/// Verifies the election parameters.
+///
+/// This corresponds to Verification `1`, i.e., the fixed parameters must be the standard
+/// parameters and the varying parameters must be consistent.
+pub fn verify_parameters(
+    report: &mut VerificationReport,
+    election_parameters: &ElectionParameters,
+) {
+    report.check(
+        1,
+        "fixed parameters",
+        election_parameters.fixed_parameters == *STANDARD_PARAMETERS,
+        "the fixed parameters are not the standard parameters",
+    );
+
+    report.record(
+        1,
+        "varying parameters",
+        election_parameters.varying_parameters.validate(),
+    );
+}
+
+/// Verifies the guardian public keys.
+///
+/// This corresponds to Verification `2`. Every guardian `1 <= i <= n` must be represented exactly
+/// once, and every guardian must publish `k` coefficient commitments together with a valid
+/// [`CoefficientProof`](crate::guardian_coeff_proof::CoefficientProof) for each of them.
+pub fn verify_guardian_public_keys(
+    report: &mut VerificationReport,
+    election_parameters: &ElectionParameters,
+    guardian_public_keys: &[GuardianPublicKey],
+) {
+    let fixed_parameters = &election_parameters.fixed_parameters;
+    let varying_parameters = &election_parameters.varying_parameters;
+    let k = varying_parameters.k.as_quantity();
+
+    let mut seen = BTreeSet::new();
+    for gpk in guardian_public_keys {
+        let i = gpk.i;
+        let object = format!("guardian {i}");
+
+        if !report.check(
+            2,
+            &object,
+            varying_parameters.is_valid_guardian_i(i.get_one_based_u32()),
+            format!(
+                "the guardian index is not in the range 1 <= i <= n={}",
+                varying_parameters.n
+            ),
+        ) {
+            continue;
+        }
+        if !report.check(
+            2,
+            &object,
+            seen.insert(i),
+            "the guardian is represented more than once",
+        ) {
+            continue;
+        }
+
+        let commitments = &gpk.coefficient_commitments.0;
+        let proofs = &gpk.coefficient_proofs;
+        if !report.check(
+            2,
+            &object,
+            commitments.len() == k && proofs.len() == k,
+            format!(
+                "{} coefficient commitments and {} proofs given, expected k={k}",
+                commitments.len(),
+                proofs.len()
+            ),
+        ) {
+            continue;
+        }
+
+        for (j, (proof, commitment)) in proofs.iter().zip(commitments).enumerate() {
+            report.record(
+                2,
+                format!("guardian {i}, coefficient {j}"),
+                proof.validate(
+                    fixed_parameters,
+                    i.get_one_based_u32(),
+                    j as u32,
+                    commitment,
+                ),
+            );
+        }
+    }
+
+    for i in varying_parameters.each_guardian_i() {
+        if !seen.contains(&i) {
+            report.fail(
+                2,
+                format!("guardian {i}"),
+                "the public key of the guardian is missing",
+            );
+        }
+    }
+}
+
+/// Verifies the disputes raised during the key ceremony.
+///
+/// This belongs to Verification `2`. Every published [`ShareDispute`] is adjudicated again and
+/// the recorded verdict must match.
+pub fn verify_share_disputes(
+    report: &mut VerificationReport,
+    election_parameters: &ElectionParameters,
+    guardian_public_keys: &[GuardianPublicKey],
+    share_disputes: &[ShareDispute],
+) {
+    for dispute in share_disputes {
+        let complaint = &dispute.complaint;
+        report.record(
+            2,
+            format!(
+                "dispute of guardian {} about share from guardian {}",
+                complaint.complainer, complaint.dealer
+            ),
+            dispute.validate(election_parameters, guardian_public_keys),
+        );
+    }
+}
+
+/// Verifies the joint election public key.
+///
+/// This corresponds to Verification `3`, i.e., the joint election public key must be a valid
+/// group element and the product of the guardians' public keys `K_i,0`.
+pub fn verify_joint_election_public_key(
+    report: &mut VerificationReport,
+    prevoting: &PreVotingData,
+    guardian_public_keys: &[GuardianPublicKey],
+) {
+    let group = &prevoting.parameters.fixed_parameters.group;
+    let joint_key = &prevoting.public_key.joint_election_public_key;
+    let object = "joint election public key";
+
+    if !report.check(
+        3,
+        object,
+        joint_key.is_valid(group),
+        "the joint election public key is not a valid group element",
+    ) {
+        return;
+    }
+
+    let product = guardian_public_keys.iter().fold(Group::one(), |acc, gpk| {
+        acc.mul(gpk.public_key_k_i_0(), group)
+    });
+    report.check(
+        3,
+        object,
+        &product == joint_key,
+        "the joint election public key is not the product of the guardian public keys",
+    );
+}
+
+/// Verifies the hashes of the election record.
+///
+/// The parameter base hash `H_P`, the manifest hash `H_M` and the election base hash `H_B`
+/// (Verification `1`), as well as the extended base hash `H_E` (Verification `4`) are recomputed
+/// and compared to the published values.
+pub fn verify_hashes(report: &mut VerificationReport, prevoting: &PreVotingData) {
+    let hashes = match Hashes::compute(&prevoting.parameters, &prevoting.manifest) {
+        Ok(hashes) => hashes,
+        Err(e) => {
+            report.fail(1, "hashes", format!("could not compute the hashes: {e:#}"));
+            return;
+        }
+    };
+    report.check(
+        1,
+        "H_P",
+        hashes.h_p == prevoting.hashes.h_p,
+        "the parameter base hash does not match",
+    );
+    report.check(
+        1,
+        "H_M",
+        hashes.h_m == prevoting.hashes.h_m,
+        "the election manifest hash does not match",
+    );
+    report.check(
+        1,
+        "H_B",
+        hashes.h_b == prevoting.hashes.h_b,
+        "the election base hash does not match",
+    );
+
+    let hashes_ext = HashesExt::compute(&prevoting.parameters, &hashes, &prevoting.public_key);
+    report.check(
+        4,
+        "H_E",
+        hashes_ext.h_e == prevoting.hashes_ext.h_e,
+        "the extended base hash does not match",
+    );
+}
+
+/// Returns the index of the ballot style of which the ballot's contests are exactly the contests
+/// of the ballot style.
+fn find_ballot_style(
+    prevoting: &PreVotingData,
+    ballot: &BallotEncrypted,
+) -> Option<BallotStyleIndex> {
+    let ballot_contests: BTreeSet<ContestIndex> = ballot.contests.keys().copied().collect();
+    let ballot_styles = &prevoting.manifest.ballot_styles;
+    ballot_styles.indices().find(|&ix| {
+        ballot_styles
+            .get(ix)
+            .is_some_and(|ballot_style| ballot_style.contests == ballot_contests)
+    })
+}
+
+/// Verifies the encrypted ballots.
+///
+/// For every ballot this checks
+/// - that it matches a ballot style and every contest has one ciphertext per option,
+/// - the range proofs of every selection (Verification `5`),
+/// - the range proof of the selection limit of every contest (Verification `6`),
+/// - the contest hash of every contest, and that its confirmation code matches the contest
+///   hashes and `B_aux` and is unique (Verification `7`).
+///
+/// The range proofs are validated in batches of up to [`RANGE_PROOF_BATCH_SIZE`] proofs with
+/// [`ProofRange::validate_batch`], using the given `csprng`. The checks are still reported in
+/// the order of the ballots.
+pub fn verify_ballots(
+    report: &mut VerificationReport,
+    prevoting: &PreVotingData,
+    ballots: &[BallotEncrypted],
+    csprng: &mut Csprng,
+) {
+    let mut confirmation_codes = HashSet::new();
+    let mut batch = RangeProofBatch::default();
+
+    for ballot in ballots {
+        let code = ballot.confirmation_code;
+        let object = format!("ballot {code}");
+
+        report.check(
+            5,
+            &object,
+            find_ballot_style(prevoting, ballot).is_some(),
+            "the contests of the ballot do not match any ballot style",
+        );
+
+        for (&contest_index, contest_encrypted) in &ballot.contests {
+            let contest_hash = contest_hash::contest_hash(
+                prevoting,
+                contest_index,
+                &contest_encrypted.selection,
+                contest_encrypted.contest_data.as_ref(),
+            );
+            report.check(
+                7,
+                format!("{object}, contest {contest_index}"),
+                contest_hash == contest_encrypted.contest_hash,
+                "the contest hash does not match the ciphertexts and contest data",
+            );
+            verify_contest(
+                report,
+                &mut batch,
+                prevoting,
+                &object,
+                contest_index,
+                contest_encrypted,
+            );
+        }
+
+        report.record(7, &object, verify_confirmation_code(prevoting, ballot));
+        report.check(
+            7,
+            &object,
+            confirmation_codes.insert(code),
+            "the confirmation code is not unique",
+        );
+
+        if batch.len() >= RANGE_PROOF_BATCH_SIZE {
+            batch.validate(report, prevoting, csprng);
+        }
+    }
+
+    batch.validate(report, prevoting, csprng);
+}
+
+/// The number of range proofs that [`verify_ballots`] validates in one batch.
+pub const RANGE_PROOF_BATCH_SIZE: usize = 4096;
+
+/// The range proofs of Verifications `5` and `6` that are still to be validated.
+///
+/// For each proof, a passed check is recorded in the report as a placeholder, which is replaced
+/// by the actual outcome in [`RangeProofBatch::validate`].
+#[derive(Default)]
+struct RangeProofBatch<'a> {
+    /// The position of the placeholder check in the report, the proof, its ciphertext and its
+    /// range bound.
+    proofs: Vec<(usize, &'a ProofRange, Ciphertext, usize)>,
+}
+
+impl<'a> RangeProofBatch<'a> {
+    /// Records a placeholder check for the given range proof and adds the proof to the batch.
+    fn push(
+        &mut self,
+        report: &mut VerificationReport,
+        step: u32,
+        object: impl Into<String>,
+        proof: &'a ProofRange,
+        ct: Ciphertext,
+        big_l: usize,
+    ) {
+        self.proofs.push((report.checks.len(), proof, ct, big_l));
+        report.pass(step, object);
+    }
+
+    /// Returns the number of proofs in the batch.
+    fn len(&self) -> usize {
+        self.proofs.len()
+    }
+
+    /// Validates all proofs in the batch, records the outcomes in place of their placeholder
+    /// checks, and empties the batch.
+    fn validate(
+        &mut self,
+        report: &mut VerificationReport,
+        prevoting: &PreVotingData,
+        csprng: &mut Csprng,
+    ) {
+        let proofs = self
+            .proofs
+            .iter()
+            .map(|(_, proof, ct, big_l)| (*proof, ct, *big_l))
+            .collect::<Vec<_>>();
+        let results = ProofRange::validate_batch(prevoting, &proofs, csprng);
+
+        for ((check_ix, ..), result) in self.proofs.iter().zip(results) {
+            if let (Some(check), Err(e)) = (report.checks.get_mut(*check_ix), result) {
+                check.passed = false;
+                check.reason = Some(e.to_string());
+            }
+        }
+        self.proofs.clear();
+    }
+}
+
+/// Verifies the ballot chains according to
+/// [`VaryingParameters::ballot_chaining`](crate::varying_parameters::VaryingParameters::ballot_chaining).
+///
+/// This corresponds to the chaining checks of Verification `7`. If chaining is required, the
+/// ballots of every device must form a closed [`BallotChain`](crate::ballot_chain::BallotChain)
+/// in the order of `ballots_by_device`. If chaining is prohibited, no ballot may have a `B_aux`.
+pub fn verify_ballot_chains(
+    report: &mut VerificationReport,
+    prevoting: &PreVotingData,
+    body: &ElectionRecordBody,
+) {
+    match prevoting.parameters.varying_parameters.ballot_chaining {
+        BallotChaining::Required => {
+            let ballots_by_code = body.ballots_by_code();
+            for (device, codes) in &body.ballots_by_device {
+                let ballots = codes
+                    .iter()
+                    .filter_map(|code| ballots_by_code.get(code).copied());
+                report.record(
+                    7,
+                    format!("ballot chain of device {device:?}"),
+                    verify_ballot_chain(
+                        prevoting,
+                        device,
+                        ballots,
+                        body.ballot_chain_closings.get(device),
+                    ),
+                );
+            }
+        }
+        BallotChaining::Prohibited => {
+            for ballot in &body.all_ballots {
+                report.check(
+                    7,
+                    format!("ballot {}", ballot.confirmation_code),
+                    ballot.b_aux == HValue::default(),
+                    "the ballot is chained although ballot chaining is prohibited",
+                );
+            }
+            report.check(
+                7,
+                "ballot chains",
+                body.ballot_chain_closings.is_empty(),
+                "there are closed ballot chains although ballot chaining is prohibited",
+            );
+        }
+        BallotChaining::Allowed => {}
+    }
+}
+
+/// Verifies the entries of the [`BallotBox`](crate::ballot_box::BallotBox), if the election
+/// record contains them.
+///
+/// The ballot box is not part of the specification. Its checks are reported with the uniqueness
+/// of the confirmation codes in Verification `7`, see [`ballot_box::verify_ballot_box`].
+pub fn verify_ballot_box(
+    report: &mut VerificationReport,
+    prevoting: &PreVotingData,
+    body: &ElectionRecordBody,
+) {
+    if body.ballot_box_entries.is_empty() {
+        return;
+    }
+    report.record(
+        7,
+        "ballot box",
+        ballot_box::verify_ballot_box(prevoting, &body.ballot_box_entries, &body.all_ballots),
+    );
+}
+
+/// Verifies a single contest of an encrypted ballot, i.e., the range proof of every selection
+/// (Verification `5`) and the range proof of the selection limit (Verification `6`).
+fn verify_contest<'a>(
+    report: &mut VerificationReport,
+    batch: &mut RangeProofBatch<'a>,
+    prevoting: &PreVotingData,
+    ballot_object: &str,
+    contest_index: ContestIndex,
+    contest_encrypted: &'a ContestEncrypted,
+) {
+    let fixed_parameters = &prevoting.parameters.fixed_parameters;
+    let object = format!("{ballot_object}, contest {contest_index}");
+
+    let Some(contest) = prevoting.manifest.contests.get(contest_index) else {
+        report.fail(5, object, "the contest does not exist in the manifest");
+        return;
+    };
+    if !report.check(
+        5,
+        &object,
+        contest_encrypted.selection.len() == contest.options.len(),
+        format!(
+            "{} ciphertexts given, expected {}",
+            contest_encrypted.selection.len(),
+            contest.options.len()
+        ),
+    ) {
+        return;
+    }
+
+    let option_limits = contest.option_vote_limits();
+    for ((ct, j), &option_limit) in contest_encrypted
+        .selection
+        .iter()
+        .zip(1..)
+        .zip(&option_limits)
+    {
+        let option_object = format!("{object}, option {j}");
+        let opt_proof = Index::from_one_based_index(j)
+            .ok()
+            .and_then(|ix| contest_encrypted.proof_ballot_correctness.get(ix));
+        match opt_proof {
+            Some(proof) => {
+                batch.push(report, 5, option_object, proof, ct.clone(), option_limit);
+            }
+            None => report.fail(5, option_object, "the range proof is missing"),
+        }
+    }
+
+    let combined_ct =
+        ContestEncrypted::sum_selection_vector(fixed_parameters, &contest_encrypted.selection);
+    batch.push(
+        report,
+        6,
+        object,
+        &contest_encrypted.proof_selection_limit,
+        combined_ct,
+        contest.selection_limit,
+    );
+}
+
+/// Verifies the decryptions of the challenged ballots.
+///
+/// Every challenged ballot must have a
+/// [`ChallengedBallotDecryption`](crate::challenged_ballot::ChallengedBallotDecryption) that
+/// matches the ballot's ciphertexts and confirmation code (Verification `12`), and the decrypted
+/// plaintext must be an admissible vote (Verification `13`).
+pub fn verify_challenged_ballots(
+    report: &mut VerificationReport,
+    prevoting: &PreVotingData,
+    body: &ElectionRecordBody,
+) {
+    let mut decryptions_by_code = BTreeMap::new();
+    for decryption in &body.challenged_ballot_decryptions {
+        decryptions_by_code
+            .entry(decryption.confirmation_code)
+            .or_insert(decryption);
+    }
+
+    for ballot in body.challenged_ballots() {
+        let code = ballot.confirmation_code;
+        let object = format!("challenged ballot {code}");
+
+        let Some(decryption) = decryptions_by_code.get(&code) else {
+            report.fail(12, object, "the challenged ballot has no decryption");
+            continue;
+        };
+
+        report.record(12, &object, decryption.validate(prevoting, ballot));
+        report.record(13, object, decryption.validate_content(&prevoting.manifest));
+    }
+}
+
+/// Verifies the aggregation of the cast ballots into the encrypted tally.
+///
+/// This corresponds to Verification `8`. The encrypted tally is recomputed from all cast ballots
+/// and compared to the published one.
+pub fn verify_tally_aggregation<'a>(
+    report: &mut VerificationReport,
+    prevoting: &PreVotingData,
+    cast_ballots: impl IntoIterator<Item = &'a BallotEncrypted>,
+    encrypted_tally: &EncryptedTally,
+) {
+    let fixed_parameters = &prevoting.parameters.fixed_parameters;
+    let one = FieldElement::from(1u8, &fixed_parameters.field);
+
+    let mut tally_builder = BallotTallyBuilder::new(&prevoting.manifest, &prevoting.parameters);
+    for ballot in cast_ballots {
+        if !report.check(
+            8,
+            format!("ballot {}", ballot.confirmation_code),
+            tally_builder.update(ballot.scale(fixed_parameters, &one)),
+            "the cast ballot is not compatible with the manifest",
+        ) {
+            return;
+        }
+    }
+
+    let recomputed_tally = tally_builder.finalize();
+    for contest_index in recomputed_tally
+        .keys()
+        .chain(encrypted_tally.keys())
+        .collect::<BTreeSet<_>>()
+    {
+        report.check(
+            8,
+            format!("encrypted tally, contest {contest_index}"),
+            recomputed_tally.get(contest_index) == encrypted_tally.get(contest_index),
+            "the encrypted tally is not the aggregation of the cast ballots",
+        );
+    }
+}
+
+/// Verifies the decryption of the encrypted tally.
+///
+/// For every option of every contest in the encrypted tally, the decrypted tally must contain a
+/// [`VerifiableDecryption`](crate::verifiable_decryption::VerifiableDecryption) with a valid proof of correct decryption (Verification `9`), and the
+/// decrypted tally must not contain any other contests or options (Verification `10`).
+pub fn verify_tally_decryption(
+    report: &mut VerificationReport,
+    prevoting: &PreVotingData,
+    encrypted_tally: &EncryptedTally,
+    decrypted_tally: &DecryptedTally,
+) {
+    let fixed_parameters = &prevoting.parameters.fixed_parameters;
+
+    for contest_index in decrypted_tally.keys() {
+        report.check(
+            10,
+            format!("decrypted tally, contest {contest_index}"),
+            encrypted_tally.contains_key(contest_index),
+            "the contest is not in the encrypted tally",
+        );
+    }
+
+    for (contest_index, ciphertexts) in encrypted_tally {
+        let object = format!("decrypted tally, contest {contest_index}");
+        let Some(decryptions) = decrypted_tally.get(contest_index) else {
+            report.fail(
+                10,
+                object,
+                "the contest is missing from the decrypted tally",
+            );
+            continue;
+        };
+        if !report.check(
+            10,
+            &object,
+            decryptions.len() == ciphertexts.len(),
+            format!(
+                "{} decrypted options given, expected {}",
+                decryptions.len(),
+                ciphertexts.len()
+            ),
+        ) {
+            continue;
+        }
+
+        for (ciphertext, decryption, j) in itertools::izip!(ciphertexts, decryptions, 1..) {
+            report.record(
+                9,
+                format!("{object}, option {j}"),
+                decryption.validate(
+                    fixed_parameters,
+                    &prevoting.hashes_ext,
+                    &prevoting.public_key,
+                    ciphertext,
+                ),
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod test {
+    use std::collections::{BTreeMap, BTreeSet};
+    use std::iter::zip;
+
+    use util::{algebra::GroupElement, csprng::Csprng};
+
+    use super::*;
+    use crate::{
+        ballot::BallotState,
+        ballot_box::BallotBox,
+        ballot_style::BallotStyle,
+        challenged_ballot::ChallengedBallotDecryption,
+        contest_selection::ContestSelection,
+        device::Device,
+        election_manifest::{Contest, ContestOption, ElectionManifest},
+        example_election_parameters::example_election_parameters,
+        guardian_secret_key::GuardianSecretKey,
+        guardian_share::{GuardianEncryptedShare, GuardianSecretKeyShare},
+        joint_election_public_key::Ciphertext,
+        vendor_data::VendorData,
+        verifiable_decryption::{
+            CombinedDecryptionShare, DecryptionProof, DecryptionShare, VerifiableDecryption,
+        },
+        zk::ProofRangeValidationError,
+    };
+
+    fn tiny_manifest() -> ElectionManifest {
+        ElectionManifest {
+            label: "Tiny Election".to_string(),
+            contests: [Contest {
+                label: "Minister of Tiny Things".to_string(),
+                selection_limit: 1,
+                options: [
+                    ContestOption {
+                        label: "Alice".to_string(),
+                        opt_vote_limit: None,
+                        vendor_data: VendorData::default(),
+                    },
+                    ContestOption {
+                        label: "Bob".to_string(),
+                        opt_vote_limit: None,
+                        vendor_data: VendorData::default(),
+                    },
+                ]
+                .try_into()
+                .unwrap(),
+                vendor_data: VendorData::default(),
+            }]
+            .try_into()
+            .unwrap(),
+            ballot_styles: [BallotStyle {
+                label: "Tiny Ballot".to_string(),
+                contests: BTreeSet::from([ContestIndex::from_one_based_index(1).unwrap()]),
+                vendor_data: VendorData::default(),
+            }]
+            .try_into()
+            .unwrap(),
+            vendor_data: VendorData::default(),
+        }
+    }
+
+    fn decrypt(
+        csprng: &mut Csprng,
+        prevoting: &PreVotingData,
+        guardian_public_keys: &[GuardianPublicKey],
+        key_shares: &[GuardianSecretKeyShare],
+        ciphertext: &Ciphertext,
+    ) -> VerifiableDecryption {
+        let fixed_parameters = &prevoting.parameters.fixed_parameters;
+        let dec_shares: Vec<_> = key_shares
+            .iter()
+            .map(|ks| DecryptionShare::from(fixed_parameters, ks, ciphertext))
+            .collect();
+        let m = CombinedDecryptionShare::combine(&prevoting.parameters, &dec_shares).unwrap();
+        let (com_shares, com_states): (Vec<_>, Vec<_>) = key_shares
+            .iter()
+            .map(|ks| {
+                DecryptionProof::generate_commit_share(csprng, fixed_parameters, ciphertext, &ks.i)
+            })
+            .unzip();
+        let rsp_shares: Vec<_> = zip(&com_states, key_shares)
+            .map(|(state, ks)| {
+                DecryptionProof::generate_response_share(
+                    fixed_parameters,
+                    &prevoting.hashes_ext,
+                    &prevoting.public_key,
+                    ciphertext,
+                    &m,
+                    &com_shares,
+                    state,
+                    ks,
+                )
+                .unwrap()
+            })
+            .collect();
+        let proof = DecryptionProof::combine_proof(
+            &prevoting.parameters,
+            &prevoting.hashes_ext,
+            ciphertext,
+            &dec_shares,
+            &com_shares,
+            &rsp_shares,
+            guardian_public_keys,
+        )
+        .unwrap();
+        VerifiableDecryption::new(
+            fixed_parameters,
+            &prevoting.public_key,
+            ciphertext,
+            &m,
+            &proof,
+        )
+        .unwrap()
+    }
+
+    fn tiny_election_record() -> ElectionRecord {
+        let mut csprng = Csprng::new(b"test_verifier");
+        let election_parameters = example_election_parameters();
+
+        let guardian_secret_keys = election_parameters
+            .varying_parameters
+            .each_guardian_i()
+            .map(|i| GuardianSecretKey::generate(&mut csprng, &election_parameters, i, None))
+            .collect::<Vec<_>>();
+        let guardian_public_keys = guardian_secret_keys
+            .iter()
+            .map(|sk| sk.make_public_key())
+            .collect::<Vec<_>>();
+
+        let prevoting =
+            PreVotingData::compute(tiny_manifest(), election_parameters, &guardian_public_keys)
+                .unwrap();
+
+        let device = Device::new("Some encryption device", prevoting.clone());
+        let contest_index = ContestIndex::from_one_based_index(1).unwrap();
+        // The second ballot is challenged, the others are cast.
+        let mut ballot_box = BallotBox::new(&prevoting);
+        for (n, vote) in [vec![1, 0], vec![0, 1], vec![0, 1], vec![1, 0]]
+            .into_iter()
+            .enumerate()
+        {
+            let selections = BTreeMap::from([(
+                contest_index,
+                ContestSelection {
+                    vote,
+                    write_ins: Vec::new(),
+                },
+            )]);
+            let ballot =
+                BallotEncrypted::new_from_selections(&device, &mut csprng, &[n as u8], &selections)
+                    .unwrap();
+            if n == 1 {
+                ballot_box.challenge(ballot).unwrap();
+            } else {
+                ballot_box.cast(ballot).unwrap();
+            }
+        }
+        let mut body = ballot_box.into_election_record_body(&prevoting).unwrap();
+        body.challenged_ballot_decryptions = vec![ChallengedBallotDecryption::from_primary_nonce(
+            &prevoting,
+            &body.all_ballots[1],
+            &[1u8],
+        )
+        .unwrap()];
+        let encrypted_tally = body.encrypted_tally.clone().unwrap();
+
+        // Only a quorum of k guardians take part in the decryption.
+        let k = prevoting.parameters.varying_parameters.k.as_quantity();
+        let key_shares = guardian_secret_keys
+            .iter()
+            .take(k)
+            .map(|sk| {
+                let shares = guardian_secret_keys
+                    .iter()
+                    .map(|dealer_sk| {
+                        GuardianEncryptedShare::encrypt(
+                            &mut csprng,
+                            &prevoting.parameters,
+                            dealer_sk,
+                            &sk.make_public_key(),
+                        )
+                        .ciphertext
+                    })
+                    .collect::<Vec<_>>();
+                GuardianSecretKeyShare::compute(
+                    &prevoting.parameters,
+                    &guardian_public_keys,
+                    &shares,
+                    sk,
+                )
+                .unwrap()
+            })
+            .collect::<Vec<_>>();
+
+        let decrypted_tally = encrypted_tally
+            .iter()
+            .map(|(&contest_index, cts)| {
+                let decryptions = cts
+                    .iter()
+                    .map(|ct| {
+                        decrypt(
+                            &mut csprng,
+                            &prevoting,
+                            &guardian_public_keys,
+                            &key_shares,
+                            ct,
+                        )
+                    })
+                    .collect();
+                (contest_index, decryptions)
+            })
+            .collect();
+
+        body.decrypted_tally = Some(decrypted_tally);
+
+        ElectionRecord {
+            prevoting,
+            guardian_public_keys,
+            share_disputes: Vec::new(),
+            body,
+        }
+    }
+
+    #[test]
+    fn test_verify_election_record() {
+        let mut csprng = Csprng::new(b"test_verify_election_record");
+        let mut election_record = tiny_election_record();
+        let report = verify_election_record(&election_record, &mut csprng);
+        assert!(report.is_success(), "{report}");
+
+        // A ballot box entry that does not match its ballot is detected.
+        let entry = &mut election_record.body.ballot_box_entries[1];
+        entry.state = BallotState::Cast;
+        entry.entry_hash = entry.compute_hash(&election_record.prevoting.hashes_ext.h_e);
+        let report = verify_election_record(&election_record, &mut csprng);
+        let failures: Vec<_> = report.failures().collect();
+        assert_eq!(failures.len(), 1, "{report}");
+        assert_eq!(
+            (failures[0].step, failures[0].object.as_str()),
+            (7, "ballot box")
+        );
+        let entry = &mut election_record.body.ballot_box_entries[1];
+        entry.state = BallotState::Challenged;
+        entry.entry_hash = entry.compute_hash(&election_record.prevoting.hashes_ext.h_e);
+
+        let contest_index = ContestIndex::from_one_based_index(1).unwrap();
+
+        // Ciphertexts with valid range proofs swapped between two cast ballots with the same
+        // votes leave the tally intact, but do not match the contest hashes.
+        let swap_ciphertexts = |election_record: &mut ElectionRecord| {
+            let ballots = &mut election_record.body.all_ballots;
+            let (first, last) = ballots.split_at_mut(3);
+            let contest_0 = first[0].contests.get_mut(&contest_index).unwrap();
+            let contest_3 = last[0].contests.get_mut(&contest_index).unwrap();
+            std::mem::swap(&mut contest_0.selection, &mut contest_3.selection);
+            std::mem::swap(
+                &mut contest_0.proof_ballot_correctness,
+                &mut contest_3.proof_ballot_correctness,
+            );
+            std::mem::swap(
+                &mut contest_0.proof_selection_limit,
+                &mut contest_3.proof_selection_limit,
+            );
+            std::mem::swap(&mut contest_0.contest_data, &mut contest_3.contest_data);
+        };
+        let code_0 = election_record.body.all_ballots[0].confirmation_code;
+        let code_3 = election_record.body.all_ballots[3].confirmation_code;
+        swap_ciphertexts(&mut election_record);
+        let report = verify_election_record(&election_record, &mut csprng);
+        let failed_objects: Vec<_> = report
+            .failures()
+            .map(|check| (check.step, check.object.clone()))
+            .collect();
+        assert_eq!(
+            failed_objects,
+            vec![
+                (7, format!("ballot {code_0}, contest 1")),
+                (7, format!("ballot {code_3}, contest 1")),
+            ],
+            "{report}"
+        );
+
+        // Swapping the contest hashes along with them breaks the confirmation codes instead.
+        let swap_contest_hashes = |election_record: &mut ElectionRecord| {
+            let ballots = &mut election_record.body.all_ballots;
+            let (first, last) = ballots.split_at_mut(3);
+            std::mem::swap(
+                &mut first[0]
+                    .contests
+                    .get_mut(&contest_index)
+                    .unwrap()
+                    .contest_hash,
+                &mut last[0]
+                    .contests
+                    .get_mut(&contest_index)
+                    .unwrap()
+                    .contest_hash,
+            );
+        };
+        swap_contest_hashes(&mut election_record);
+        let report = verify_election_record(&election_record, &mut csprng);
+        let failed_objects: Vec<_> = report
+            .failures()
+            .map(|check| (check.step, check.object.clone()))
+            .collect();
+        assert_eq!(
+            failed_objects,
+            vec![
+                (7, format!("ballot {code_0}")),
+                (7, format!("ballot {code_3}"))
+            ],
+            "{report}"
+        );
+        swap_contest_hashes(&mut election_record);
+        swap_ciphertexts(&mut election_record);
+        let decrypted_contest = election_record
+            .body
+            .decrypted_tally
+            .as_ref()
+            .unwrap()
+            .get(&contest_index)
+            .unwrap();
+        let field = &election_record.prevoting.parameters.fixed_parameters.field;
+        assert_eq!(
+            decrypted_contest
+                .iter()
+                .map(|d| d.plain_text.clone())
+                .collect::<Vec<_>>(),
+            vec![
+                FieldElement::from(2u8, field),
+                FieldElement::from(1u8, field)
+            ]
+        );
+
+        // A challenged ballot without decryption is detected.
+        let challenged_ballot_decryption =
+            election_record.body.challenged_ballot_decryptions.remove(0);
+        let report = verify_election_record(&election_record, &mut csprng);
+        let failures: Vec<_> = report.failures().collect();
+        assert_eq!(failures.len(), 1, "{report}");
+        assert_eq!(failures[0].step, 12);
+        assert_eq!(
+            failures[0].object,
+            format!(
+                "challenged ballot {}",
+                challenged_ballot_decryption.confirmation_code
+            )
+        );
+        election_record
+            .body
+            .challenged_ballot_decryptions
+            .push(challenged_ballot_decryption);
+
+        // A wrong decrypted tally is detected.
+        let decrypted_tally = election_record.body.decrypted_tally.as_mut().unwrap();
+        let decryption = &mut decrypted_tally.get_mut(&contest_index).unwrap()[0];
+        decryption.plain_text = FieldElement::from(3u8, field);
+        let report = verify_election_record(&election_record, &mut csprng);
+        let failures: Vec<_> = report.failures().collect();
+        assert_eq!(failures.len(), 1, "{report}");
+        assert_eq!(failures[0].step, 9);
+        assert_eq!(failures[0].object, "decrypted tally, contest 1, option 1");
+
+        // Dropping a cast ballot invalidates the encrypted tally.
+        election_record.body.decrypted_tally = None;
+        election_record.body.all_ballots.pop();
+        election_record.body.ballot_box_entries.pop();
+        let report = verify_election_record(&election_record, &mut csprng);
+        let failures: Vec<_> = report.failures().collect();
+        assert_eq!(failures.len(), 1, "{report}");
+        assert_eq!(failures[0].step, 8);
+        assert_eq!(failures[0].object, "encrypted tally, contest 1");
+
+        // A tampered ballot is detected.
+        let ballot = &mut election_record.body.all_ballots[0];
+        let contest = ballot.contests.get_mut(&contest_index).unwrap();
+        contest.selection.swap(0, 1);
+        let report = verify_election_record(&election_record, &mut csprng);
+        let code = election_record.body.all_ballots[0].confirmation_code;
+        let failed_objects: Vec<_> = report
+            .failures()
+            .map(|check| (check.step, check.object.clone()))
+            .collect();
+        assert_eq!(
+            failed_objects,
+            vec![
+                (7, format!("ballot {code}, contest 1")),
+                (5, format!("ballot {code}, contest 1, option 1")),
+                (5, format!("ballot {code}, contest 1, option 2")),
+                (8, "encrypted tally, contest 1".to_string()),
+            ],
+            "{report}"
+        );
+
+        // A ciphertext that is not in the group fails the batch check and is pinpointed.
+        let group = &election_record.prevoting.parameters.fixed_parameters.group;
+        let invalid: GroupElement = serde_json::from_str("\"base16:2\"").unwrap();
+        assert!(!invalid.is_valid(group));
+        let ballot = &mut election_record.body.all_ballots[0];
+        let contest = ballot.contests.get_mut(&contest_index).unwrap();
+        contest.selection.swap(0, 1);
+        contest.selection[0].alpha = invalid;
+        let report = verify_election_record(&election_record, &mut csprng);
+        let failures: Vec<_> = report.failures().collect();
+        assert_eq!(failures.len(), 4, "{report}");
+        assert_eq!(
+            (failures[0].step, failures[0].object.clone()),
+            (7, format!("ballot {code}, contest 1"))
+        );
+        assert_eq!(
+            (failures[1].step, failures[1].object.clone()),
+            (5, format!("ballot {code}, contest 1, option 1"))
+        );
+        assert_eq!(
+            failures[1].reason,
+            Some(ProofRangeValidationError::CiphertextNotInGroup.to_string())
+        );
+        assert_eq!(
+            (failures[2].step, failures[2].object.clone()),
+            (6, format!("ballot {code}, contest 1"))
+        );
+        assert_eq!(failures[3].step, 8);
+    }
+}
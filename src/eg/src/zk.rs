@@ -9,9 +9,11 @@
 //! For more details see Section `3.3.5` of the Electionguard specification `2.0.0`.
 
 use serde::{Deserialize, Serialize};
+use thiserror::Error;
 use util::{
     algebra::{FieldElement, GroupElement, ScalarField},
     csprng::Csprng,
+    multi_exp::{batch_is_valid, multi_exp},
 };
 
 use crate::{
@@ -22,12 +24,21 @@ use crate::{
     vec1::HasIndexTypeMarker,
 };
 
+/// A challenge-response pair of a [`ProofRange`] together with its commitments.
+///
+/// The specification publishes only the challenge and the response, from which the commitments
+/// are recomputed (5.1, 5.2). They are published as well so that many proofs can be validated
+/// together, see [`ProofRange::validate_batch`].
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ProofRangeSingle {
     /// Challenge
     pub c: FieldElement,
     /// Response
     pub v: FieldElement,
+    /// Commitment `a_j = g^v_j * alpha^c_j`
+    pub a: GroupElement,
+    /// Commitment `b_j = K^w_j * beta^c_j`
+    pub b: GroupElement,
 }
 
 /// A 1-based index of a [`ProofRange`] in the order it is stored in the [`crate::contest_encrypted::ContestEncrypted`].
@@ -36,6 +47,30 @@ pub type ProofRangeIndex = Index<ProofRange>;
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ProofRange(Vec<ProofRangeSingle>);
 
+/// Represents errors occurring during the validation of a range proof.
+#[derive(Error, Debug, PartialEq)]
+pub enum ProofRangeValidationError {
+    /// Occurs if the proof does not contain one challenge-response pair per value in the range.
+    #[error("The proof has {found} challenge-response pairs, but {expected} are required.")]
+    WrongLength { expected: usize, found: usize },
+    /// Occurs if the ciphertext is not a pair of valid group elements (Check `5.A`).
+    #[error("The ciphertext is not a pair of valid elements in Z_p^r (5.A).")]
+    CiphertextNotInGroup,
+    /// Occurs if a challenge is not a valid field element (Check `5.B`).
+    #[error("The challenge c_{0} is not a valid element in Z_q (5.B).")]
+    ChallengeNotInField(usize),
+    /// Occurs if a response is not a valid field element (Check `5.C`).
+    #[error("The response v_{0} is not a valid element in Z_q (5.C).")]
+    ResponseNotInField(usize),
+    /// Occurs if the computed challenge does not match the sum of the given ones (Check `5.D`).
+    #[error("The computed challenge does not match the sum of the given ones (5.D).")]
+    ChallengeMismatch,
+    /// Occurs if the given commitments do not match the ones computed from the challenge and
+    /// the response (Equations `5.1` and `5.2`).
+    #[error("The commitments a_{0}, b_{0} do not match the challenge and response (5.1, 5.2).")]
+    CommitmentMismatch(usize),
+}
+
 impl HasIndexTypeMarker for ProofRange {}
 
 impl ProofRange {
@@ -104,7 +139,7 @@ impl ProofRange {
             .map(|_| field.random_field_elem(csprng))
             .collect::<Vec<FieldElement>>();
         let a = (0..big_l + 1)
-            .map(|j| group.g_exp(&u[j]))
+            .map(|j| group.g_exp_secret(&u[j]))
             .collect::<Vec<GroupElement>>();
         let l_scalar = FieldElement::from(small_l, field);
         let mut t = u.clone();
@@ -116,7 +151,7 @@ impl ProofRange {
             }
         }
         let b = (0..big_l + 1)
-            .map(|j| pvd.public_key.joint_election_public_key.exp(&t[j], group))
+            .map(|j| pvd.public_key.exp_secret(&t[j], group))
             .collect::<Vec<GroupElement>>();
 
         // Compute real challenge c_{small_l}
@@ -138,6 +173,8 @@ impl ProofRange {
                 .map(|j| ProofRangeSingle {
                     c: c[j].clone(),
                     v: v[j].clone(),
+                    a: a[j].clone(),
+                    b: b[j].clone(),
                 })
                 .collect(),
         )
@@ -151,58 +188,274 @@ impl ProofRange {
     /// - `ct` - the ciphertext
     /// - `big_l` - the range bound
     ///
-    /// This is essentially Verification (5).
+    /// This is essentially Verification (5). See [`ProofRange::validate`] for the reason of a
+    /// failed verification.
     pub fn verify(&self, pvd: &PreVotingData, ct: &Ciphertext, big_l: usize) -> bool {
+        self.validate(pvd, ct, big_l).is_ok()
+    }
+
+    /// This function validates a [`ProofRange`] with respect to a given [`Ciphertext`] and context.
+    ///
+    /// The arguments are
+    /// - `self` - the range proof
+    /// - `pvd` - the pre-voting data
+    /// - `ct` - the ciphertext
+    /// - `big_l` - the range bound
+    ///
+    /// This is essentially Verification (5). If the proof is invalid, the error states which
+    /// check failed.
+    pub fn validate(
+        &self,
+        pvd: &PreVotingData,
+        ct: &Ciphertext,
+        big_l: usize,
+    ) -> Result<(), ProofRangeValidationError> {
+        let group = &pvd.parameters.fixed_parameters.group;
+
+        self.validate_length(big_l)?;
+
+        // Verification check (5.A) alpha, beta are valid group elements
+        if !ct.alpha.is_valid(group) || !ct.beta.is_valid(group) {
+            return Err(ProofRangeValidationError::CiphertextNotInGroup);
+        }
+
+        self.validate_responses(pvd)?;
+        self.validate_challenge(pvd, ct)?;
+        self.validate_commitments(pvd, ct)
+    }
+
+    /// This function validates many [`ProofRange`]s, each with respect to its [`Ciphertext`]
+    /// and range bound, in the same context.
+    ///
+    /// The arguments are
+    /// - `pvd` - the pre-voting data
+    /// - `proofs` - the range proofs together with their ciphertext and range bound
+    /// - `csprng` - secure randomness generator, which must be unpredictable to the prover
+    ///
+    /// Returns the same result for every proof as [`ProofRange::validate`], except with
+    /// probability at most `2^-64`.
+    ///
+    /// The challenges (5.D) are checked for every proof on its own, which needs no
+    /// exponentiation as the commitments are part of the proof. The group membership (5.A) of
+    /// all ciphertexts and commitments is checked with [`batch_is_valid`]. Then the Equations
+    /// `5.1` and `5.2` of all proofs, `a_j = g^v_j * alpha^c_j` and `b_j = K^w_j * beta^c_j`,
+    /// are checked at once by a random linear combination with `64`-bit coefficients `r_j` and
+    /// `s_j`:
+    ///
+    /// `prod(a_j^r_j * b_j^s_j) = g^sum(r_j v_j) * K^sum(s_j w_j) * prod(alpha^sum(r_j c_j) * beta^sum(s_j c_j))`
+    ///
+    /// Both sides are computed with [`multi_exp`], so a commitment costs a share of a
+    /// multi-exponentiation with a `64`-bit exponent instead of a full exponentiation. If a batch
+    /// check fails, the proofs of the batch are validated individually to pinpoint the invalid
+    /// ones.
+    pub fn validate_batch(
+        pvd: &PreVotingData,
+        proofs: &[(&ProofRange, &Ciphertext, usize)],
+        csprng: &mut Csprng,
+    ) -> Vec<Result<(), ProofRangeValidationError>> {
+        // The checks without exponentiations, and the full validation of any failing proof to
+        // report the same error as `validate`.
+        let mut results = proofs
+            .iter()
+            .map(|(proof, ct, big_l)| {
+                proof
+                    .validate_length(*big_l)
+                    .and_then(|_| proof.validate_responses(pvd))
+                    .and_then(|_| proof.validate_challenge(pvd, ct))
+                    .or_else(|_| proof.validate(pvd, ct, *big_l))
+            })
+            .collect::<Vec<_>>();
+
+        let pending = proofs
+            .iter()
+            .zip(&results)
+            .filter(|(_, result)| result.is_ok())
+            .map(|(item, _)| *item)
+            .collect::<Vec<_>>();
+        if Self::batch_is_valid(pvd, &pending, csprng) {
+            return results;
+        }
+
+        for ((proof, ct, big_l), result) in proofs.iter().zip(results.iter_mut()) {
+            if result.is_ok() {
+                *result = proof.validate(pvd, ct, *big_l);
+            }
+        }
+        results
+    }
+
+    /// Checks the group membership (5.A) of the ciphertexts and commitments and the Equations
+    /// `5.1` and `5.2` of all given proofs, see [`ProofRange::validate_batch`].
+    ///
+    /// The proofs must have the right length and valid challenges and responses.
+    fn batch_is_valid(
+        pvd: &PreVotingData,
+        proofs: &[(&ProofRange, &Ciphertext, usize)],
+        csprng: &mut Csprng,
+    ) -> bool {
         let field = &pvd.parameters.fixed_parameters.field;
         let group = &pvd.parameters.fixed_parameters.group;
 
-        // (5.1)
-        let a = (0..big_l + 1)
-            .map(|j| {
-                group
-                    .g_exp(&self.0[j].v)
-                    .mul(&ct.alpha.exp(&self.0[j].c, group), group)
+        let elements = proofs
+            .iter()
+            .flat_map(|(proof, ct, _)| {
+                let commitments = proof.0.iter().flat_map(|pf| [&pf.a, &pf.b]);
+                [&ct.alpha, &ct.beta].into_iter().chain(commitments)
             })
-            .collect::<Vec<GroupElement>>();
-        // (5.2)
-        let w = (0..big_l + 1)
-            .map(|j| {
+            .collect::<Vec<_>>();
+        if !batch_is_valid(&elements, group, csprng) {
+            return false;
+        }
+
+        // The random coefficients r_j, s_j of every commitment.
+        let coefficients = proofs
+            .iter()
+            .map(|(proof, ..)| {
+                proof
+                    .0
+                    .iter()
+                    .map(|_| {
+                        (
+                            FieldElement::from(csprng.next_u64(), field),
+                            FieldElement::from(csprng.next_u64(), field),
+                        )
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+
+        let mut exp_g = ScalarField::zero();
+        let mut exp_k = ScalarField::zero();
+        let mut exps_ct = Vec::with_capacity(proofs.len());
+        for ((proof, ..), coefficients) in proofs.iter().zip(&coefficients) {
+            let mut exp_alpha = ScalarField::zero();
+            let mut exp_beta = ScalarField::zero();
+            for (j, (pf, (r, s))) in proof.0.iter().zip(coefficients).enumerate() {
+                // w_j = v_j - j*c_j
                 let j_scalar = FieldElement::from(j, field);
-                self.0[j].v.sub(&j_scalar.mul(&self.0[j].c, field), field)
+                let w = pf.v.sub(&j_scalar.mul(&pf.c, field), field);
+
+                exp_g = exp_g.add(&r.mul(&pf.v, field), field);
+                exp_k = exp_k.add(&s.mul(&w, field), field);
+                exp_alpha = exp_alpha.add(&r.mul(&pf.c, field), field);
+                exp_beta = exp_beta.add(&s.mul(&pf.c, field), field);
+            }
+            exps_ct.push((exp_alpha, exp_beta));
+        }
+
+        let lhs_terms = proofs
+            .iter()
+            .zip(&coefficients)
+            .flat_map(|((proof, ..), coefficients)| {
+                proof
+                    .0
+                    .iter()
+                    .zip(coefficients)
+                    .flat_map(|(pf, (r, s))| [(&pf.a, r), (&pf.b, s)])
             })
-            .collect::<Vec<FieldElement>>();
-        let b = (0..big_l + 1)
-            .map(|j| {
-                let k_w = pvd.public_key.joint_election_public_key.exp(&w[j], group);
-                let b_c = ct.beta.exp(&self.0[j].c, group);
-                k_w.mul(&b_c, group)
+            .collect::<Vec<_>>();
+        let rhs_terms = proofs
+            .iter()
+            .zip(&exps_ct)
+            .flat_map(|((_, ct, _), (exp_alpha, exp_beta))| {
+                [(&ct.alpha, exp_alpha), (&ct.beta, exp_beta)]
             })
-            .collect::<Vec<GroupElement>>();
-        // (5.3)
-        let c = Self::challenge(pvd, ct, &a, &b);
+            .collect::<Vec<_>>();
 
-        // Verification check (5.A) alpha, beta are valid group elements
-        if !ct.alpha.is_valid(group) || !ct.beta.is_valid(group) {
-            return false;
+        let lhs = multi_exp(&lhs_terms, group);
+        let rhs = group
+            .g_exp(&exp_g)
+            .mul(&pvd.public_key.exp(&exp_k, group), group)
+            .mul(&multi_exp(&rhs_terms, group), group);
+        lhs == rhs
+    }
+
+    /// Checks that the proof contains exactly one challenge-response pair per value in the range.
+    fn validate_length(&self, big_l: usize) -> Result<(), ProofRangeValidationError> {
+        if self.0.len() != big_l + 1 {
+            return Err(ProofRangeValidationError::WrongLength {
+                expected: big_l + 1,
+                found: self.0.len(),
+            });
         }
-        for j in 0..big_l + 1 {
+        Ok(())
+    }
+
+    /// Performs the verification checks (5.B) and (5.C).
+    fn validate_responses(&self, pvd: &PreVotingData) -> Result<(), ProofRangeValidationError> {
+        let field = &pvd.parameters.fixed_parameters.field;
+
+        for (j, pf) in self.0.iter().enumerate() {
             // Verification check (5.B) 0 <= c_j < 2^256
             // This is enforced by c_j being a valid field element (q < 2^256 for standard parameter)
-            if !self.0[j].c.is_valid(field) {
-                return false;
+            if !pf.c.is_valid(field) {
+                return Err(ProofRangeValidationError::ChallengeNotInField(j));
             }
             // Verification check (5.C) v_j is a valid field element
-            if !self.0[j].v.is_valid(field) {
-                return false;
+            if !pf.v.is_valid(field) {
+                return Err(ProofRangeValidationError::ResponseNotInField(j));
             }
         }
+        Ok(())
+    }
+
+    /// Computes the challenge (5.3) from the given commitments and performs the verification
+    /// check (5.D).
+    fn validate_challenge(
+        &self,
+        pvd: &PreVotingData,
+        ct: &Ciphertext,
+    ) -> Result<(), ProofRangeValidationError> {
+        let field = &pvd.parameters.fixed_parameters.field;
+
+        // (5.3)
+        let a = self.0.iter().map(|pf| pf.a.clone()).collect::<Vec<_>>();
+        let b = self.0.iter().map(|pf| pf.b.clone()).collect::<Vec<_>>();
+        let c = Self::challenge(pvd, ct, &a, &b);
 
         // Verification check (5.D)
         let rhs = self
             .0
             .iter()
             .fold(ScalarField::zero(), |acc, pf| acc.add(&pf.c, field));
-        c == rhs
+        if c != rhs {
+            return Err(ProofRangeValidationError::ChallengeMismatch);
+        }
+
+        Ok(())
+    }
+
+    /// Recomputes the commitments (5.1, 5.2) and checks that they match the given ones.
+    ///
+    /// The powers `g^v_j` and `K^w_j` are computed with the cached fixed-base tables, so every
+    /// commitment costs a single generic exponentiation `alpha^c_j` or `beta^c_j`. This is faster
+    /// than a two-term [`multi_exp`], which has to square the product for every bit of the
+    /// exponents.
+    fn validate_commitments(
+        &self,
+        pvd: &PreVotingData,
+        ct: &Ciphertext,
+    ) -> Result<(), ProofRangeValidationError> {
+        let field = &pvd.parameters.fixed_parameters.field;
+        let group = &pvd.parameters.fixed_parameters.group;
+
+        for (j, pf) in self.0.iter().enumerate() {
+            // (5.1) a_j = g^v_j * alpha^c_j
+            let a = group.g_exp(&pf.v).mul(&ct.alpha.exp(&pf.c, group), group);
+            // (5.2) b_j = K^w_j * beta^c_j with w_j = v_j - j*c_j
+            let j_scalar = FieldElement::from(j, field);
+            let w = pf.v.sub(&j_scalar.mul(&pf.c, field), field);
+            let b = pvd
+                .public_key
+                .exp(&w, group)
+                .mul(&ct.beta.exp(&pf.c, group), group);
+
+            if a != pf.a || b != pf.b {
+                return Err(ProofRangeValidationError::CommitmentMismatch(j));
+            }
+        }
+
+        Ok(())
     }
 }
 
@@ -373,3 +626,117 @@ impl ProofGuardian {
     }
 }
 */
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod test {
+    use util::algebra::GroupElement;
+
+    use super::*;
+    use crate::{
+        example_election_manifest::example_election_manifest,
+        example_election_parameters::example_election_parameters,
+        guardian_secret_key::GuardianSecretKey,
+    };
+
+    #[test]
+    fn test_validate_batch() {
+        let mut csprng = Csprng::new(b"test_validate_batch");
+        let election_parameters = example_election_parameters();
+        let guardian_public_keys = election_parameters
+            .varying_parameters
+            .each_guardian_i()
+            .map(|i| {
+                GuardianSecretKey::generate(&mut csprng, &election_parameters, i, None)
+                    .make_public_key()
+            })
+            .collect::<Vec<_>>();
+        let pvd = PreVotingData::compute(
+            example_election_manifest(),
+            election_parameters,
+            &guardian_public_keys,
+        )
+        .unwrap();
+        let fixed_parameters = &pvd.parameters.fixed_parameters;
+        let field = &fixed_parameters.field;
+
+        // Encryptions of the votes with range bounds (vote, big_l)
+        let votes = [(0, 1), (1, 1), (2, 3), (0, 2), (1, 1)];
+        let (mut cts, mut proofs): (Vec<_>, Vec<_>) = votes
+            .iter()
+            .map(|&(vote, big_l)| {
+                let nonce = Nonce::new(field.random_field_elem(&mut csprng));
+                let ct = pvd
+                    .public_key
+                    .encrypt_with(fixed_parameters, &nonce.xi, vote);
+                let proof = ProofRange::new(&pvd, &mut csprng, &ct, &nonce, vote, big_l);
+                (ct, proof)
+            })
+            .unzip();
+
+        let batch = |proofs: &[ProofRange], cts: &[Ciphertext], csprng: &mut Csprng| {
+            let items = proofs
+                .iter()
+                .zip(cts)
+                .zip(&votes)
+                .map(|((proof, ct), &(_, big_l))| (proof, ct, big_l))
+                .collect::<Vec<_>>();
+            ProofRange::validate_batch(&pvd, &items, csprng)
+        };
+        assert!(batch(&proofs, &cts, &mut csprng).iter().all(Result::is_ok));
+
+        // A proof for another ciphertext is pinpointed by its challenge.
+        cts.swap(0, 1);
+        let results = batch(&proofs, &cts, &mut csprng);
+        assert_eq!(
+            results,
+            vec![
+                Err(ProofRangeValidationError::ChallengeMismatch),
+                Err(ProofRangeValidationError::ChallengeMismatch),
+                Ok(()),
+                Ok(()),
+                Ok(()),
+            ]
+        );
+        cts.swap(0, 1);
+
+        // A wrong response passes the challenge check, but fails the random linear combination
+        // and is pinpointed individually.
+        let v = proofs[2].0[1].v.clone();
+        proofs[2].0[1].v = v.add(&FieldElement::from(1_u8, field), field);
+        let results = batch(&proofs, &cts, &mut csprng);
+        assert_eq!(
+            results,
+            vec![
+                Ok(()),
+                Ok(()),
+                Err(ProofRangeValidationError::CommitmentMismatch(1)),
+                Ok(()),
+                Ok(()),
+            ]
+        );
+        proofs[2].0[1].v = v;
+        assert!(batch(&proofs, &cts, &mut csprng).iter().all(Result::is_ok));
+
+        // A ciphertext outside the group fails the batch check and is pinpointed individually.
+        let invalid: GroupElement = serde_json::from_str("\"base16:2\"").unwrap();
+        assert!(!invalid.is_valid(&fixed_parameters.group));
+        cts[3].beta = invalid;
+        let results = batch(&proofs, &cts, &mut csprng);
+        assert_eq!(
+            results,
+            vec![
+                Ok(()),
+                Ok(()),
+                Ok(()),
+                Err(ProofRangeValidationError::CiphertextNotInGroup),
+                Ok(()),
+            ]
+        );
+        for (result, ((proof, ct), &(_, big_l))) in
+            results.iter().zip(proofs.iter().zip(&cts).zip(&votes))
+        {
+            assert_eq!(result, &proof.validate(&pvd, ct, big_l));
+        }
+    }
+}
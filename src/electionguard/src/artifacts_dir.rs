@@ -30,7 +30,19 @@ pub(crate) enum ArtifactFile {
     VoterSelection(u128, u64),
     GuardianSecretKey(GuardianIndex),
     GuardianPublicKey(GuardianIndex),
+    GuardianEncryptedShare(GuardianIndex, GuardianIndex),
+    ShareDispute(GuardianIndex, GuardianIndex),
+    GuardianSecretKeyShare(GuardianIndex),
+    GuardianTallyDecryptionShares(GuardianIndex),
+    GuardianTallyDecryptionState(GuardianIndex),
+    GuardianTallyDecryptionStateUsed(GuardianIndex, HValue),
+    GuardianTallyDecryptionResponses(GuardianIndex),
     JointElectionPublicKey,
+    EncryptedTally,
+    TallyDecryptionQuorum,
+    TallyDecryptionShares,
+    DecryptedTally,
+    ElectionRecord,
 }
 
 impl std::fmt::Display for ArtifactFile {
@@ -44,6 +56,10 @@ fn election_public_dir() -> PathBuf {
     "public".into()
 }
 
+fn encrypted_ballots_dir() -> PathBuf {
+    "record/ballots".into()
+}
+
 fn guardian_secret_dir(i: GuardianIndex) -> PathBuf {
     format!("SECRET_for_guardian_{i}").into()
 }
@@ -70,14 +86,10 @@ impl From<ArtifactFile> for PathBuf {
             PreEncryptedBallotMetadata(ts) => Path::new("pre_encrypted/ballots/")
                 .join(format!("{ts}"))
                 .join(format!("metadata.{ts}.dat")),
-            EncryptedBallot(ts, i) => {
-                Path::new("record/ballots/")
-                    .join(format!("{ts}"))
-                    .join(format!(
-                        "ballot.{}.json",
-                        i.to_string_hex_no_prefix_suffix()
-                    ))
-            }
+            EncryptedBallot(ts, i) => encrypted_ballots_dir().join(format!("{ts}")).join(format!(
+                "ballot.{}.json",
+                i.to_string_hex_no_prefix_suffix()
+            )),
             PreEncryptedBallot(ts, i) => Path::new("pre_encrypted/ballots/")
                 .join(format!("{ts}"))
                 .join(format!(
@@ -110,8 +122,35 @@ impl From<ArtifactFile> for PathBuf {
             GuardianPublicKey(i) => {
                 election_public_dir().join(format!("guardian_{i}.public_key.json"))
             }
+            GuardianEncryptedShare(i, l) => election_public_dir().join(format!(
+                "guardian_{i}.encrypted_share_for_guardian_{l}.json"
+            )),
+            ShareDispute(i, l) => election_public_dir()
+                .join(format!("guardian_{i}.share_dispute_by_guardian_{l}.json")),
+            GuardianSecretKeyShare(i) => {
+                guardian_secret_dir(i).join(format!("guardian_{i}.SECRET_key_share.json"))
+            }
+            GuardianTallyDecryptionShares(i) => {
+                election_public_dir().join(format!("guardian_{i}.tally_decryption_shares.json"))
+            }
+            GuardianTallyDecryptionState(i) => guardian_secret_dir(i)
+                .join(format!("guardian_{i}.SECRET_tally_decryption_state.json")),
+            GuardianTallyDecryptionStateUsed(i, h_commitments) => {
+                guardian_secret_dir(i).join(format!(
+                    "guardian_{i}.tally_decryption_state_used.{}.json",
+                    h_commitments.to_string_hex_no_prefix_suffix()
+                ))
+            }
+            GuardianTallyDecryptionResponses(i) => {
+                election_public_dir().join(format!("guardian_{i}.tally_decryption_responses.json"))
+            }
             JointElectionPublicKey => election_public_dir().join("joint_election_public_key.json"),
             HashesExt => election_public_dir().join("hashes_ext.json"),
+            EncryptedTally => election_public_dir().join("encrypted_tally.json"),
+            TallyDecryptionQuorum => election_public_dir().join("tally_decryption_quorum.json"),
+            TallyDecryptionShares => election_public_dir().join("tally_decryption_shares.json"),
+            DecryptedTally => election_public_dir().join("decrypted_tally.json"),
+            ElectionRecord => Path::new("record").join("election_record.json"),
         }
     }
 }
@@ -143,6 +182,41 @@ impl ArtifactsDir {
         self.path(artifact_file).try_exists().unwrap_or_default()
     }
 
+    /// Returns the paths of all encrypted ballot files in the artifacts directory,
+    /// i.e., every [`ArtifactFile::EncryptedBallot`], in sorted order.
+    pub fn encrypted_ballot_paths(&self) -> Result<Vec<PathBuf>> {
+        let ballots_dir = self.dir_path.join(encrypted_ballots_dir());
+
+        let mut paths = Vec::new();
+        if !ballots_dir.try_exists().unwrap_or_default() {
+            return Ok(paths);
+        }
+
+        for batch_entry in std::fs::read_dir(&ballots_dir)
+            .with_context(|| format!("Couldn't read directory: {}", ballots_dir.display()))?
+        {
+            let batch_path = batch_entry?.path();
+            if !batch_path.is_dir() {
+                continue;
+            }
+            for ballot_entry in std::fs::read_dir(&batch_path)
+                .with_context(|| format!("Couldn't read directory: {}", batch_path.display()))?
+            {
+                let ballot_path = ballot_entry?.path();
+                let is_ballot_file = ballot_path
+                    .file_name()
+                    .and_then(|name| name.to_str())
+                    .is_some_and(|name| name.starts_with("ballot.") && name.ends_with(".json"));
+                if is_ballot_file {
+                    paths.push(ballot_path);
+                }
+            }
+        }
+
+        paths.sort();
+        Ok(paths)
+    }
+
     /// Opens the specified artifact file according to the provided options.
     /// Returns the file and its path.
     pub fn open(
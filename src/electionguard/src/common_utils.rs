@@ -11,10 +11,24 @@ use anyhow::{ensure, Context, Result};
 use rand_core::{OsRng, RngCore};
 
 use eg::{
-    election_manifest::ElectionManifest, election_parameters::ElectionParameters,
-    example_election_manifest::example_election_manifest, guardian::GuardianIndex,
-    guardian_public_key::GuardianPublicKey, guardian_secret_key::GuardianSecretKey, hashes::Hashes,
-    hashes_ext::HashesExt, joint_election_public_key::JointElectionPublicKey,
+    ballot::BallotEncrypted,
+    decryption_quorum::DecryptionQuorum,
+    election_manifest::ElectionManifest,
+    election_parameters::ElectionParameters,
+    election_record::{ElectionRecord, ElectionRecordBody, EncryptedTally, PreVotingData},
+    example_election_manifest::example_election_manifest,
+    guardian::GuardianIndex,
+    guardian_public_key::GuardianPublicKey,
+    guardian_secret_key::GuardianSecretKey,
+    guardian_share::{GuardianEncryptedShare, GuardianSecretKeyShare},
+    hashes::Hashes,
+    hashes_ext::HashesExt,
+    joint_election_public_key::JointElectionPublicKey,
+    label::LabelPolicy,
+    share_dispute::ShareDispute,
+    tally_decryption::{
+        GuardianTallyDecryptionResponses, GuardianTallyDecryptionShares, TallyDecryptionCeremony,
+    },
 };
 use util::csprng::Csprng;
 
@@ -31,6 +45,16 @@ impl ElectionManifestSource {
     pub(crate) fn load_election_manifest(
         &self,
         artifacts_dir: &ArtifactsDir,
+    ) -> Result<ElectionManifest> {
+        self.load_election_manifest_with_label_policy(artifacts_dir, LabelPolicy::WarnOnly)
+    }
+
+    /// Loads the election manifest and checks its labels. Violations of the label policy are
+    /// errors with [`LabelPolicy::Strict`] and are reported as warnings otherwise.
+    pub(crate) fn load_election_manifest_with_label_policy(
+        &self,
+        artifacts_dir: &ArtifactsDir,
+        label_policy: LabelPolicy,
     ) -> Result<ElectionManifest> {
         let (opt_path, opt_artifact_file): (Option<PathBuf>, Option<ArtifactFile>) = match self {
             ElectionManifestSource::ArtifactFileElectionManifestPretty => {
@@ -48,11 +72,19 @@ impl ElectionManifestSource {
         let (mut stdioread, actual_path) =
             artifacts_dir.in_file_stdioread(&opt_path, opt_artifact_file)?;
 
-        let election_manifest = ElectionManifest::from_stdioread_validated(&mut stdioread)
+        let (election_manifest, label_issues) =
+            ElectionManifest::from_stdioread_validated_with_label_policy(
+                &mut stdioread,
+                label_policy,
+            )
             .with_context(|| {
                 format!("Loading election manifest from: {}", actual_path.display())
             })?;
 
+        for label_issue in &label_issues {
+            eprintln!("Warning: election manifest label at {label_issue}");
+        }
+
         eprintln!("Election manifest loaded from: {}", actual_path.display());
 
         Ok(election_manifest)
@@ -164,6 +196,85 @@ pub(crate) fn load_guardian_public_key(
     Ok(guardian_public_key)
 }
 
+/// Loads the encrypted shares sent to guardian `recipient` by every guardian, in order of the
+/// dealer's guardian index.
+pub(crate) fn load_all_guardian_encrypted_shares_for(
+    recipient: GuardianIndex,
+    artifacts_dir: &ArtifactsDir,
+    election_parameters: &ElectionParameters,
+) -> Result<Vec<GuardianEncryptedShare>> {
+    let mut encrypted_shares = Vec::new();
+
+    for dealer in election_parameters.varying_parameters.each_guardian_i() {
+        let (mut stdioread, path) = artifacts_dir.in_file_stdioread(
+            &None,
+            Some(ArtifactFile::GuardianEncryptedShare(dealer, recipient)),
+        )?;
+
+        let encrypted_share = GuardianEncryptedShare::from_stdioread(&mut stdioread)
+            .with_context(|| format!("Loading encrypted share from: {}", path.display()))?;
+
+        ensure!(
+            encrypted_share.dealer == dealer && encrypted_share.recipient == recipient,
+            "The encrypted share in {} is from guardian {} for guardian {}, expected from guardian {dealer} for guardian {recipient}",
+            path.display(),
+            encrypted_share.dealer,
+            encrypted_share.recipient
+        );
+
+        eprintln!(
+            "Encrypted share from guardian number {dealer} for guardian number {recipient} loaded from: {}",
+            path.display()
+        );
+
+        encrypted_shares.push(encrypted_share);
+    }
+
+    Ok(encrypted_shares)
+}
+
+/// Loads every [`ShareDispute`] of the key ceremony present in the artifacts dir, in order of
+/// the dealer's and then the complainer's guardian index.
+pub(crate) fn load_all_share_disputes(
+    artifacts_dir: &ArtifactsDir,
+    election_parameters: &ElectionParameters,
+) -> Result<Vec<ShareDispute>> {
+    let mut share_disputes = Vec::new();
+
+    for dealer in election_parameters.varying_parameters.each_guardian_i() {
+        for complainer in election_parameters.varying_parameters.each_guardian_i() {
+            let artifact_file = ArtifactFile::ShareDispute(dealer, complainer);
+            if !artifacts_dir.exists(artifact_file) {
+                continue;
+            }
+
+            let (mut stdioread, path) =
+                artifacts_dir.in_file_stdioread(&None, Some(artifact_file))?;
+
+            let share_dispute = ShareDispute::from_stdioread(&mut stdioread)
+                .with_context(|| format!("Loading share dispute from: {}", path.display()))?;
+
+            let complaint = &share_dispute.complaint;
+            ensure!(
+                complaint.dealer == dealer && complaint.complainer == complainer,
+                "The share dispute in {} is by guardian {} about guardian {}, expected by guardian {complainer} about guardian {dealer}",
+                path.display(),
+                complaint.complainer,
+                complaint.dealer
+            );
+
+            eprintln!(
+                "Share dispute by guardian number {complainer} about guardian number {dealer} loaded from: {}",
+                path.display()
+            );
+
+            share_disputes.push(share_dispute);
+        }
+    }
+
+    Ok(share_disputes)
+}
+
 pub(crate) fn load_joint_election_public_key(
     artifacts_dir: &ArtifactsDir,
     election_parameters: &ElectionParameters,
@@ -232,3 +343,273 @@ pub(crate) fn load_all_guardian_public_keys(
 
     Ok(guardian_public_keys)
 }
+
+/// Assembles the [`PreVotingData`] from the individual artifact files: the election parameters,
+/// manifest, hashes and joint election public key.
+pub(crate) fn load_pre_voting_data(
+    artifacts_dir: &ArtifactsDir,
+    csprng: &mut Csprng,
+) -> Result<PreVotingData> {
+    let election_parameters = load_election_parameters(artifacts_dir, csprng)?;
+
+    let election_manifest = ElectionManifestSource::ArtifactFileElectionManifestCanonical
+        .load_election_manifest(artifacts_dir)?;
+
+    let hashes = load_hashes(artifacts_dir)?;
+    let hashes_ext = load_hashes_ext(artifacts_dir)?;
+    let joint_election_public_key =
+        load_joint_election_public_key(artifacts_dir, &election_parameters)?;
+
+    Ok(PreVotingData::new(
+        election_manifest,
+        election_parameters,
+        hashes,
+        hashes_ext,
+        joint_election_public_key,
+    ))
+}
+
+/// Loads every encrypted ballot in the artifacts directory.
+pub(crate) fn load_all_encrypted_ballots(
+    artifacts_dir: &ArtifactsDir,
+) -> Result<Vec<BallotEncrypted>> {
+    let mut all_ballots = Vec::new();
+    for path in artifacts_dir.encrypted_ballot_paths()? {
+        let (mut stdioread, path) = artifacts_dir.in_file_stdioread(&Some(path), None)?;
+        let ballot = BallotEncrypted::from_stdioread(&mut stdioread)
+            .with_context(|| format!("Loading encrypted ballot from: {}", path.display()))?;
+        all_ballots.push(ballot);
+    }
+    eprintln!("Loaded {} encrypted ballots", all_ballots.len());
+
+    Ok(all_ballots)
+}
+
+pub(crate) fn load_encrypted_tally(
+    opt_encrypted_tally_path: &Option<PathBuf>,
+    artifacts_dir: &ArtifactsDir,
+) -> Result<EncryptedTally> {
+    let (mut stdioread, path) = artifacts_dir
+        .in_file_stdioread(opt_encrypted_tally_path, Some(ArtifactFile::EncryptedTally))?;
+
+    let encrypted_tally = ElectionRecordBody::encrypted_tally_from_stdioread(&mut stdioread)
+        .with_context(|| format!("Loading encrypted tally from: {}", path.display()))?;
+
+    eprintln!("Encrypted tally loaded from: {}", path.display());
+
+    Ok(encrypted_tally)
+}
+
+pub(crate) fn load_guardian_secret_key_share(
+    opt_i: Option<GuardianIndex>,
+    opt_secret_key_share_path: &Option<PathBuf>,
+    artifacts_dir: &ArtifactsDir,
+    election_parameters: &ElectionParameters,
+) -> Result<GuardianSecretKeyShare> {
+    ensure!(
+        opt_i.is_some() || opt_secret_key_share_path.is_some(),
+        "Need the guardian number 'i' or secret key share file path"
+    );
+
+    let (mut stdioread, path) = artifacts_dir.in_file_stdioread(
+        opt_secret_key_share_path,
+        opt_i.map(ArtifactFile::GuardianSecretKeyShare),
+    )?;
+
+    let secret_key_share =
+        GuardianSecretKeyShare::from_stdioread_validated(&mut stdioread, election_parameters)?;
+
+    if let Some(i) = opt_i {
+        ensure!(i == secret_key_share.i,
+            "Guardian number specified by --i {i} does not match the guardian number {} in the secret key share file: {}",
+                secret_key_share.i,
+                path.display()
+            );
+    }
+
+    eprintln!(
+        "Secret key share for guardian number {} loaded from: {}",
+        secret_key_share.i,
+        path.display()
+    );
+
+    Ok(secret_key_share)
+}
+
+/// Sets up the [`TallyDecryptionCeremony`] for an encrypted tally from the decryption shares of
+/// every guardian found in the artifacts directory. If `with_responses` is `true`, the proof
+/// responses of every one of these guardians are loaded as well.
+pub(crate) fn load_tally_decryption_ceremony(
+    artifacts_dir: &ArtifactsDir,
+    election_parameters: &ElectionParameters,
+    encrypted_tally: EncryptedTally,
+    with_responses: bool,
+) -> Result<TallyDecryptionCeremony> {
+    let opt_quorum = load_tally_decryption_quorum(artifacts_dir, election_parameters)?;
+
+    let mut ceremony = match opt_quorum.clone() {
+        Some(quorum) => TallyDecryptionCeremony::with_quorum(encrypted_tally, quorum),
+        None => TallyDecryptionCeremony::new(encrypted_tally),
+    };
+
+    for i in election_parameters.varying_parameters.each_guardian_i() {
+        if !artifacts_dir.exists(ArtifactFile::GuardianTallyDecryptionShares(i)) {
+            continue;
+        }
+        if opt_quorum
+            .as_ref()
+            .is_some_and(|quorum| !quorum.contains(i))
+        {
+            eprintln!(
+                "Skipping decryption shares of guardian number {i}, who is not in the quorum"
+            );
+            continue;
+        }
+
+        let (mut stdioread, path) = artifacts_dir
+            .in_file_stdioread(&None, Some(ArtifactFile::GuardianTallyDecryptionShares(i)))?;
+        let shares = GuardianTallyDecryptionShares::from_stdioread(&mut stdioread)?;
+        ensure!(
+            shares.i == i,
+            "The decryption shares in {} are not from guardian {i}",
+            path.display()
+        );
+        ceremony
+            .add_shares(shares)
+            .with_context(|| format!("Adding decryption shares from: {}", path.display()))?;
+
+        eprintln!(
+            "Decryption shares for guardian number {i} loaded from: {}",
+            path.display()
+        );
+    }
+
+    if with_responses {
+        let participating_guardians: Vec<_> = ceremony.participating_guardians().collect();
+        for i in participating_guardians {
+            let (mut stdioread, path) = artifacts_dir.in_file_stdioread(
+                &None,
+                Some(ArtifactFile::GuardianTallyDecryptionResponses(i)),
+            )?;
+            let responses = GuardianTallyDecryptionResponses::from_stdioread(&mut stdioread)?;
+            ensure!(
+                responses.i == i,
+                "The proof responses in {} are not from guardian {i}",
+                path.display()
+            );
+            ceremony
+                .add_responses(responses)
+                .with_context(|| format!("Adding proof responses from: {}", path.display()))?;
+
+            eprintln!(
+                "Proof responses for guardian number {i} loaded from: {}",
+                path.display()
+            );
+        }
+    }
+
+    Ok(ceremony)
+}
+
+/// Loads the [`DecryptionQuorum`] for the tally decryption from the artifacts dir, if it was
+/// written.
+pub(crate) fn load_tally_decryption_quorum(
+    artifacts_dir: &ArtifactsDir,
+    election_parameters: &ElectionParameters,
+) -> Result<Option<DecryptionQuorum>> {
+    if !artifacts_dir.exists(ArtifactFile::TallyDecryptionQuorum) {
+        return Ok(None);
+    }
+
+    let (mut stdioread, path) =
+        artifacts_dir.in_file_stdioread(&None, Some(ArtifactFile::TallyDecryptionQuorum))?;
+    let quorum = DecryptionQuorum::from_stdioread_validated(&mut stdioread, election_parameters)
+        .with_context(|| format!("Loading decryption quorum from: {}", path.display()))?;
+
+    eprintln!(
+        "Decryption quorum of guardians {:?} loaded from: {}",
+        quorum.participants,
+        path.display()
+    );
+
+    Ok(Some(quorum))
+}
+
+/// Assembles the [`ElectionRecord`] from the individual artifact files: the election parameters,
+/// manifest, hashes, guardian public keys, joint election public key, the share disputes of the
+/// key ceremony, all encrypted ballots and, if present, the encrypted tally and its decryption.
+pub(crate) fn assemble_election_record(
+    artifacts_dir: &ArtifactsDir,
+    csprng: &mut Csprng,
+) -> Result<ElectionRecord> {
+    let prevoting = load_pre_voting_data(artifacts_dir, csprng)?;
+
+    let guardian_public_keys = load_all_guardian_public_keys(artifacts_dir, &prevoting.parameters)?;
+
+    let share_disputes = load_all_share_disputes(artifacts_dir, &prevoting.parameters)?;
+
+    let mut body = ElectionRecordBody::new(load_all_encrypted_ballots(artifacts_dir)?);
+
+    if artifacts_dir.exists(ArtifactFile::EncryptedTally) {
+        body.encrypted_tally = Some(load_encrypted_tally(&None, artifacts_dir)?);
+    }
+
+    body.tally_decryption_quorum =
+        load_tally_decryption_quorum(artifacts_dir, &prevoting.parameters)?;
+
+    if artifacts_dir.exists(ArtifactFile::TallyDecryptionShares) {
+        let (mut stdioread, path) =
+            artifacts_dir.in_file_stdioread(&None, Some(ArtifactFile::TallyDecryptionShares))?;
+        let tally_decryption_shares =
+            ElectionRecordBody::tally_decryption_shares_from_stdioread(&mut stdioread)
+                .with_context(|| {
+                    format!("Loading tally decryption shares from: {}", path.display())
+                })?;
+        eprintln!("Tally decryption shares loaded from: {}", path.display());
+        body.tally_decryption_shares = Some(tally_decryption_shares);
+    }
+
+    if artifacts_dir.exists(ArtifactFile::DecryptedTally) {
+        let (mut stdioread, path) =
+            artifacts_dir.in_file_stdioread(&None, Some(ArtifactFile::DecryptedTally))?;
+        let decrypted_tally = ElectionRecordBody::decrypted_tally_from_stdioread(&mut stdioread)
+            .with_context(|| format!("Loading decrypted tally from: {}", path.display()))?;
+        eprintln!("Decrypted tally loaded from: {}", path.display());
+        body.decrypted_tally = Some(decrypted_tally);
+    }
+
+    let election_record = ElectionRecord {
+        prevoting,
+        guardian_public_keys,
+        share_disputes,
+        body,
+    };
+
+    election_record
+        .body
+        .validate()
+        .context("Assembling election record")?;
+
+    Ok(election_record)
+}
+
+/// Loads the [`ElectionRecord`] from the specified file or, if none is specified, assembles it
+/// from the individual artifact files.
+pub(crate) fn load_election_record(
+    opt_election_record_path: &Option<PathBuf>,
+    artifacts_dir: &ArtifactsDir,
+    csprng: &mut Csprng,
+) -> Result<ElectionRecord> {
+    if opt_election_record_path.is_none() {
+        return assemble_election_record(artifacts_dir, csprng);
+    }
+
+    let (mut stdioread, path) = artifacts_dir.in_file_stdioread(opt_election_record_path, None)?;
+
+    let election_record = ElectionRecord::from_stdioread_validated(&mut stdioread, csprng)
+        .with_context(|| format!("Loading election record from: {}", path.display()))?;
+
+    eprintln!("Election record loaded from: {}", path.display());
+
+    Ok(election_record)
+}
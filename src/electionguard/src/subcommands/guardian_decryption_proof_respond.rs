@@ -0,0 +1,197 @@
+// Copyright (C) Microsoft Corporation. All rights reserved.
+
+#![deny(clippy::unwrap_used)]
+#![deny(clippy::expect_used)]
+#![deny(clippy::panic)]
+#![deny(clippy::manual_assert)]
+
+use std::path::PathBuf;
+
+use anyhow::{bail, ensure, Context, Result};
+
+use eg::{
+    guardian::GuardianIndex,
+    tally_decryption::{GuardianTallyDecryptionResponses, GuardianTallyDecryptionState},
+};
+
+use crate::{
+    artifacts_dir::ArtifactFile,
+    common_utils::{
+        load_encrypted_tally, load_guardian_secret_key_share, load_pre_voting_data,
+        load_tally_decryption_ceremony,
+    },
+    subcommand_helper::SubcommandHelper,
+    subcommands::Subcommand,
+};
+
+/// Compute a guardian's responses for the proofs of correct decryption of the encrypted tally.
+/// This is the second round of the tally decryption. It requires the decryption shares of every
+/// participating guardian to be present in the artifacts dir.
+///
+/// A second response with the same SECRET state from the first round would reveal the guardian's
+/// secret key share. So before the responses are written, the state is marked as used in the
+/// artifacts dir, keyed by the hash of the guardian's commitments, and the state file is deleted.
+/// This refuses to run with a state that is marked as used, whatever the output path. For the
+/// same reason the state cannot be read from stdin.
+#[derive(clap::Args, Debug, Default)]
+pub(crate) struct GuardianDecryptionProofRespond {
+    /// Guardian number, 1 <= i <= n.
+    #[arg(long)]
+    i: Option<GuardianIndex>,
+
+    /// File containing the guardian's secret key share.
+    /// Default is to look in the artifacts dir, if --i is provided.
+    #[arg(long)]
+    secret_key_share_in: Option<PathBuf>,
+
+    /// File containing the guardian's SECRET state from the first round.
+    /// Default is in the artifacts dir, based on the guardian number from the secret key share file.
+    /// Reading from stdin is not supported, as the file is deleted after use.
+    #[arg(long)]
+    state_in: Option<PathBuf>,
+
+    /// File containing the encrypted tally.
+    /// Default is in the artifacts dir.
+    #[arg(long)]
+    encrypted_tally_in: Option<PathBuf>,
+
+    /// File to which to write the guardian's proof responses.
+    /// Default is in the artifacts dir, based on the guardian number from the secret key share file.
+    /// If "-", write to stdout.
+    #[arg(long)]
+    responses_out: Option<PathBuf>,
+}
+
+impl Subcommand for GuardianDecryptionProofRespond {
+    fn uses_csprng(&self) -> bool {
+        true
+    }
+
+    fn do_it(&mut self, subcommand_helper: &mut SubcommandHelper) -> Result<()> {
+        let mut csprng = subcommand_helper
+            .get_csprng(format!("GuardianDecryptionProofRespond({:?})", self.i).as_bytes())?;
+
+        if self.secret_key_share_in.is_none() && self.i.is_none() {
+            bail!("Specify at least one of --i or --secret-key-share-in");
+        }
+
+        let pre_voting_data = load_pre_voting_data(&subcommand_helper.artifacts_dir, &mut csprng)?;
+
+        let secret_key_share = load_guardian_secret_key_share(
+            self.i,
+            &self.secret_key_share_in,
+            &subcommand_helper.artifacts_dir,
+            &pre_voting_data.parameters,
+        )?;
+
+        let i = secret_key_share.i;
+
+        if self
+            .state_in
+            .as_ref()
+            .is_some_and(|path| path.as_os_str() == "-")
+        {
+            bail!("The tally decryption state cannot be read from stdin, as it must be deleted after use");
+        }
+
+        let (state, state_path) = {
+            let (mut stdioread, path) = subcommand_helper.artifacts_dir.in_file_stdioread(
+                &self.state_in,
+                Some(ArtifactFile::GuardianTallyDecryptionState(i)),
+            )?;
+            let state = GuardianTallyDecryptionState::from_stdioread(&mut stdioread)?;
+            ensure!(
+                state.i == i,
+                "The tally decryption state in {} is not from guardian {i}",
+                path.display()
+            );
+            eprintln!(
+                "Tally decryption state for guardian number {i} loaded from: {}",
+                path.display()
+            );
+            (state, path)
+        };
+
+        let used_artifact = ArtifactFile::GuardianTallyDecryptionStateUsed(i, state.h_commitments);
+        if subcommand_helper.artifacts_dir.exists(used_artifact) {
+            bail!(
+                "The tally decryption state of guardian {i} has already been used, see: {}",
+                subcommand_helper
+                    .artifacts_dir
+                    .path(used_artifact)
+                    .display()
+            );
+        }
+        let h_commitments = state.h_commitments;
+
+        let encrypted_tally =
+            load_encrypted_tally(&self.encrypted_tally_in, &subcommand_helper.artifacts_dir)?;
+
+        let ceremony = load_tally_decryption_ceremony(
+            &subcommand_helper.artifacts_dir,
+            &pre_voting_data.parameters,
+            encrypted_tally,
+            false,
+        )?;
+
+        let responses = GuardianTallyDecryptionResponses::generate(
+            &pre_voting_data,
+            &ceremony,
+            state,
+            &secret_key_share,
+        )?;
+
+        // The state must not be used again, so it is marked as used and deleted before the
+        // responses are published. Creating the marker fails if it exists already.
+        {
+            let mut open_options = std::fs::OpenOptions::new();
+            open_options.write(true).create_new(true);
+            let (mut file, path) = subcommand_helper
+                .artifacts_dir
+                .open(used_artifact, &open_options)?;
+            h_commitments.to_stdiowrite(&mut file).with_context(|| {
+                format!(
+                    "Marking the tally decryption state as used: {}",
+                    path.display()
+                )
+            })?;
+            eprintln!(
+                "Marked the tally decryption state as used: {}",
+                path.display()
+            );
+        }
+        std::fs::remove_file(&state_path).with_context(|| {
+            format!(
+                "Deleting the used tally decryption state: {}",
+                state_path.display()
+            )
+        })?;
+        eprintln!(
+            "Deleted the used tally decryption state: {}",
+            state_path.display()
+        );
+
+        let (mut stdiowrite, path) = subcommand_helper.artifacts_dir.out_file_stdiowrite(
+            &self.responses_out,
+            Some(ArtifactFile::GuardianTallyDecryptionResponses(i)),
+        )?;
+
+        responses
+            .to_stdiowrite(stdiowrite.as_mut())
+            .with_context(|| {
+                format!(
+                    "Writing tally decryption proof responses for guardian {i} to: {}",
+                    path.display()
+                )
+            })?;
+
+        drop(stdiowrite);
+
+        eprintln!(
+            "Wrote tally decryption proof responses for guardian {i} to: {}",
+            path.display()
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,140 @@
+// Copyright (C) Microsoft Corporation. All rights reserved.
+
+#![deny(clippy::unwrap_used)]
+#![deny(clippy::expect_used)]
+#![deny(clippy::panic)]
+#![deny(clippy::manual_assert)]
+
+use std::path::PathBuf;
+
+use anyhow::{bail, Context, Result};
+
+use eg::{guardian::GuardianIndex, tally_decryption::GuardianTallyDecryptionShares};
+
+use crate::{
+    artifacts_dir::ArtifactFile,
+    common_utils::{
+        load_encrypted_tally, load_guardian_secret_key_share, load_pre_voting_data,
+        load_tally_decryption_quorum,
+    },
+    subcommand_helper::SubcommandHelper,
+    subcommands::Subcommand,
+};
+
+/// Compute a guardian's decryption shares of the encrypted tally, together with the commitments
+/// for the proofs of correct decryption. This is the first round of the tally decryption.
+#[derive(clap::Args, Debug, Default)]
+pub(crate) struct GuardianDecryptionShareGenerate {
+    /// Guardian number, 1 <= i <= n.
+    #[arg(long)]
+    i: Option<GuardianIndex>,
+
+    /// File containing the guardian's secret key share.
+    /// Default is to look in the artifacts dir, if --i is provided.
+    #[arg(long)]
+    secret_key_share_in: Option<PathBuf>,
+
+    /// File containing the encrypted tally.
+    /// Default is in the artifacts dir.
+    #[arg(long)]
+    encrypted_tally_in: Option<PathBuf>,
+
+    /// File to which to write the guardian's decryption shares.
+    /// Default is in the artifacts dir, based on the guardian number from the secret key share file.
+    /// If "-", write to stdout.
+    #[arg(long)]
+    shares_out: Option<PathBuf>,
+
+    /// File to which to write the guardian's SECRET state needed to respond in the second round.
+    /// Default is in the artifacts dir, based on the guardian number from the secret key share file.
+    #[arg(long)]
+    state_out: Option<PathBuf>,
+}
+
+impl Subcommand for GuardianDecryptionShareGenerate {
+    fn uses_csprng(&self) -> bool {
+        true
+    }
+
+    fn do_it(&mut self, subcommand_helper: &mut SubcommandHelper) -> Result<()> {
+        let mut csprng = subcommand_helper
+            .get_csprng(format!("GuardianDecryptionShareGenerate({:?})", self.i).as_bytes())?;
+
+        if self.secret_key_share_in.is_none() && self.i.is_none() {
+            bail!("Specify at least one of --i or --secret-key-share-in");
+        }
+
+        let pre_voting_data = load_pre_voting_data(&subcommand_helper.artifacts_dir, &mut csprng)?;
+
+        let secret_key_share = load_guardian_secret_key_share(
+            self.i,
+            &self.secret_key_share_in,
+            &subcommand_helper.artifacts_dir,
+            &pre_voting_data.parameters,
+        )?;
+
+        let i = secret_key_share.i;
+
+        if let Some(quorum) = load_tally_decryption_quorum(
+            &subcommand_helper.artifacts_dir,
+            &pre_voting_data.parameters,
+        )? {
+            if !quorum.contains(i) {
+                bail!(
+                    "Guardian {i} is not in the decryption quorum {:?}",
+                    quorum.participants
+                );
+            }
+        }
+
+        let encrypted_tally =
+            load_encrypted_tally(&self.encrypted_tally_in, &subcommand_helper.artifacts_dir)?;
+
+        let (shares, state) = GuardianTallyDecryptionShares::generate(
+            &mut csprng,
+            &pre_voting_data,
+            &encrypted_tally,
+            &secret_key_share,
+        );
+
+        let (mut stdiowrite, path) = subcommand_helper.artifacts_dir.out_file_stdiowrite(
+            &self.state_out,
+            Some(ArtifactFile::GuardianTallyDecryptionState(i)),
+        )?;
+
+        state.to_stdiowrite(stdiowrite.as_mut()).with_context(|| {
+            format!(
+                "Writing tally decryption state for guardian {i} to: {}",
+                path.display()
+            )
+        })?;
+
+        drop(stdiowrite);
+
+        eprintln!(
+            "Wrote tally decryption state for guardian {i} to: {}",
+            path.display()
+        );
+
+        let (mut stdiowrite, path) = subcommand_helper.artifacts_dir.out_file_stdiowrite(
+            &self.shares_out,
+            Some(ArtifactFile::GuardianTallyDecryptionShares(i)),
+        )?;
+
+        shares.to_stdiowrite(stdiowrite.as_mut()).with_context(|| {
+            format!(
+                "Writing tally decryption shares for guardian {i} to: {}",
+                path.display()
+            )
+        })?;
+
+        drop(stdiowrite);
+
+        eprintln!(
+            "Wrote tally decryption shares for guardian {i} to: {}",
+            path.display()
+        );
+
+        Ok(())
+    }
+}
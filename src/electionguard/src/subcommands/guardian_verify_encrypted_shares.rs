@@ -0,0 +1,99 @@
+// Copyright (C) Microsoft Corporation. All rights reserved.
+
+#![deny(clippy::unwrap_used)]
+#![deny(clippy::expect_used)]
+#![deny(clippy::panic)]
+#![deny(clippy::manual_assert)]
+
+use std::{iter::zip, path::PathBuf};
+
+use anyhow::{bail, Result};
+
+use eg::guardian::GuardianIndex;
+
+use crate::{
+    common_utils::{
+        load_all_guardian_encrypted_shares_for, load_all_guardian_public_keys,
+        load_election_parameters, load_guardian_secret_key,
+    },
+    subcommand_helper::SubcommandHelper,
+    subcommands::Subcommand,
+};
+
+/// Decrypt and validate the encrypted shares the guardian received from every guardian.
+#[derive(clap::Args, Debug, Default)]
+pub(crate) struct GuardianVerifyEncryptedShares {
+    /// Guardian number, 1 <= i <= n.
+    #[arg(long)]
+    i: Option<GuardianIndex>,
+
+    /// File containing the guardian's secret key.
+    /// Default is to look in the artifacts dir, if --i is provided.
+    #[arg(long)]
+    secret_key_in: Option<PathBuf>,
+}
+
+impl Subcommand for GuardianVerifyEncryptedShares {
+    fn uses_csprng(&self) -> bool {
+        true
+    }
+
+    fn do_it(&mut self, subcommand_helper: &mut SubcommandHelper) -> Result<()> {
+        let mut csprng = subcommand_helper
+            .get_csprng(format!("GuardianVerifyEncryptedShares({:?})", self.i).as_bytes())?;
+
+        if self.secret_key_in.is_none() && self.i.is_none() {
+            bail!("Specify at least one of --i or --secret-key-in");
+        }
+
+        let election_parameters =
+            load_election_parameters(&subcommand_helper.artifacts_dir, &mut csprng)?;
+
+        let guardian_secret_key = load_guardian_secret_key(
+            self.i,
+            &self.secret_key_in,
+            &subcommand_helper.artifacts_dir,
+            &election_parameters,
+        )?;
+
+        let i = guardian_secret_key.i;
+
+        let guardian_public_keys =
+            load_all_guardian_public_keys(&subcommand_helper.artifacts_dir, &election_parameters)?;
+
+        let encrypted_shares = load_all_guardian_encrypted_shares_for(
+            i,
+            &subcommand_helper.artifacts_dir,
+            &election_parameters,
+        )?;
+
+        let mut invalid_dealers = Vec::new();
+        for (dealer_public_key, encrypted_share) in zip(&guardian_public_keys, &encrypted_shares) {
+            let dealer = dealer_public_key.i;
+            match encrypted_share.decrypt_and_validate(
+                &election_parameters,
+                dealer_public_key,
+                &guardian_secret_key,
+            ) {
+                Ok(_) => eprintln!("Share from guardian {dealer} for guardian {i}: valid"),
+                Err(e) => {
+                    eprintln!("Share from guardian {dealer} for guardian {i}: INVALID: {e}");
+                    invalid_dealers.push(dealer);
+                }
+            }
+        }
+
+        if !invalid_dealers.is_empty() {
+            let dealers = invalid_dealers
+                .iter()
+                .map(|dealer| dealer.to_string())
+                .collect::<Vec<_>>()
+                .join(", ");
+            bail!("Guardian {i} received invalid shares from guardians: {dealers}");
+        }
+
+        eprintln!("All shares for guardian {i} are valid");
+
+        Ok(())
+    }
+}
@@ -0,0 +1,98 @@
+// Copyright (C) Microsoft Corporation. All rights reserved.
+
+#![deny(clippy::unwrap_used)]
+#![deny(clippy::expect_used)]
+#![deny(clippy::panic)]
+#![deny(clippy::manual_assert)]
+
+use std::path::PathBuf;
+
+use anyhow::{bail, Context, Result};
+
+use eg::{guardian::GuardianIndex, guardian_share::GuardianEncryptedShare};
+
+use crate::{
+    artifacts_dir::ArtifactFile,
+    common_utils::{
+        load_all_guardian_public_keys, load_election_parameters, load_guardian_secret_key,
+    },
+    subcommand_helper::SubcommandHelper,
+    subcommands::Subcommand,
+};
+
+/// Encrypt a share of the guardian's secret key for every guardian, including the guardian itself.
+#[derive(clap::Args, Debug, Default)]
+pub(crate) struct GuardianWriteEncryptedShares {
+    /// Guardian number, 1 <= i <= n.
+    #[arg(long)]
+    i: Option<GuardianIndex>,
+
+    /// File containing the guardian's secret key.
+    /// Default is to look in the artifacts dir, if --i is provided.
+    #[arg(long)]
+    secret_key_in: Option<PathBuf>,
+}
+
+impl Subcommand for GuardianWriteEncryptedShares {
+    fn uses_csprng(&self) -> bool {
+        true
+    }
+
+    fn do_it(&mut self, subcommand_helper: &mut SubcommandHelper) -> Result<()> {
+        let mut csprng = subcommand_helper
+            .get_csprng(format!("GuardianWriteEncryptedShares({:?})", self.i).as_bytes())?;
+
+        if self.secret_key_in.is_none() && self.i.is_none() {
+            bail!("Specify at least one of --i or --secret-key-in");
+        }
+
+        let election_parameters =
+            load_election_parameters(&subcommand_helper.artifacts_dir, &mut csprng)?;
+
+        let guardian_secret_key = load_guardian_secret_key(
+            self.i,
+            &self.secret_key_in,
+            &subcommand_helper.artifacts_dir,
+            &election_parameters,
+        )?;
+
+        let i = guardian_secret_key.i;
+
+        let guardian_public_keys =
+            load_all_guardian_public_keys(&subcommand_helper.artifacts_dir, &election_parameters)?;
+
+        for recipient_public_key in &guardian_public_keys {
+            let l = recipient_public_key.i;
+
+            let encrypted_share = GuardianEncryptedShare::encrypt(
+                &mut csprng,
+                &election_parameters,
+                &guardian_secret_key,
+                recipient_public_key,
+            )
+            .ciphertext;
+
+            let (mut stdiowrite, path) = subcommand_helper
+                .artifacts_dir
+                .out_file_stdiowrite(&None, Some(ArtifactFile::GuardianEncryptedShare(i, l)))?;
+
+            encrypted_share
+                .to_stdiowrite(stdiowrite.as_mut())
+                .with_context(|| {
+                    format!(
+                        "Writing encrypted share from guardian {i} for guardian {l} to: {}",
+                        path.display()
+                    )
+                })?;
+
+            drop(stdiowrite);
+
+            eprintln!(
+                "Wrote encrypted share from guardian {i} for guardian {l} to: {}",
+                path.display()
+            );
+        }
+
+        Ok(())
+    }
+}
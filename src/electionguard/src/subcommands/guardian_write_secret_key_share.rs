@@ -0,0 +1,109 @@
+// Copyright (C) Microsoft Corporation. All rights reserved.
+
+#![deny(clippy::unwrap_used)]
+#![deny(clippy::expect_used)]
+#![deny(clippy::panic)]
+#![deny(clippy::manual_assert)]
+
+use std::path::PathBuf;
+
+use anyhow::{bail, Context, Result};
+
+use eg::{guardian::GuardianIndex, guardian_share::GuardianSecretKeyShare};
+
+use crate::{
+    artifacts_dir::ArtifactFile,
+    common_utils::{
+        load_all_guardian_encrypted_shares_for, load_all_guardian_public_keys,
+        load_election_parameters, load_guardian_secret_key,
+    },
+    subcommand_helper::SubcommandHelper,
+    subcommands::Subcommand,
+};
+
+/// Compute the guardian's secret key share from the encrypted shares it received from every
+/// guardian.
+#[derive(clap::Args, Debug, Default)]
+pub(crate) struct GuardianWriteSecretKeyShare {
+    /// Guardian number, 1 <= i <= n.
+    #[arg(long)]
+    i: Option<GuardianIndex>,
+
+    /// File containing the guardian's secret key.
+    /// Default is to look in the artifacts dir, if --i is provided.
+    #[arg(long)]
+    secret_key_in: Option<PathBuf>,
+
+    /// File to which to write the guardian's secret key share.
+    /// Default is in the artifacts dir, based on the guardian number from the secret key file.
+    /// If "-", write to stdout.
+    #[arg(long)]
+    secret_key_share_out: Option<PathBuf>,
+}
+
+impl Subcommand for GuardianWriteSecretKeyShare {
+    fn uses_csprng(&self) -> bool {
+        true
+    }
+
+    fn do_it(&mut self, subcommand_helper: &mut SubcommandHelper) -> Result<()> {
+        let mut csprng = subcommand_helper
+            .get_csprng(format!("GuardianWriteSecretKeyShare({:?})", self.i).as_bytes())?;
+
+        if self.secret_key_in.is_none() && self.i.is_none() {
+            bail!("Specify at least one of --i or --secret-key-in");
+        }
+
+        let election_parameters =
+            load_election_parameters(&subcommand_helper.artifacts_dir, &mut csprng)?;
+
+        let guardian_secret_key = load_guardian_secret_key(
+            self.i,
+            &self.secret_key_in,
+            &subcommand_helper.artifacts_dir,
+            &election_parameters,
+        )?;
+
+        let i = guardian_secret_key.i;
+
+        let guardian_public_keys =
+            load_all_guardian_public_keys(&subcommand_helper.artifacts_dir, &election_parameters)?;
+
+        let encrypted_shares = load_all_guardian_encrypted_shares_for(
+            i,
+            &subcommand_helper.artifacts_dir,
+            &election_parameters,
+        )?;
+
+        let secret_key_share = GuardianSecretKeyShare::compute(
+            &election_parameters,
+            &guardian_public_keys,
+            &encrypted_shares,
+            &guardian_secret_key,
+        )
+        .with_context(|| format!("Computing the secret key share of guardian {i}"))?;
+
+        let (mut stdiowrite, path) = subcommand_helper.artifacts_dir.out_file_stdiowrite(
+            &self.secret_key_share_out,
+            Some(ArtifactFile::GuardianSecretKeyShare(i)),
+        )?;
+
+        secret_key_share
+            .to_stdiowrite(stdiowrite.as_mut())
+            .with_context(|| {
+                format!(
+                    "Writing secret key share for guardian {i} to: {}",
+                    path.display()
+                )
+            })?;
+
+        drop(stdiowrite);
+
+        eprintln!(
+            "Wrote secret key share for guardian {i} to: {}",
+            path.display()
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,119 @@
+// Copyright (C) Microsoft Corporation. All rights reserved.
+
+#![deny(clippy::unwrap_used)]
+#![deny(clippy::expect_used)]
+#![deny(clippy::panic)]
+#![deny(clippy::manual_assert)]
+
+use std::path::PathBuf;
+
+use anyhow::{bail, Context, Result};
+
+use eg::nist_cdf::import_election_report;
+
+use crate::{
+    artifacts_dir::ArtifactFile,
+    subcommand_helper::SubcommandHelper,
+    subcommands::{
+        write_manifest::{ElectionManifestFormat, ElectionManifestLabelPolicy},
+        Subcommand,
+    },
+};
+
+#[derive(clap::Args, Debug, Default)]
+pub(crate) struct ImportManifest {
+    /// Input NIST SP 1500-100 election definition (an `ElectionReport` in JSON format).
+    /// If "-", read from stdin.
+    #[arg(long)]
+    pub in_file: PathBuf,
+
+    /// Language of the text to import, e.g., for contest titles and candidate names. Text not
+    /// available in this language is imported in the first language given.
+    #[arg(long, default_value = "en")]
+    pub language: String,
+
+    /// Import the election definition even if it contains constructs that are not supported,
+    /// e.g., ranked-choice contests. These constructs are reported as warnings and are not
+    /// imported.
+    #[arg(long)]
+    pub allow_unsupported: bool,
+
+    /// How to treat labels of the imported election manifest that violate the label policy,
+    /// e.g., labels that are confusable.
+    #[arg(value_enum, long, default_value = "strict")]
+    pub label_policy: ElectionManifestLabelPolicy,
+
+    /// Output format. Default is canonical.
+    /// Unless `--out-file` is specified, the output is written to the appropriate file in the
+    /// artifacts dir.
+    #[arg(value_enum, long, default_value = "canonical")]
+    pub out_format: ElectionManifestFormat,
+
+    /// File to which to write the election manifest.
+    /// Default is the appropriate election manifest file in the artifacts dir.
+    /// If "-", write to stdout.
+    #[arg(long)]
+    out_file: Option<PathBuf>,
+}
+
+impl Subcommand for ImportManifest {
+    fn uses_csprng(&self) -> bool {
+        false
+    }
+
+    fn do_it(&mut self, subcommand_helper: &mut SubcommandHelper) -> Result<()> {
+        let (mut stdioread, in_path) = subcommand_helper
+            .artifacts_dir
+            .in_file_stdioread(&Some(self.in_file.clone()), None)?;
+
+        let (election_manifest, import_issues) =
+            import_election_report(&mut stdioread, &self.language).with_context(|| {
+                format!("Importing election definition from: {}", in_path.display())
+            })?;
+
+        for import_issue in &import_issues {
+            eprintln!("Warning: {import_issue}");
+        }
+        if !import_issues.is_empty() && !self.allow_unsupported {
+            bail!(
+                "The election definition contains {} unsupported constructs, specify `--allow-unsupported` to import it without them",
+                import_issues.len()
+            );
+        }
+
+        let label_issues = election_manifest.validate_labels(self.label_policy.into())?;
+        for label_issue in &label_issues {
+            eprintln!("Warning: election manifest label at {label_issue}");
+        }
+
+        use ElectionManifestFormat::*;
+        let (artifact_file, description) = match self.out_format {
+            Canonical => (ArtifactFile::ElectionManifestCanonical, "canonical bytes"),
+            Pretty => (ArtifactFile::ElectionManifestPretty, "pretty JSON"),
+        };
+
+        let (mut stdiowrite, path) = subcommand_helper
+            .artifacts_dir
+            .out_file_stdiowrite(&self.out_file, Some(artifact_file))?;
+
+        let write_result = match self.out_format {
+            Canonical => election_manifest.to_stdiowrite_canonical(&mut stdiowrite),
+            Pretty => election_manifest.to_stdiowrite_pretty(&mut stdiowrite),
+        };
+
+        write_result.with_context(|| {
+            format!(
+                "Writing election manifest {description} to: {}",
+                path.display()
+            )
+        })?;
+
+        eprintln!(
+            "Wrote election manifest {description} imported from {} to: {}",
+            in_path.display(),
+            path.display()
+        );
+
+        Ok(())
+    }
+}
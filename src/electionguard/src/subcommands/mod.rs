@@ -5,15 +5,25 @@
 #![deny(clippy::panic)]
 #![deny(clippy::manual_assert)]
 
+mod guardian_decryption_proof_respond;
+mod guardian_decryption_share_generate;
 mod guardian_secret_key_generate;
-//? TODO mod guardian_secret_key_write_encrypted_share;
 mod guardian_secret_key_write_public_key;
+mod guardian_verify_encrypted_shares;
+mod guardian_write_encrypted_shares;
+mod guardian_write_secret_key_share;
+mod import_manifest;
 mod none;
 mod preencrypted_ballot_generate;
 mod preencrypted_ballot_record;
+mod tally_decrypt;
+mod verify_election_record;
 mod verify_standard_parameters;
-//? TODO mod voter_write_random_selections;
 mod voter_write_confirmation_code;
+mod voter_write_random_selections;
+mod write_decryption_quorum;
+mod write_election_record;
+mod write_encrypted_tally;
 mod write_hashes;
 mod write_hashes_ext;
 mod write_joint_election_public_key;
@@ -52,15 +62,16 @@ pub(crate) enum Subcommands {
     /// Write the election manifest to a file.
     WriteManifest(crate::subcommands::write_manifest::WriteManifest),
 
+    /// Import the election manifest from a NIST SP 1500-100 election definition and write it
+    /// to a file.
+    ImportManifest(crate::subcommands::import_manifest::ImportManifest),
+
     /// Write the election parameters to a file.
     WriteParameters(crate::subcommands::write_parameters::WriteParameters),
 
     /// Write the hashes to a file.
     WriteHashes(crate::subcommands::write_hashes::WriteHashes),
 
-    //TODO /// Generate an encrypted share of the guardian secret key.
-    //TODO GuardianSecretKeyWriteEncryptedShare(crate::subcommands::guardian_secret_key_write_encrypted_share::GuardianSecretKeyWriteEncryptedShare),
-    //
     /// Generate pre-encrypted ballots.
     PreEncryptedBallotGenerate(
         crate::subcommands::preencrypted_ballot_generate::PreEncryptedBallotGenerate,
@@ -81,14 +92,31 @@ pub(crate) enum Subcommands {
         crate::subcommands::guardian_secret_key_write_public_key::GuardianSecretKeyWritePublicKey,
     ),
 
+    /// Write an encrypted share of a guardian's secret key for every guardian.
+    GuardianWriteEncryptedShares(
+        crate::subcommands::guardian_write_encrypted_shares::GuardianWriteEncryptedShares,
+    ),
+
+    /// Decrypt and validate the encrypted shares a guardian received.
+    GuardianVerifyEncryptedShares(
+        crate::subcommands::guardian_verify_encrypted_shares::GuardianVerifyEncryptedShares,
+    ),
+
+    /// Compute a guardian's secret key share from the encrypted shares it received.
+    GuardianWriteSecretKeyShare(
+        crate::subcommands::guardian_write_secret_key_share::GuardianWriteSecretKeyShare,
+    ),
+
     /// Write the confirmation QR code for a voter.
     VoterWriteConfirmationCode(
         crate::subcommands::voter_write_confirmation_code::VoterWriteConfirmationCode,
     ),
 
-    //TODO /// Write random ballot selections to a file for testing.
-    //TODO VoterWriteRandomSelections(crate::subcommands::voter_write_random_selections::VoterWriteRandomSelection),
-    //
+    /// Write random voter selections to files for testing.
+    VoterWriteRandomSelections(
+        crate::subcommands::voter_write_random_selections::VoterWriteRandomSelections,
+    ),
+
     /// Compute the joint election public key from the guardian public keys and write it to a file.
     WriteJointElectionPublicKey(
         crate::subcommands::write_joint_election_public_key::WriteJointElectionPublicKey,
@@ -96,6 +124,31 @@ pub(crate) enum Subcommands {
 
     /// Write the extended hash to a file.
     WriteHashesExt(crate::subcommands::write_hashes_ext::WriteHashesExt),
+
+    /// Compute the encrypted tally of all cast ballots and write it to a file.
+    WriteEncryptedTally(crate::subcommands::write_encrypted_tally::WriteEncryptedTally),
+
+    /// Select the quorum of guardians that decrypt the tally from the guardians that are present.
+    WriteDecryptionQuorum(crate::subcommands::write_decryption_quorum::WriteDecryptionQuorum),
+
+    /// Generate a guardian's decryption shares of the encrypted tally.
+    GuardianDecryptionShareGenerate(
+        crate::subcommands::guardian_decryption_share_generate::GuardianDecryptionShareGenerate,
+    ),
+
+    /// Generate a guardian's responses for the proofs of correct decryption of the encrypted tally.
+    GuardianDecryptionProofRespond(
+        crate::subcommands::guardian_decryption_proof_respond::GuardianDecryptionProofRespond,
+    ),
+
+    /// Combine the guardians' decryption shares and proof responses into the decrypted tally.
+    TallyDecrypt(crate::subcommands::tally_decrypt::TallyDecrypt),
+
+    /// Assemble the election record from the artifacts directory and write it to a file.
+    WriteElectionRecord(crate::subcommands::write_election_record::WriteElectionRecord),
+
+    /// Verify the election record in the artifacts directory.
+    VerifyElectionRecord(crate::subcommands::verify_election_record::VerifyElectionRecord),
 }
 
 impl Default for Subcommands {
@@ -112,17 +165,27 @@ impl<'a> From<&'a mut Subcommands> for &'a mut dyn Subcommand {
             WriteRandomSeed(a) => a,
             VerifyStandardParameters(a) => a,
             WriteManifest(a) => a,
+            ImportManifest(a) => a,
             WriteParameters(a) => a,
             WriteHashes(a) => a,
             GuardianSecretKeyGenerate(a) => a,
             GuardianSecretKeyWritePublicKey(a) => a,
-            //TODO GuardianSecretKeyWriteEncryptedShare(a) => a,
+            GuardianWriteEncryptedShares(a) => a,
+            GuardianVerifyEncryptedShares(a) => a,
+            GuardianWriteSecretKeyShare(a) => a,
             PreEncryptedBallotGenerate(a) => a,
             PreEncryptedBallotRecord(a) => a,
-            //TODO VoterWriteRandomSelections(a) => a,
+            VoterWriteRandomSelections(a) => a,
             VoterWriteConfirmationCode(a) => a,
             WriteJointElectionPublicKey(a) => a,
             WriteHashesExt(a) => a,
+            WriteEncryptedTally(a) => a,
+            WriteDecryptionQuorum(a) => a,
+            GuardianDecryptionShareGenerate(a) => a,
+            GuardianDecryptionProofRespond(a) => a,
+            TallyDecrypt(a) => a,
+            WriteElectionRecord(a) => a,
+            VerifyElectionRecord(a) => a,
         }
     }
 }
@@ -7,12 +7,11 @@
 
 use anyhow::{bail, Context, Result};
 
-use eg::{
-    ballot_style::BallotStyleIndex, device::Device, election_record::PreVotingData, hash::HValue,
-};
+use eg::{ballot_style::BallotStyleIndex, device::Device, election_record::PreVotingData};
 use preencrypted::{
     ballot::{BallotPreEncrypted, VoterSelection},
     ballot_recording_tool::BallotRecordingTool,
+    nonce::PrimaryNonce,
 };
 use util::file::create_path;
 
@@ -122,7 +121,7 @@ impl Subcommand for PreEncryptedBallotRecord {
                         codes[b_idx - 1],
                     )),
                 )?;
-                HValue::from_stdioread(&mut stdioread)?
+                PrimaryNonce::from_stdioread(&mut stdioread)?
             };
 
             let (regenerated_ballot, matched) =
@@ -143,8 +142,9 @@ impl Subcommand for PreEncryptedBallotRecord {
 
                     VoterSelection::from_stdioread(&mut stdioread)?
                 };
-                let encrypted_ballot =
+                let mut encrypted_ballot =
                     regenerated_ballot.finalize(&device, &mut csprng, &voter_ballot);
+                encrypted_ballot.cast()?;
 
                 let (mut bx_write, path) = subcommand_helper.artifacts_dir.out_file_stdiowrite(
                     &None,
@@ -159,10 +159,7 @@ impl Subcommand for PreEncryptedBallotRecord {
                     .with_context(|| format!("Writing encrypted ballot to: {}", path.display()))?;
                 drop(bx_write);
             } else {
-                eprintln!(
-                    "Regenerated ballot with nonce {} does not match ballot {}",
-                    nonce, b_idx
-                );
+                eprintln!("Regenerated ballot does not match ballot {}", b_idx);
             }
         }
 
@@ -0,0 +1,102 @@
+// Copyright (C) Microsoft Corporation. All rights reserved.
+
+#![deny(clippy::unwrap_used)]
+#![deny(clippy::expect_used)]
+#![deny(clippy::panic)]
+#![deny(clippy::manual_assert)]
+
+use std::path::PathBuf;
+
+use anyhow::{Context, Result};
+
+use eg::election_record::ElectionRecordBody;
+
+use crate::{
+    artifacts_dir::ArtifactFile,
+    common_utils::{
+        load_all_guardian_public_keys, load_encrypted_tally, load_pre_voting_data,
+        load_tally_decryption_ceremony,
+    },
+    subcommand_helper::SubcommandHelper,
+    subcommands::Subcommand,
+};
+
+/// Combine the decryption shares and proof responses of the participating guardians into the
+/// verifiable decrypted tally.
+#[derive(clap::Args, Debug, Default)]
+pub(crate) struct TallyDecrypt {
+    /// File containing the encrypted tally.
+    /// Default is in the artifacts dir.
+    #[arg(long)]
+    encrypted_tally_in: Option<PathBuf>,
+
+    /// File to which to write the decryption shares of all participating guardians.
+    /// Default is in the artifacts dir.
+    /// If "-", write to stdout.
+    #[arg(long)]
+    shares_out: Option<PathBuf>,
+
+    /// File to which to write the decrypted tally.
+    /// Default is in the artifacts dir.
+    /// If "-", write to stdout.
+    #[arg(long)]
+    decrypted_tally_out: Option<PathBuf>,
+}
+
+impl Subcommand for TallyDecrypt {
+    fn uses_csprng(&self) -> bool {
+        true
+    }
+
+    fn do_it(&mut self, subcommand_helper: &mut SubcommandHelper) -> Result<()> {
+        let mut csprng = subcommand_helper.get_csprng(b"TallyDecrypt")?;
+
+        let pre_voting_data = load_pre_voting_data(&subcommand_helper.artifacts_dir, &mut csprng)?;
+
+        let guardian_public_keys = load_all_guardian_public_keys(
+            &subcommand_helper.artifacts_dir,
+            &pre_voting_data.parameters,
+        )?;
+
+        let encrypted_tally =
+            load_encrypted_tally(&self.encrypted_tally_in, &subcommand_helper.artifacts_dir)?;
+
+        let ceremony = load_tally_decryption_ceremony(
+            &subcommand_helper.artifacts_dir,
+            &pre_voting_data.parameters,
+            encrypted_tally,
+            true,
+        )?;
+
+        let (decrypted_tally, tally_decryption_shares) =
+            ceremony.finalize(&pre_voting_data, &guardian_public_keys)?;
+
+        let (mut stdiowrite, path) = subcommand_helper
+            .artifacts_dir
+            .out_file_stdiowrite(&self.shares_out, Some(ArtifactFile::TallyDecryptionShares))?;
+
+        ElectionRecordBody::tally_decryption_shares_to_stdiowrite(
+            &tally_decryption_shares,
+            stdiowrite.as_mut(),
+        )
+        .with_context(|| format!("Writing tally decryption shares to: {}", path.display()))?;
+
+        drop(stdiowrite);
+
+        eprintln!("Wrote tally decryption shares to: {}", path.display());
+
+        let (mut stdiowrite, path) = subcommand_helper.artifacts_dir.out_file_stdiowrite(
+            &self.decrypted_tally_out,
+            Some(ArtifactFile::DecryptedTally),
+        )?;
+
+        ElectionRecordBody::decrypted_tally_to_stdiowrite(&decrypted_tally, stdiowrite.as_mut())
+            .with_context(|| format!("Writing decrypted tally to: {}", path.display()))?;
+
+        drop(stdiowrite);
+
+        eprintln!("Wrote decrypted tally to: {}", path.display());
+
+        Ok(())
+    }
+}
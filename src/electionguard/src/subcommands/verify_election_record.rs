@@ -0,0 +1,79 @@
+// Copyright (C) Microsoft Corporation. All rights reserved.
+
+#![deny(clippy::unwrap_used)]
+#![deny(clippy::expect_used)]
+#![deny(clippy::panic)]
+#![deny(clippy::manual_assert)]
+
+use std::path::PathBuf;
+
+use anyhow::{bail, Context, Result};
+
+use eg::verifier::verify_election_record;
+
+use crate::{
+    common_utils::load_election_record, subcommand_helper::SubcommandHelper,
+    subcommands::Subcommand,
+};
+
+/// Verify the election record in the artifacts directory.
+#[derive(clap::Args, Debug, Default)]
+pub(crate) struct VerifyElectionRecord {
+    /// File from which to read the election record.
+    /// If "-", read from stdin.
+    /// If not specified, the election record is assembled from the individual files in the
+    /// artifacts dir.
+    #[arg(long)]
+    election_record_file: Option<PathBuf>,
+
+    /// File to which to write the verification report as JSON.
+    /// If "-", write to stdout.
+    /// If not specified, the report is only printed as text.
+    #[arg(long)]
+    report_file: Option<PathBuf>,
+}
+
+impl Subcommand for VerifyElectionRecord {
+    fn uses_csprng(&self) -> bool {
+        true
+    }
+
+    fn do_it(&mut self, subcommand_helper: &mut SubcommandHelper) -> Result<()> {
+        let mut csprng = subcommand_helper.get_csprng(b"VerifyElectionRecord")?;
+        let election_record = load_election_record(
+            &self.election_record_file,
+            &subcommand_helper.artifacts_dir,
+            &mut csprng,
+        )?;
+
+        let report = verify_election_record(&election_record, &mut csprng);
+
+        eprintln!("{report}");
+
+        if self.report_file.is_some() {
+            let (mut stdiowrite, path) = subcommand_helper
+                .artifacts_dir
+                .out_file_stdiowrite(&self.report_file, None)?;
+
+            report
+                .to_stdiowrite(stdiowrite.as_mut())
+                .with_context(|| format!("Writing verification report to: {}", path.display()))?;
+
+            drop(stdiowrite);
+
+            eprintln!("Wrote verification report to: {}", path.display());
+        }
+
+        if !report.is_success() {
+            bail!(
+                "Election record verification failed: {} of {} checks failed",
+                report.failures().count(),
+                report.checks.len()
+            );
+        }
+
+        eprintln!("Election record verified successfully.");
+
+        Ok(())
+    }
+}
@@ -0,0 +1,118 @@
+// Copyright (C) Microsoft Corporation. All rights reserved.
+
+#![deny(clippy::unwrap_used)]
+#![deny(clippy::expect_used)]
+#![deny(clippy::panic)]
+#![deny(clippy::manual_assert)]
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use anyhow::{bail, Context, Result};
+
+use eg::ballot_style::BallotStyleIndex;
+use preencrypted::ballot::VoterSelection;
+use util::file::create_path;
+
+use crate::{
+    artifacts_dir::ArtifactFile, common_utils::ElectionManifestSource,
+    subcommand_helper::SubcommandHelper, subcommands::Subcommand,
+};
+
+/// Write random voter selections to files in the artifacts directory, e.g., to generate
+/// synthetic elections for testing.
+#[derive(clap::Args, Debug, Default)]
+pub(crate) struct VoterWriteRandomSelections {
+    /// Number of voter selections to generate.
+    #[arg(short, long, default_value_t = 1)]
+    num_ballots: u64,
+
+    /// The ballot style of the selections, 1 <= i <= the number of ballot styles.
+    /// If not given, every selection uses a ballot style picked at random.
+    #[arg(short, long)]
+    ballot_style_index: Option<u32>,
+
+    /// The label of the directory to which to write the selections.
+    /// Default is the current time in seconds since the Unix epoch.
+    #[arg(long)]
+    label: Option<u128>,
+}
+
+impl Subcommand for VoterWriteRandomSelections {
+    fn uses_csprng(&self) -> bool {
+        true
+    }
+
+    fn do_it(&mut self, subcommand_helper: &mut SubcommandHelper) -> Result<()> {
+        let mut csprng = subcommand_helper.get_csprng(b"VoterWriteRandomSelections")?;
+
+        //? TODO: Do we need a command line arg to specify the election manifest source?
+        let election_manifest_source =
+            ElectionManifestSource::ArtifactFileElectionManifestCanonical;
+        let election_manifest =
+            election_manifest_source.load_election_manifest(&subcommand_helper.artifacts_dir)?;
+
+        let cnt_ballot_styles = election_manifest.ballot_styles.len() as u64;
+        if cnt_ballot_styles == 0 {
+            bail!("The election manifest has no ballot styles");
+        }
+
+        let opt_ballot_style_index = self
+            .ballot_style_index
+            .map(|ix| {
+                BallotStyleIndex::from_one_based_index(ix)
+                    .ok()
+                    .filter(|&ix| election_manifest.ballot_styles.get(ix).is_some())
+                    .with_context(|| format!("Ballot style {ix} is not in the election manifest"))
+            })
+            .transpose()?;
+
+        let label = match self.label {
+            Some(label) => label,
+            None => SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs() as u128,
+        };
+        create_path(
+            &subcommand_helper
+                .artifacts_dir
+                .dir_path
+                .join(format!("pre_encrypted/selections/{label}")),
+        );
+
+        for b_idx in 1..=self.num_ballots {
+            let ballot_style_index = match opt_ballot_style_index {
+                Some(ix) => ix,
+                None => {
+                    let ix = csprng.next_u64_lt(cnt_ballot_styles) as u32 + 1;
+                    BallotStyleIndex::from_one_based_index(ix)?
+                }
+            };
+
+            let voter_selection = VoterSelection::new_pick_random(
+                &election_manifest,
+                ballot_style_index,
+                &mut csprng,
+            );
+
+            let (mut stdiowrite, path) = subcommand_helper
+                .artifacts_dir
+                .out_file_stdiowrite(&None, Some(ArtifactFile::VoterSelection(label, b_idx)))?;
+
+            voter_selection
+                .to_stdiowrite(stdiowrite.as_mut())
+                .with_context(|| format!("Writing voter selection to: {}", path.display()))?;
+
+            drop(stdiowrite);
+        }
+
+        eprintln!(
+            "Wrote {} random voter selections with label {label} to: {}",
+            self.num_ballots,
+            subcommand_helper
+                .artifacts_dir
+                .dir_path
+                .join(format!("pre_encrypted/selections/{label}"))
+                .display()
+        );
+
+        Ok(())
+    }
+}
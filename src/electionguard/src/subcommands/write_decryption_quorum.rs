@@ -0,0 +1,66 @@
+// Copyright (C) Microsoft Corporation. All rights reserved.
+
+#![deny(clippy::unwrap_used)]
+#![deny(clippy::expect_used)]
+#![deny(clippy::panic)]
+#![deny(clippy::manual_assert)]
+
+use std::path::PathBuf;
+
+use anyhow::{Context, Result};
+
+use eg::{decryption_quorum::DecryptionQuorum, guardian::GuardianIndex};
+
+use crate::{
+    artifacts_dir::ArtifactFile, common_utils::load_election_parameters,
+    subcommand_helper::SubcommandHelper, subcommands::Subcommand,
+};
+
+/// Select the quorum of guardians that take part in the tally decryption from the guardians
+/// that are present. Only the guardians of the quorum then generate decryption shares.
+#[derive(clap::Args, Debug, Default)]
+pub(crate) struct WriteDecryptionQuorum {
+    /// Comma-separated guardian numbers of the present guardians, 1 <= i <= n.
+    #[arg(long, value_delimiter = ',', required = true)]
+    present: Vec<GuardianIndex>,
+
+    /// File to which to write the decryption quorum.
+    /// Default is in the artifacts dir.
+    /// If "-", write to stdout.
+    #[arg(long)]
+    out_file: Option<PathBuf>,
+}
+
+impl Subcommand for WriteDecryptionQuorum {
+    fn uses_csprng(&self) -> bool {
+        true
+    }
+
+    fn do_it(&mut self, subcommand_helper: &mut SubcommandHelper) -> Result<()> {
+        let mut csprng = subcommand_helper.get_csprng(b"WriteDecryptionQuorum")?;
+
+        let election_parameters =
+            load_election_parameters(&subcommand_helper.artifacts_dir, &mut csprng)?;
+
+        let quorum = DecryptionQuorum::select(&election_parameters, self.present.iter().copied())?;
+
+        let (mut stdiowrite, path) = subcommand_helper
+            .artifacts_dir
+            .out_file_stdiowrite(&self.out_file, Some(ArtifactFile::TallyDecryptionQuorum))?;
+
+        quorum
+            .to_stdiowrite(stdiowrite.as_mut())
+            .with_context(|| format!("Writing decryption quorum to: {}", path.display()))?;
+
+        drop(stdiowrite);
+
+        eprintln!(
+            "Wrote decryption quorum of guardians {:?} (absent: {:?}) to: {}",
+            quorum.participants,
+            quorum.absent,
+            path.display()
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,52 @@
+// Copyright (C) Microsoft Corporation. All rights reserved.
+
+#![deny(clippy::unwrap_used)]
+#![deny(clippy::expect_used)]
+#![deny(clippy::panic)]
+#![deny(clippy::manual_assert)]
+
+use std::path::PathBuf;
+
+use anyhow::{Context, Result};
+
+use crate::{
+    artifacts_dir::ArtifactFile, common_utils::assemble_election_record,
+    subcommand_helper::SubcommandHelper, subcommands::Subcommand,
+};
+
+/// Assemble the election record from the artifacts directory and write it to a single file.
+#[derive(clap::Args, Debug, Default)]
+pub(crate) struct WriteElectionRecord {
+    /// File to which to write the election record.
+    /// Default is in the artifacts dir.
+    /// If "-", write to stdout.
+    #[arg(long)]
+    out_file: Option<PathBuf>,
+}
+
+impl Subcommand for WriteElectionRecord {
+    fn uses_csprng(&self) -> bool {
+        true
+    }
+
+    fn do_it(&mut self, subcommand_helper: &mut SubcommandHelper) -> Result<()> {
+        let mut csprng = subcommand_helper.get_csprng(b"WriteElectionRecord")?;
+
+        let election_record =
+            assemble_election_record(&subcommand_helper.artifacts_dir, &mut csprng)?;
+
+        let (mut stdiowrite, path) = subcommand_helper
+            .artifacts_dir
+            .out_file_stdiowrite(&self.out_file, Some(ArtifactFile::ElectionRecord))?;
+
+        election_record
+            .to_stdiowrite(stdiowrite.as_mut())
+            .with_context(|| format!("Writing election record to: {}", path.display()))?;
+
+        drop(stdiowrite);
+
+        eprintln!("Wrote election record to: {}", path.display());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,64 @@
+// Copyright (C) Microsoft Corporation. All rights reserved.
+
+#![deny(clippy::unwrap_used)]
+#![deny(clippy::expect_used)]
+#![deny(clippy::panic)]
+#![deny(clippy::manual_assert)]
+
+use std::path::PathBuf;
+
+use anyhow::{Context, Result};
+
+use eg::election_record::ElectionRecordBody;
+
+use crate::{
+    artifacts_dir::ArtifactFile,
+    common_utils::{load_all_encrypted_ballots, load_pre_voting_data},
+    subcommand_helper::SubcommandHelper,
+    subcommands::Subcommand,
+};
+
+/// Compute the encrypted tally of all cast ballots in the artifacts directory.
+#[derive(clap::Args, Debug, Default)]
+pub(crate) struct WriteEncryptedTally {
+    /// File to which to write the encrypted tally.
+    /// Default is in the artifacts dir.
+    /// If "-", write to stdout.
+    #[arg(long)]
+    out_file: Option<PathBuf>,
+}
+
+impl Subcommand for WriteEncryptedTally {
+    fn uses_csprng(&self) -> bool {
+        true
+    }
+
+    fn do_it(&mut self, subcommand_helper: &mut SubcommandHelper) -> Result<()> {
+        let mut csprng = subcommand_helper.get_csprng(b"WriteEncryptedTally")?;
+
+        let pre_voting_data = load_pre_voting_data(&subcommand_helper.artifacts_dir, &mut csprng)?;
+
+        let body = ElectionRecordBody::new(load_all_encrypted_ballots(
+            &subcommand_helper.artifacts_dir,
+        )?);
+
+        let encrypted_tally = body.compute_encrypted_tally(&pre_voting_data)?;
+
+        let (mut stdiowrite, path) = subcommand_helper
+            .artifacts_dir
+            .out_file_stdiowrite(&self.out_file, Some(ArtifactFile::EncryptedTally))?;
+
+        ElectionRecordBody::encrypted_tally_to_stdiowrite(&encrypted_tally, stdiowrite.as_mut())
+            .with_context(|| format!("Writing encrypted tally to: {}", path.display()))?;
+
+        drop(stdiowrite);
+
+        eprintln!(
+            "Wrote encrypted tally of {} cast ballots to: {}",
+            body.cast_ballots.len(),
+            path.display()
+        );
+
+        Ok(())
+    }
+}
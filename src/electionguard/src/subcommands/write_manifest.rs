@@ -9,6 +9,8 @@ use std::path::PathBuf;
 
 use anyhow::{bail, Context, Result};
 
+use eg::label::LabelPolicy;
+
 use crate::{
     artifacts_dir::ArtifactFile, common_utils::ElectionManifestSource,
     subcommand_helper::SubcommandHelper, subcommands::Subcommand,
@@ -21,6 +23,24 @@ pub(crate) enum ElectionManifestFormat {
     Pretty,
 }
 
+#[derive(Clone, Copy, Debug, Default, clap::ValueEnum)]
+pub(crate) enum ElectionManifestLabelPolicy {
+    /// Labels violating the label policy are an error.
+    #[default]
+    Strict,
+    /// Labels violating the label policy are reported as warnings.
+    WarnOnly,
+}
+
+impl From<ElectionManifestLabelPolicy> for LabelPolicy {
+    fn from(label_policy: ElectionManifestLabelPolicy) -> Self {
+        match label_policy {
+            ElectionManifestLabelPolicy::Strict => LabelPolicy::Strict,
+            ElectionManifestLabelPolicy::WarnOnly => LabelPolicy::WarnOnly,
+        }
+    }
+}
+
 #[derive(clap::Args, Debug, Default)]
 pub(crate) struct WriteManifest {
     /// Use the pretty JSON election manifest file in the artifacts dir..
@@ -39,6 +59,16 @@ pub(crate) struct WriteManifest {
     #[arg(long)]
     pub in_example: bool,
 
+    /// How to treat labels of the input election manifest that violate the label policy,
+    /// e.g., labels that are not NFC normalized or that are confusable.
+    #[arg(value_enum, long, default_value = "strict")]
+    pub label_policy: ElectionManifestLabelPolicy,
+
+    /// Normalize the labels of the input election manifest to Unicode Normalization Form C
+    /// before checking them.
+    #[arg(long)]
+    pub normalize_labels: bool,
+
     /// Output format. Default is canonical.
     /// Unless `--out-file` is specified, the output is written to the appropriate file in the
     /// artifacts dir.
@@ -77,8 +107,22 @@ impl Subcommand for WriteManifest {
             ElectionManifestSource::ArtifactFileElectionManifestCanonical
         };
 
-        let election_manifest =
-            election_manifest_source.load_election_manifest(&subcommand_helper.artifacts_dir)?;
+        // Labels are only checked against the requested policy after any normalization.
+        let load_label_policy = if self.normalize_labels {
+            LabelPolicy::WarnOnly
+        } else {
+            self.label_policy.into()
+        };
+        let mut election_manifest = election_manifest_source
+            .load_election_manifest_with_label_policy(
+                &subcommand_helper.artifacts_dir,
+                load_label_policy,
+            )?;
+
+        if self.normalize_labels {
+            election_manifest.normalize_labels();
+            election_manifest.validate_labels(self.label_policy.into())?;
+        }
 
         use ElectionManifestFormat::*;
         let (artifact_file, description) = match self.out_format {
@@ -7,10 +7,12 @@
 
 use std::{collections::BTreeMap, fs, path::PathBuf};
 
-use crate::{confirmation_code::confirmation_code, contest::ContestPreEncrypted};
+use crate::{
+    confirmation_code::confirmation_code, contest::ContestPreEncrypted, nonce::PrimaryNonce,
+};
 use anyhow::{anyhow, Context, Result};
 use eg::{
-    ballot::{BallotEncrypted, BallotState},
+    ballot::BallotEncrypted,
     ballot_style::BallotStyleIndex,
     contest_selection::ContestSelection,
     device::Device,
@@ -60,10 +62,8 @@ impl VoterSelection {
             let contest = manifest.contests.get(*i).unwrap();
             #[allow(clippy::unwrap_used)] //? TODO: Remove temp development code
             selections
-                .try_push(ContestSelection::new_pick_random(
-                    csprng,
-                    contest.selection_limit,
-                    contest.options.len(),
+                .try_push(ContestSelection::new_pick_random_for_contest(
+                    csprng, contest,
                 ))
                 .unwrap();
         });
@@ -147,13 +147,17 @@ impl BallotPreEncrypted {
         ballot_style_index: BallotStyleIndex,
         csprng: &mut Csprng,
         store_nonces: bool,
-    ) -> (BallotPreEncrypted, HValue) {
-        let mut primary_nonce = [0u8; 32];
-        (0..32).for_each(|i| primary_nonce[i] = csprng.next_u8());
+    ) -> (BallotPreEncrypted, PrimaryNonce) {
+        let primary_nonce = PrimaryNonce::generate(csprng);
 
         (
-            BallotPreEncrypted::new_with(pv_data, ballot_style_index, &primary_nonce, store_nonces),
-            HValue(primary_nonce),
+            BallotPreEncrypted::new_with(
+                pv_data,
+                ballot_style_index,
+                primary_nonce.as_bytes(),
+                store_nonces,
+            ),
+            primary_nonce,
         )
     }
 
@@ -173,6 +177,8 @@ impl BallotPreEncrypted {
         }
     }
 
+    /// Finalizes the pre-encrypted ballot with the voter's selections into an uncast
+    /// [`BallotEncrypted`].
     pub fn finalize(
         &self,
         device: &Device,
@@ -209,7 +215,6 @@ impl BallotPreEncrypted {
 
         BallotEncrypted::new(
             &contests,
-            BallotState::Cast,
             self.confirmation_code,
             &device.header.parameters.varying_parameters.date,
             device.get_uuid(),
@@ -18,6 +18,7 @@ use util::logging::Logging;
 
 use crate::ballot::BallotPreEncrypted;
 use crate::contest::ContestPreEncrypted;
+use crate::nonce::PrimaryNonce;
 
 pub struct BallotEncryptingTool {
     /// The pre-voting data.
@@ -75,7 +76,7 @@ impl BallotEncryptingTool {
         &self,
         csprng: &mut Csprng,
         num_ballots: usize,
-    ) -> (Vec<BallotPreEncrypted>, Vec<HValue>) {
+    ) -> (Vec<BallotPreEncrypted>, Vec<PrimaryNonce>) {
         let mut ballots = Vec::new();
         let mut primary_nonces = Vec::new();
 
@@ -11,7 +11,7 @@ use anyhow::Result;
 
 use eg::{ballot_style::BallotStyleIndex, election_record::PreVotingData, hash::HValue};
 
-use crate::ballot::BallotPreEncrypted;
+use crate::{ballot::BallotPreEncrypted, nonce::PrimaryNonce};
 
 pub struct BallotRecordingTool {
     /// The election record header.
@@ -38,12 +38,12 @@ impl BallotRecordingTool {
         &self,
         ballot: &BallotPreEncrypted,
         ballot_style_index: BallotStyleIndex,
-        primary_nonce: &HValue,
+        primary_nonce: &PrimaryNonce,
     ) -> (Option<BallotPreEncrypted>, bool) {
         let regenerated_ballot = BallotPreEncrypted::new_with(
             &self.pre_voting_data,
             ballot_style_index,
-            &primary_nonce.0,
+            primary_nonce.as_bytes(),
             true,
         );
         if *ballot != regenerated_ballot {
@@ -166,7 +166,19 @@ impl ContestPreEncrypted {
 
         assert!(selections.len() == selection_limit);
 
-        let mut combined_selection = selections[0].clone();
+        // The voter's selections are only combined on a regenerated ballot, whose selections
+        // always store their nonces. The nonces are copied explicitly, as `Nonce` is not `Clone`.
+        let nonce_of = |maybe_nonce: &Option<Nonce>| {
+            #[allow(clippy::expect_used)]
+            maybe_nonce
+                .as_ref()
+                .map(|nonce| nonce.xi.clone())
+                .expect("the selections of a regenerated ballot store their nonces")
+        };
+        let mut combined_selection: Vec<(Ciphertext, Nonce)> = selections[0]
+            .iter()
+            .map(|(ct, maybe_nonce)| (ct.clone(), Nonce::new(nonce_of(maybe_nonce))))
+            .collect();
 
         #[allow(clippy::needless_range_loop)]
         for i in 1..selections.len() {
@@ -183,21 +195,14 @@ impl ContestPreEncrypted {
                     .beta
                     .mul(&selections_i_j.0.beta, group);
 
-                #[allow(clippy::unwrap_used)] //? TODO: Remove temp development code
                 let cs_j_nonce = combined_selection_j
                     .1
-                    .as_ref()
-                    .unwrap()
                     .xi
-                    .add(&selections_i_j.1.as_ref().unwrap().xi, field);
-                combined_selection_j.1 = Some(Nonce::new(cs_j_nonce));
+                    .add(&nonce_of(&selections_i_j.1), field);
+                combined_selection_j.1 = Nonce::new(cs_j_nonce);
             }
         }
-        #[allow(clippy::unwrap_used)] //? TODO: Remove temp development code
         combined_selection
-            .iter()
-            .map(|(ct, maybe_nonce)| (ct.clone(), maybe_nonce.as_ref().unwrap().clone()))
-            .collect()
     }
 
     pub fn finalize(
@@ -248,6 +253,7 @@ impl ContestPreEncrypted {
             contest_hash: self.contest_hash,
             proof_ballot_correctness,
             proof_selection_limit,
+            contest_data: None,
         }
     }
 }
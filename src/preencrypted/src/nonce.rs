@@ -5,12 +5,60 @@
 #![deny(clippy::panic)]
 #![deny(clippy::manual_assert)]
 
+use anyhow::{Context, Result};
 use eg::{
     election_manifest::{ContestIndex, ContestOptionIndex},
     election_record::PreVotingData,
-    hash::eg_h,
+    hash::{eg_h, HValue, HVALUE_BYTE_LEN},
 };
-use util::algebra::FieldElement;
+use serde::{Deserialize, Serialize};
+use util::{algebra::FieldElement, csprng::Csprng};
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+/// The primary nonce `ξ` of a pre-encrypted ballot, from which all its nonces are derived.
+///
+/// The nonce is zeroized on drop and redacted in its `Debug` output. It is not `Clone`, so that
+/// no copies of it are left behind.
+#[derive(Serialize, Deserialize, Zeroize, ZeroizeOnDrop)]
+#[serde(transparent)]
+pub struct PrimaryNonce(HValue);
+
+impl PrimaryNonce {
+    /// Generates a fresh primary nonce.
+    pub fn generate(csprng: &mut Csprng) -> Self {
+        let mut primary_nonce = PrimaryNonce(HValue::default());
+        (0..HVALUE_BYTE_LEN).for_each(|i| primary_nonce.0 .0[i] = csprng.next_u8());
+        primary_nonce
+    }
+
+    /// Returns the bytes of the primary nonce.
+    pub fn as_bytes(&self) -> &[u8] {
+        self.0 .0.as_slice()
+    }
+
+    /// Reads a `PrimaryNonce` from a `std::io::Read`.
+    pub fn from_stdioread(stdioread: &mut dyn std::io::Read) -> Result<Self> {
+        serde_json::from_reader(stdioread).context("Reading PrimaryNonce")
+    }
+
+    /// Writes a `PrimaryNonce` to a `std::io::Write`.
+    pub fn to_stdiowrite(&self, stdiowrite: &mut dyn std::io::Write) -> Result<()> {
+        let mut ser = serde_json::Serializer::pretty(stdiowrite);
+
+        self.serialize(&mut ser)
+            .context("Error writing PrimaryNonce")?;
+
+        ser.into_inner()
+            .write_all(b"\n")
+            .context("Error writing PrimaryNonce file")
+    }
+}
+
+impl std::fmt::Debug for PrimaryNonce {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("PrimaryNonce(<redacted>)")
+    }
+}
 
 // impl Nonce {
 /// Generates a nonce for pre-encrypted ballots (Equation 97)
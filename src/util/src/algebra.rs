@@ -8,12 +8,15 @@
 use crate::{
     algebra_utils::{cnt_bits_repr, mod_inverse, to_be_bytes_left_pad},
     csprng::Csprng,
+    fixed_base::LazyFixedBaseTable,
+    montgomery::LazyMontgomery,
     prime::is_prime,
 };
 use num_bigint::BigUint;
 use num_integer::Integer;
 use num_traits::{One, Zero};
 use serde::{Deserialize, Serialize};
+use zeroize::Zeroize;
 
 /// A an element of field `Z_q` as defined by [`ScalarField`].
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq, Hash)]
@@ -22,9 +25,21 @@ pub struct FieldElement(
         serialize_with = "crate::biguint_serde::biguint_serialize",
         deserialize_with = "crate::biguint_serde::biguint_deserialize"
     )]
-    BigUint,
+    pub(crate) BigUint,
 );
 
+impl Zeroize for FieldElement {
+    /// Overwrites the digits of the element with zeros, which also sets it to zero.
+    ///
+    /// As [`BigUint`] does not expose its buffer, this is best effort: digits that an earlier
+    /// computation left in unused capacity of the buffer are not overwritten.
+    fn zeroize(&mut self) {
+        let cnt_digits = self.0.bits().div_ceil(32) as usize;
+        // This clears the buffer and then extends it in place.
+        self.0.assign_from_slice(&vec![0_u32; cnt_digits]);
+    }
+}
+
 /// The finite field `Z_q` of integers modulo prime `q`.
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
 pub struct ScalarField {
@@ -34,6 +49,10 @@ pub struct ScalarField {
         deserialize_with = "crate::biguint_serde::biguint_deserialize"
     )]
     q: BigUint,
+    /// Montgomery context for the order `q`, computed on first use.
+    #[cfg(feature = "montgomery")]
+    #[serde(skip)]
+    montgomery: LazyMontgomery,
 }
 
 impl FieldElement {
@@ -59,7 +78,7 @@ impl FieldElement {
     ///
     /// That is the function computes `(self * other) % q` where `q` is the field order.
     pub fn mul(&self, other: &FieldElement, field: &ScalarField) -> Self {
-        FieldElement((&self.0 * &other.0) % &field.q)
+        FieldElement(field.mul_mod_q(&self.0, &other.0))
     }
 
     /// Computes the multiplicative inverse of a field element if it exists
@@ -75,7 +94,7 @@ impl FieldElement {
     /// Performs modular exponentiation of the field element with a given integer exponent.
     pub fn pow(&self, exponent: impl Into<BigUint>, field: &ScalarField) -> FieldElement {
         let x = exponent.into();
-        FieldElement(self.0.modpow(&x, &field.q))
+        FieldElement(field.pow_mod_q(&self.0, &x))
     }
 
     /// Creates a field element from a given integer.
@@ -133,7 +152,7 @@ impl ScalarField {
     ///
     /// Alternatively, one can use fixed, *trusted/tested* parameters with [`ScalarField::new_unchecked`].
     pub fn new(order: BigUint, csprng: &mut Csprng) -> Option<Self> {
-        let f = ScalarField { q: order };
+        let f = Self::new_unchecked(order);
         if f.is_valid(csprng) {
             return Some(f);
         }
@@ -144,7 +163,11 @@ impl ScalarField {
     ///
     /// This function *assumes* that the given order is prime.
     pub fn new_unchecked(order: BigUint) -> Self {
-        ScalarField { q: order }
+        ScalarField {
+            q: order,
+            #[cfg(feature = "montgomery")]
+            montgomery: LazyMontgomery::default(),
+        }
     }
 
     /// The function validates the given field by checking that the modulus is prime. The call is expensive.
@@ -180,6 +203,24 @@ impl ScalarField {
     pub fn l_q(&self) -> usize {
         (cnt_bits_repr(&self.q) + 7) / 8
     }
+
+    /// Computes `a * b mod q`, in Montgomery form with the `montgomery` feature.
+    fn mul_mod_q(&self, a: &BigUint, b: &BigUint) -> BigUint {
+        #[cfg(feature = "montgomery")]
+        if let Some(montgomery) = self.montgomery.get(&self.q) {
+            return montgomery.mul(a, b);
+        }
+        (a * b) % &self.q
+    }
+
+    /// Computes `base^exponent mod q`, in Montgomery form with the `montgomery` feature.
+    fn pow_mod_q(&self, base: &BigUint, exponent: &BigUint) -> BigUint {
+        #[cfg(feature = "montgomery")]
+        if let Some(montgomery) = self.montgomery.get(&self.q) {
+            return montgomery.pow(base, exponent);
+        }
+        base.modpow(exponent, &self.q)
+    }
 }
 
 /// An element of the multiplicative group `Z_p^r` as defined by [`Group`].
@@ -189,7 +230,7 @@ pub struct GroupElement(
         serialize_with = "crate::biguint_serde::biguint_serialize",
         deserialize_with = "crate::biguint_serde::biguint_deserialize"
     )]
-    BigUint,
+    pub(crate) BigUint,
 );
 
 /// The group `Z_p^r`, a multiplicative subgroup of `Z_p`.
@@ -213,6 +254,15 @@ pub struct Group {
         deserialize_with = "crate::biguint_serde::biguint_deserialize"
     )]
     q: BigUint,
+    /// Fixed-base table for the generator `g`, computed on first use.
+    #[serde(skip)]
+    g_table: LazyFixedBaseTable,
+    /// Montgomery context for the modulus `p`, computed on first use.
+    ///
+    /// It is used for all arithmetic with the `montgomery` feature, and for exponentiations
+    /// with secret exponents in any case.
+    #[serde(skip)]
+    montgomery: LazyMontgomery,
 }
 
 impl GroupElement {
@@ -220,7 +270,7 @@ impl GroupElement {
     ///
     /// That is the function computes `(self * other) mod p` where `p` is the group modulus.
     pub fn mul(&self, other: &GroupElement, group: &Group) -> GroupElement {
-        GroupElement((&self.0 * &other.0) % &group.p)
+        GroupElement(group.mul_mod_p(&self.0, &other.0))
     }
 
     /// Computes the (multiplicative) inverse of a group element.
@@ -233,14 +283,23 @@ impl GroupElement {
     /// Performs modular exponentiation of the group element with a given integer exponent.
     pub fn pow(&self, exponent: impl Into<BigUint>, group: &Group) -> GroupElement {
         let x = exponent.into();
-        GroupElement(self.0.modpow(&x, &group.p))
+        GroupElement(group.pow_mod_p(&self.0, &x))
     }
 
     /// Performs modular exponentiation of the group element with a given field element.
     ///
     /// This defines an action of the field over the group.
     pub fn exp(&self, exponent: &FieldElement, group: &Group) -> GroupElement {
-        GroupElement(self.0.modpow(&exponent.0, &group.p))
+        GroupElement(group.pow_mod_p(&self.0, &exponent.0))
+    }
+
+    /// Performs modular exponentiation of the group element with a secret field element, e.g., a
+    /// secret key or an encryption nonce.
+    ///
+    /// Unlike [`GroupElement::exp`], the running time and memory accesses do not depend on the
+    /// value of the exponent, see [`Montgomery::pow_secret`](crate::montgomery::Montgomery::pow_secret).
+    pub fn exp_secret(&self, exponent: &FieldElement, group: &Group) -> GroupElement {
+        GroupElement(group.pow_secret_mod_p(&self.0, &exponent.0))
     }
 
     /// Checks if the element is a valid member of the given group.
@@ -249,7 +308,7 @@ impl GroupElement {
     pub fn is_valid(&self, group: &Group) -> bool {
         // It is enough to check the upper bound as self.0 is unsigned.
         let elem_less_than_p = self.0 < group.p;
-        let elem_has_order_q = self.0.modpow(&group.q, &group.p).is_one();
+        let elem_has_order_q = group.pow_mod_p(&self.0, &group.q).is_one();
         elem_less_than_p && elem_has_order_q
     }
 
@@ -285,11 +344,7 @@ impl Group {
         generator: BigUint,
         csprng: &mut Csprng,
     ) -> Option<Self> {
-        let group = Group {
-            p: modulus,
-            g: generator,
-            q: order,
-        };
+        let group = Self::new_unchecked(modulus, order, generator);
         if group.is_valid(csprng) {
             return Some(group);
         }
@@ -302,6 +357,8 @@ impl Group {
             p: modulus,
             g: generator,
             q: order,
+            g_table: LazyFixedBaseTable::default(),
+            montgomery: LazyMontgomery::default(),
         }
     }
 
@@ -324,7 +381,7 @@ impl Group {
 
         // This ensures that the order of generator `g` is at most `q` and `g != 1`
         // and if `q` is prime => order of generator is `q`
-        if self.g.is_one() || !self.g.modpow(&self.q, &self.p).is_one() {
+        if self.g.is_one() || !self.pow_mod_p(&self.g, &self.q).is_one() {
             return false;
         }
 
@@ -350,8 +407,19 @@ impl Group {
     }
 
     /// Returns generator `g` raised to the power of `x` mod modulus `p`.
+    ///
+    /// This uses a [fixed-base table](crate::fixed_base) for `g`, which is computed on first use
+    /// and shared by clones of the group.
     pub fn g_exp(&self, x: &FieldElement) -> GroupElement {
-        GroupElement(self.g.modpow(&x.0, &self.p))
+        self.g_table.exp(&self.generator(), x, self)
+    }
+
+    /// Returns generator `g` raised to the power of a secret `x` mod modulus `p`.
+    ///
+    /// Like [`Group::g_exp`] this uses the fixed-base table for `g`, but the running time and
+    /// memory accesses do not depend on the value of `x`, see [`GroupElement::exp_secret`].
+    pub fn g_exp_secret(&self, x: &FieldElement) -> GroupElement {
+        self.g_table.exp_secret(&self.generator(), x, self)
     }
 
     /// Returns one, the neutral element, as a group element.
@@ -385,6 +453,85 @@ impl Group {
     pub fn matches_field(self: &Group, field: &ScalarField) -> bool {
         self.q == field.q
     }
+
+    /// Computes `a * b mod p`, in Montgomery form with the `montgomery` feature.
+    fn mul_mod_p(&self, a: &BigUint, b: &BigUint) -> BigUint {
+        #[cfg(feature = "montgomery")]
+        if let Some(montgomery) = self.montgomery.get(&self.p) {
+            return montgomery.mul(a, b);
+        }
+        (a * b) % &self.p
+    }
+
+    /// Computes `base^exponent mod p`, in Montgomery form with the `montgomery` feature.
+    fn pow_mod_p(&self, base: &BigUint, exponent: &BigUint) -> BigUint {
+        #[cfg(feature = "montgomery")]
+        if let Some(montgomery) = self.montgomery.get(&self.p) {
+            return montgomery.pow(base, exponent);
+        }
+        base.modpow(exponent, &self.p)
+    }
+
+    /// Computes `base^exponent mod p` for a secret exponent less than `q`, in time that does not
+    /// depend on the exponent.
+    ///
+    /// Falls back to a generic exponentiation if `p` is even, i.e., for an invalid group.
+    fn pow_secret_mod_p(&self, base: &BigUint, exponent: &BigUint) -> BigUint {
+        if let Some(montgomery) = self.montgomery.get(&self.p) {
+            return montgomery.pow_secret(base, exponent, self.q.bits() as usize);
+        }
+        base.modpow(exponent, &self.p)
+    }
+
+    /// Converts `a` to the representation in which [`Group::mul_repr`] multiplies, i.e., to
+    /// Montgomery form with the `montgomery` feature, and to `a mod p` otherwise.
+    ///
+    /// A sequence of multiplications in this representation, e.g., in a
+    /// [fixed-base table](crate::fixed_base), saves the reduction modulo `p` of every product.
+    pub(crate) fn repr_of(&self, a: &BigUint) -> BigUint {
+        #[cfg(feature = "montgomery")]
+        if let Some(montgomery) = self.montgomery.get(&self.p) {
+            return montgomery.to_montgomery(a);
+        }
+        a % &self.p
+    }
+
+    /// Converts `a` back from the representation of [`Group::repr_of`].
+    pub(crate) fn repr_value(&self, a: BigUint) -> BigUint {
+        #[cfg(feature = "montgomery")]
+        if let Some(montgomery) = self.montgomery.get(&self.p) {
+            return montgomery.from_montgomery(&a);
+        }
+        a
+    }
+
+    /// Multiplies two values in the representation of [`Group::repr_of`].
+    pub(crate) fn mul_repr(&self, a: &BigUint, b: &BigUint) -> BigUint {
+        #[cfg(feature = "montgomery")]
+        if let Some(montgomery) = self.montgomery.get(&self.p) {
+            return montgomery.mul_montgomery(a, b);
+        }
+        (a * b) % &self.p
+    }
+
+    /// Multiplies two values in the representation of [`Group::repr_of`], in time that does not
+    /// depend on the values.
+    pub(crate) fn mul_repr_secret(&self, a: &BigUint, b: &BigUint) -> BigUint {
+        match self.montgomery.get(&self.p) {
+            Some(montgomery) if cfg!(feature = "montgomery") => montgomery.mul_montgomery(a, b),
+            Some(montgomery) => montgomery.mul(a, b),
+            None => self.mul_repr(a, b),
+        }
+    }
+
+    /// Returns `candidates[index]` for values in the representation of [`Group::repr_of`],
+    /// reading all candidates, so that the memory accesses do not depend on the index.
+    pub(crate) fn select_repr_secret(&self, candidates: &[BigUint], index: usize) -> BigUint {
+        match self.montgomery.get(&self.p) {
+            Some(montgomery) => montgomery.select_secret(candidates, index),
+            None => candidates.get(index).cloned().unwrap_or_default(),
+        }
+    }
 }
 
 // Unit tests for algebra.
@@ -394,6 +541,7 @@ mod test {
     use crate::algebra::{FieldElement, Group, GroupElement, ScalarField};
     use crate::csprng::Csprng;
     use num_bigint::BigUint;
+    use zeroize::Zeroize;
 
     fn get_toy_algebras() -> (ScalarField, Group) {
         (
@@ -473,6 +621,11 @@ mod test {
         let g = group.generator();
         assert_eq!(g.pow(14_u32, &group), g2);
 
+        // Exponentiations with secret exponents give the same results.
+        assert_eq!(group.g_exp_secret(&a), g1);
+        assert_eq!(g.exp_secret(&a, &group), g1);
+        assert_eq!(g1.exp_secret(&ScalarField::zero(), &group), Group::one());
+
         for _ in 0..100 {
             let u = group.random_group_elem(&mut csprng);
             assert!(u.is_valid(&group));
@@ -569,4 +722,11 @@ mod test {
         // Testing length of encoding
         assert_eq!(u.to_32_be_bytes().len(), 32)
     }
+
+    #[test]
+    fn test_field_element_zeroize() {
+        let mut u = FieldElement(BigUint::from(u128::MAX));
+        u.zeroize();
+        assert_eq!(u, ScalarField::zero());
+    }
 }
@@ -100,6 +100,43 @@ pub fn mod_inverse(a_u: &BigUint, m_u: &BigUint) -> Option<BigUint> {
     None
 }
 
+/// Computes the Jacobi symbol `(a/n)` for an odd modulus `n`.
+///
+/// For a prime `n` this is the Legendre symbol, i.e., `1` if `a` is a non-zero square modulo
+/// `n`, `-1` if it is a non-square, and `0` if `n` divides `a`. Returns `0` if `n` is even.
+///
+/// Unlike Euler's criterion this needs no modular exponentiation, only a gcd-like sequence of
+/// reductions.
+pub fn jacobi_symbol(a: &BigUint, n: &BigUint) -> i8 {
+    if !n.bit(0) {
+        return 0;
+    }
+    let low_bits = |x: &BigUint| x.iter_u64_digits().next().unwrap_or(0) & 7;
+
+    let mut a = a % n;
+    let mut n = n.clone();
+    let mut symbol = 1;
+    while let Some(cnt_zeros) = a.trailing_zeros() {
+        // (2/n) = -1 iff n = 3, 5 mod 8
+        a >>= cnt_zeros;
+        if cnt_zeros % 2 == 1 && matches!(low_bits(&n), 3 | 5) {
+            symbol = -symbol;
+        }
+        // Quadratic reciprocity for the odd a and n
+        if low_bits(&a) & 3 == 3 && low_bits(&n) & 3 == 3 {
+            symbol = -symbol;
+        }
+        mem::swap(&mut a, &mut n);
+        a %= &n;
+    }
+
+    if n.is_one() {
+        symbol
+    } else {
+        0
+    }
+}
+
 /// Holds a hash table of the Baby-step giant-step algorithm for computing discrete logarithms with respect to `base` and `modulus`.
 pub struct DiscreteLog {
     /// The hash table
@@ -330,6 +367,40 @@ mod tests {
         )
     }
 
+    #[test]
+    fn test_jacobi_symbol() {
+        // For a prime modulus the Jacobi symbol matches Euler's criterion.
+        let p = BigUint::from(59183_u32);
+        let half = (&p - 1_u8) / 2_u8;
+        for a in [0_u32, 1, 2, 3, 12345, 32616, 59182, 59183, 100000] {
+            let a = BigUint::from(a);
+            let expected = match a.modpow(&half, &p) {
+                x if x.is_zero() => 0,
+                x if x.is_one() => 1,
+                _ => -1,
+            };
+            assert_eq!(jacobi_symbol(&a, &p), expected, "a = {a}");
+        }
+
+        // (2/15) = (2/3)(2/5) = 1, although 2 is not a square modulo 15
+        assert_eq!(
+            jacobi_symbol(&BigUint::from(2_u8), &BigUint::from(15_u8)),
+            1
+        );
+        assert_eq!(
+            jacobi_symbol(&BigUint::from(7_u8), &BigUint::from(15_u8)),
+            -1
+        );
+        assert_eq!(
+            jacobi_symbol(&BigUint::from(6_u8), &BigUint::from(15_u8)),
+            0
+        );
+        assert_eq!(
+            jacobi_symbol(&BigUint::from(3_u8), &BigUint::from(12_u8)),
+            0
+        );
+    }
+
     fn get_toy_algebras() -> (ScalarField, Group) {
         (
             ScalarField::new_unchecked(BigUint::from(127_u8)),
@@ -103,6 +103,24 @@ impl Csprng {
         u64::from_le_bytes(buf)
     }
 
+    /// Returns a random number uniformly from `0 <= n < end`.
+    /// `end` must be greater than `0`.
+    ///
+    /// Unlike `next_u64() % end`, this is free of modulo bias: values from the incomplete
+    /// final interval of length `2^64 mod end` are rejected and drawn again.
+    pub fn next_u64_lt(&mut self, end: u64) -> u64 {
+        assert!(end != 0, "end must be greater than 0");
+
+        // `2^64 mod end`, i.e., the count of values below which a draw would be biased.
+        let threshold = end.wrapping_neg() % end;
+        loop {
+            let n = self.next_u64();
+            if threshold <= n {
+                break n % end;
+            }
+        }
+    }
+
     /// Returns a uniformly random `bool`.
     pub fn next_bool(&mut self) -> bool {
         self.next_u8() & 1 != 0
@@ -228,6 +246,26 @@ mod test_csprng {
         assert!(csprng.next_bool());
     }
 
+    #[test]
+    fn next_u64_lt() {
+        let mut csprng = Csprng::new(b"test_csprng::next_u64_lt");
+        for end in 1u64..100 {
+            let j = csprng.next_u64_lt(end);
+            assert!(j < end);
+        }
+
+        let end = (1u64 << 63) + 1;
+        for _ in 0..100 {
+            assert!(csprng.next_u64_lt(end) < end);
+        }
+
+        let mut counts = [0usize; 3];
+        for _ in 0..3000 {
+            counts[csprng.next_u64_lt(3) as usize] += 1;
+        }
+        assert!(counts.iter().all(|&c| 900 < c && c < 1100));
+    }
+
     #[test]
     fn next_biguint() {
         let mut csprng = Csprng::new(b"test_csprng::next_biguint");
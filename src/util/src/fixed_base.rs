@@ -0,0 +1,271 @@
+// Copyright (C) Microsoft Corporation. All rights reserved.
+
+#![deny(clippy::unwrap_used)]
+#![deny(clippy::expect_used)]
+#![deny(clippy::panic)]
+#![deny(clippy::manual_assert)]
+
+//! This module provides fixed-base precomputation tables for exponentiation in a [`Group`].
+//!
+//! Almost all exponentiations in ElectionGuard have the generator `g` or the joint election
+//! public key `K` as their base. For such a fixed base `b` a [`FixedBaseTable`] stores the
+//! powers `b^(d * 2^(w*i))` for every window `i` of `w` bits of an exponent and every digit
+//! `1 <= d < 2^w`. An exponentiation `b^x` is then the product of one table entry per non-zero
+//! window of `x`, i.e., at most `⌈|q|/w⌉` modular multiplications instead of the roughly `|q|`
+//! squarings and further multiplications of a generic modular exponentiation.
+//!
+//! A [`LazyFixedBaseTable`] computes the table on first use, so that it can be cached next to
+//! the base, e.g., in [`Group`].
+
+use std::sync::{Arc, OnceLock};
+
+use num_bigint::BigUint;
+use num_traits::One;
+
+use crate::{
+    algebra::{FieldElement, Group, GroupElement},
+    algebra_utils::cnt_bits_repr,
+};
+
+/// A table of precomputed powers of a fixed base for fast exponentiation.
+pub struct FixedBaseTable {
+    /// The base `b`.
+    base: BigUint,
+    /// The modulus `p` of the group.
+    modulus: BigUint,
+    /// `powers[i][d - 1] = b^(d * 2^(w*i)) mod p` for the window width `w`, in the
+    /// representation of the group's modular arithmetic backend, i.e., in Montgomery form with
+    /// the `montgomery` feature.
+    powers: Vec<Vec<BigUint>>,
+}
+
+impl FixedBaseTable {
+    /// The window width `w` in bits.
+    ///
+    /// For the standard parameters the table for one base has `43 * 63` entries of `512` bytes
+    /// each. Computing it costs about as much as fifteen generic exponentiations, every
+    /// subsequent exponentiation is about four times faster.
+    pub const WINDOW_BITS: usize = 6;
+
+    /// Precomputes the table for the given base. The base should be an element of the group.
+    pub fn new(base: &GroupElement, group: &Group) -> Self {
+        let p = group.modulus();
+        let cnt_windows = cnt_bits_repr(group.order()).div_ceil(Self::WINDOW_BITS);
+        let cnt_digits = (1_usize << Self::WINDOW_BITS) - 1;
+
+        let mut powers = Vec::with_capacity(cnt_windows);
+
+        // The base of the current window, i.e., `b^(2^(w*i))`.
+        let mut b_i = group.repr_of(base.as_biguint());
+        for _ in 0..cnt_windows {
+            let mut row = Vec::with_capacity(cnt_digits);
+            row.push(b_i.clone());
+            for d in 1..cnt_digits {
+                let power = group.mul_repr(&row[d - 1], &b_i);
+                row.push(power);
+            }
+            b_i = group.mul_repr(&row[cnt_digits - 1], &b_i);
+            powers.push(row);
+        }
+
+        FixedBaseTable {
+            base: base.as_biguint().clone(),
+            modulus: p.clone(),
+            powers,
+        }
+    }
+
+    /// Returns whether this is the table for the given base in the given group.
+    pub fn is_for(&self, base: &GroupElement, group: &Group) -> bool {
+        &self.base == base.as_biguint() && &self.modulus == group.modulus()
+    }
+
+    /// Computes `b^x mod p` for the base `b` of the table, which must have been computed for
+    /// the given group, see [`FixedBaseTable::is_for`].
+    ///
+    /// The exponent must be a valid field element, i.e., less than the group order `q`.
+    /// Exponents with more bits than the group order fall back to a generic exponentiation.
+    pub fn exp(&self, exponent: &FieldElement, group: &Group) -> GroupElement {
+        let x = &exponent.0;
+        if x.bits() > (self.powers.len() * Self::WINDOW_BITS) as u64 {
+            return GroupElement(self.base.clone()).exp(exponent, group);
+        }
+
+        let limbs = x.to_u64_digits();
+        let mask = (1_u64 << Self::WINDOW_BITS) - 1;
+
+        let mut result = group.repr_of(&BigUint::one());
+        for (i, row) in self.powers.iter().enumerate() {
+            let offset = i * Self::WINDOW_BITS;
+            let (limb_ix, shift) = (offset / 64, offset % 64);
+
+            let Some(&limb) = limbs.get(limb_ix) else {
+                break;
+            };
+            let mut digit = limb >> shift;
+            if shift + Self::WINDOW_BITS > 64 {
+                if let Some(&next_limb) = limbs.get(limb_ix + 1) {
+                    digit |= next_limb << (64 - shift);
+                }
+            }
+            let digit = (digit & mask) as usize;
+
+            if digit != 0 {
+                result = group.mul_repr(&result, &row[digit - 1]);
+            }
+        }
+
+        GroupElement(group.repr_value(result))
+    }
+
+    /// Computes `b^x mod p` for the base `b` of the table and a secret exponent `x`, like
+    /// [`FixedBaseTable::exp`].
+    ///
+    /// The running time and memory accesses do not depend on the value of `x`: every window is
+    /// multiplied in, also if its digit is zero, and the entry for a digit is selected by
+    /// reading the whole row.
+    pub fn exp_secret(&self, exponent: &FieldElement, group: &Group) -> GroupElement {
+        let x = &exponent.0;
+        let cnt_bits = self.powers.len() * Self::WINDOW_BITS;
+        if x.bits() > cnt_bits as u64 {
+            return GroupElement(self.base.clone()).exp_secret(exponent, group);
+        }
+
+        // One more limb than needed, so that every window can read the limb after its own.
+        let mut limbs = x.to_u64_digits();
+        limbs.resize(cnt_bits.div_ceil(64) + 1, 0);
+        let mask = (1_u64 << Self::WINDOW_BITS) - 1;
+
+        let one = group.repr_of(&BigUint::one());
+        let mut result = one.clone();
+        for (i, row) in self.powers.iter().enumerate() {
+            let offset = i * Self::WINDOW_BITS;
+            let (limb_ix, shift) = (offset / 64, offset % 64);
+
+            // The shift of the next limb is split in two, so that it is less than 64.
+            let next_limb = (limbs[limb_ix + 1] << 1) << (63 - shift);
+            let digit = (((limbs[limb_ix] >> shift) | next_limb) & mask) as usize;
+
+            // For digit zero no entry of the row is selected, and then `1` instead.
+            let entry = group.select_repr_secret(row, digit.wrapping_sub(1));
+            let factor = group.select_repr_secret(&[one.clone(), entry], (digit != 0) as usize);
+            result = group.mul_repr_secret(&result, &factor);
+        }
+
+        GroupElement(group.repr_value(result))
+    }
+}
+
+/// A [`FixedBaseTable`] that is computed when it is first used.
+///
+/// Clones share the computed table. Two lazy tables always compare as equal, as they are only
+/// a cache of their base.
+#[derive(Clone, Default)]
+pub struct LazyFixedBaseTable(OnceLock<Arc<FixedBaseTable>>);
+
+impl LazyFixedBaseTable {
+    /// Computes `base^x mod p`, using the table for `base`.
+    ///
+    /// The table is computed for `base` on first use. If it was computed for a different base or
+    /// group, e.g., because the base was modified, this falls back to a generic exponentiation.
+    pub fn exp(&self, base: &GroupElement, exponent: &FieldElement, group: &Group) -> GroupElement {
+        let table = self
+            .0
+            .get_or_init(|| Arc::new(FixedBaseTable::new(base, group)));
+        if table.is_for(base, group) {
+            table.exp(exponent, group)
+        } else {
+            base.exp(exponent, group)
+        }
+    }
+
+    /// Computes `base^x mod p` for a secret exponent `x`, using the table for `base` like
+    /// [`LazyFixedBaseTable::exp`], see [`FixedBaseTable::exp_secret`].
+    pub fn exp_secret(
+        &self,
+        base: &GroupElement,
+        exponent: &FieldElement,
+        group: &Group,
+    ) -> GroupElement {
+        let table = self
+            .0
+            .get_or_init(|| Arc::new(FixedBaseTable::new(base, group)));
+        if table.is_for(base, group) {
+            table.exp_secret(exponent, group)
+        } else {
+            base.exp_secret(exponent, group)
+        }
+    }
+
+    /// Returns whether the table has been computed.
+    pub fn is_computed(&self) -> bool {
+        self.0.get().is_some()
+    }
+}
+
+impl PartialEq for LazyFixedBaseTable {
+    fn eq(&self, _other: &Self) -> bool {
+        true
+    }
+}
+
+impl Eq for LazyFixedBaseTable {}
+
+impl std::fmt::Debug for LazyFixedBaseTable {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LazyFixedBaseTable")
+            .field("is_computed", &self.is_computed())
+            .finish()
+    }
+}
+
+// Unit tests for fixed-base tables.
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod test {
+    use super::*;
+    use crate::{algebra::ScalarField, csprng::Csprng};
+
+    #[test]
+    fn test_fixed_base_table() {
+        let mut csprng = Csprng::new(b"testing fixed-base tables");
+
+        // Toy parameters according to specs
+        let field = ScalarField::new_unchecked(BigUint::from(127_u8));
+        let group = Group::new_unchecked(
+            BigUint::from(59183_u32),
+            BigUint::from(127_u8),
+            BigUint::from(32616_u32),
+        );
+
+        let base = group.random_group_elem(&mut csprng);
+        let table = FixedBaseTable::new(&base, &group);
+        for x in 0..127_u8 {
+            let x = FieldElement::from(x, &field);
+            assert_eq!(table.exp(&x, &group), base.exp(&x, &group));
+            assert_eq!(table.exp_secret(&x, &group), base.exp(&x, &group));
+        }
+
+        // Exponents that are not reduced fall back to a generic exponentiation.
+        let x = FieldElement(BigUint::from(1000_u32));
+        assert_eq!(table.exp(&x, &group), base.exp(&x, &group));
+        assert_eq!(table.exp_secret(&x, &group), base.exp(&x, &group));
+
+        let lazy_table = LazyFixedBaseTable::default();
+        assert!(!lazy_table.is_computed());
+        let x = field.random_field_elem(&mut csprng);
+        assert_eq!(lazy_table.exp(&base, &x, &group), base.exp(&x, &group));
+        assert!(lazy_table.is_computed());
+        assert_eq!(
+            lazy_table.exp_secret(&base, &x, &group),
+            base.exp(&x, &group)
+        );
+
+        // A table for another base is not used.
+        let other_base = group.random_group_elem(&mut csprng);
+        assert_eq!(
+            lazy_table.exp(&other_base, &x, &group),
+            other_base.exp(&x, &group)
+        );
+    }
+}
@@ -13,6 +13,9 @@ pub mod biguint_serde;
 pub mod bitwise;
 pub mod csprng;
 pub mod file;
+pub mod fixed_base;
 pub mod hex_dump;
 pub mod logging;
+pub mod montgomery;
+pub mod multi_exp;
 pub mod prime;
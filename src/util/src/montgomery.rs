@@ -0,0 +1,460 @@
+// Copyright (C) Microsoft Corporation. All rights reserved.
+
+#![deny(clippy::unwrap_used)]
+#![deny(clippy::expect_used)]
+#![deny(clippy::panic)]
+#![deny(clippy::manual_assert)]
+
+//! This module provides modular arithmetic in Montgomery form for an odd modulus `n`.
+//!
+//! With `R = 2^(64*l)` for the count `l` of 64-bit limbs of `n`, the Montgomery form of `a` is
+//! `a * R mod n`. The Montgomery product of `a * R` and `b * R` is `a * b * R mod n` and is
+//! computed without a division by `n`. Values are therefore converted to Montgomery form once,
+//! e.g., before an exponentiation, and back at the end.
+//!
+//! With the `montgomery` feature, [`Group`](crate::algebra::Group) and
+//! [`ScalarField`](crate::algebra::ScalarField) use this module for their multiplications and
+//! exponentiations.
+
+use std::sync::{Arc, OnceLock};
+
+use num_bigint::BigUint;
+use num_traits::{One, Zero};
+
+/// The precomputed values for Montgomery arithmetic modulo an odd modulus `n`.
+pub struct Montgomery {
+    /// The modulus `n`.
+    modulus: BigUint,
+    /// The limbs of `n`, least significant first.
+    n: Vec<u64>,
+    /// `-n^(-1) mod 2^64`
+    n0_inv: u64,
+    /// `R^2 mod n`
+    r2: Vec<u64>,
+    /// `R mod n`, i.e., `1` in Montgomery form.
+    r1: Vec<u64>,
+}
+
+impl Montgomery {
+    /// The window width in bits for [`Montgomery::pow`].
+    const WINDOW_BITS: usize = 5;
+
+    /// Precomputes the values for the given modulus. Returns `None` if the modulus is even or
+    /// `1`.
+    pub fn new(modulus: &BigUint) -> Option<Self> {
+        if !modulus.bit(0) || modulus.is_one() {
+            return None;
+        }
+
+        let n = modulus.to_u64_digits();
+        let l = n.len();
+
+        // Newton's iteration doubles the count of correct low bits of the inverse of `n_0`.
+        let mut inv = 1_u64;
+        for _ in 0..6 {
+            inv = inv.wrapping_mul(2_u64.wrapping_sub(n[0].wrapping_mul(inv)));
+        }
+
+        let r = BigUint::one() << (64 * l);
+        let r1 = Self::to_limbs(&(&r % modulus), l);
+        let r2 = Self::to_limbs(&((&r * &r) % modulus), l);
+
+        Some(Montgomery {
+            modulus: modulus.clone(),
+            n,
+            n0_inv: inv.wrapping_neg(),
+            r2,
+            r1,
+        })
+    }
+
+    /// The modulus `n`.
+    pub fn modulus(&self) -> &BigUint {
+        &self.modulus
+    }
+
+    /// Returns the Montgomery form `a * R mod n` of `a`.
+    pub fn to_montgomery(&self, a: &BigUint) -> BigUint {
+        Self::from_limbs(&self.mul_limbs(&self.reduced_limbs(a), &self.r2))
+    }
+
+    /// Returns `a` for its Montgomery form `a * R mod n`.
+    pub fn from_montgomery(&self, a_r: &BigUint) -> BigUint {
+        self.montgomery_limbs_value(&self.reduced_limbs(a_r))
+    }
+
+    /// Returns the Montgomery product `a * b * R^(-1) mod n`, i.e., the Montgomery form of the
+    /// product of two values in Montgomery form.
+    pub fn mul_montgomery(&self, a_r: &BigUint, b_r: &BigUint) -> BigUint {
+        Self::from_limbs(&self.mul_limbs(&self.reduced_limbs(a_r), &self.reduced_limbs(b_r)))
+    }
+
+    /// Returns `a * b mod n`.
+    pub fn mul(&self, a: &BigUint, b: &BigUint) -> BigUint {
+        // `(a * b * R^(-1)) * (R^2) * R^(-1) = a * b`
+        let ab = self.mul_limbs(&self.reduced_limbs(a), &self.reduced_limbs(b));
+        Self::from_limbs(&self.mul_limbs(&ab, &self.r2))
+    }
+
+    /// Returns `base^exponent mod n`.
+    ///
+    /// This uses a fixed window of [`Montgomery::WINDOW_BITS`] bits.
+    pub fn pow(&self, base: &BigUint, exponent: &BigUint) -> BigUint {
+        let powers = self.window_powers(base);
+
+        let cnt_windows = (exponent.bits() as usize).div_ceil(Self::WINDOW_BITS);
+        let mut result = self.r1.clone();
+        for i in (0..cnt_windows).rev() {
+            for _ in 0..Self::WINDOW_BITS {
+                result = self.sqr_limbs(&result);
+            }
+
+            let mut digit = 0_usize;
+            for j in (0..Self::WINDOW_BITS).rev() {
+                let bit = exponent.bit((i * Self::WINDOW_BITS + j) as u64);
+                digit = (digit << 1) | bit as usize;
+            }
+            if digit != 0 {
+                result = self.mul_limbs(&result, &powers[digit]);
+            }
+        }
+
+        self.montgomery_limbs_value(&result)
+    }
+
+    /// Returns `base^exponent mod n` for a secret exponent of at most `cnt_exponent_bits` bits.
+    ///
+    /// Unlike [`Montgomery::pow`], the sequence of operations and memory accesses only depends
+    /// on `cnt_exponent_bits`, and not on the value of the exponent: every window is multiplied
+    /// in, also if its digit is zero, the power for a digit is selected by reading the whole
+    /// table, and products are reduced without branches. Exponents with more bits are
+    /// processed with as many windows as they need.
+    pub fn pow_secret(
+        &self,
+        base: &BigUint,
+        exponent: &BigUint,
+        cnt_exponent_bits: usize,
+    ) -> BigUint {
+        let powers = self.window_powers(base);
+
+        let cnt_bits = cnt_exponent_bits.max(exponent.bits() as usize);
+        let cnt_windows = cnt_bits.div_ceil(Self::WINDOW_BITS);
+        let exponent = Self::to_limbs(exponent, (cnt_windows * Self::WINDOW_BITS).div_ceil(64));
+
+        let mut result = self.r1.clone();
+        for i in (0..cnt_windows).rev() {
+            for _ in 0..Self::WINDOW_BITS {
+                result = self.sqr_limbs(&result);
+            }
+
+            let mut digit = 0_usize;
+            for j in (0..Self::WINDOW_BITS).rev() {
+                let ix = i * Self::WINDOW_BITS + j;
+                let bit = (exponent[ix / 64] >> (ix % 64)) & 1;
+                digit = (digit << 1) | bit as usize;
+            }
+            result = self.mul_limbs(&result, &self.select_limbs(&powers, digit));
+        }
+
+        self.montgomery_limbs_value(&result)
+    }
+
+    /// Returns `candidates[index]`, or zero if the index is out of range.
+    ///
+    /// All candidates are read, so that the memory accesses do not depend on the index. The
+    /// candidates must be less than `n`.
+    pub fn select_secret(&self, candidates: &[BigUint], index: usize) -> BigUint {
+        let l = self.n.len();
+        let candidates: Vec<_> = candidates.iter().map(|c| Self::to_limbs(c, l)).collect();
+        Self::from_limbs(&self.select_limbs(&candidates, index))
+    }
+
+    /// The powers `base^d` in Montgomery form for all digits `d` of a window.
+    fn window_powers(&self, base: &BigUint) -> Vec<Vec<u64>> {
+        let base_r = self.mul_limbs(&self.reduced_limbs(base), &self.r2);
+
+        let cnt_powers = 1_usize << Self::WINDOW_BITS;
+        let mut powers = Vec::with_capacity(cnt_powers);
+        powers.push(self.r1.clone());
+        for d in 1..cnt_powers {
+            let power = self.mul_limbs(&powers[d - 1], &base_r);
+            powers.push(power);
+        }
+        powers
+    }
+
+    /// Returns `a` for the limbs of its Montgomery form.
+    fn montgomery_limbs_value(&self, a_r: &[u64]) -> BigUint {
+        let mut one = vec![0_u64; self.n.len()];
+        one[0] = 1;
+        Self::from_limbs(&self.mul_limbs(a_r, &one))
+    }
+
+    /// Returns `table[index]`, or zero if the index is out of range, reading all entries.
+    fn select_limbs(&self, table: &[Vec<u64>], index: usize) -> Vec<u64> {
+        let mut selected = vec![0_u64; self.n.len()];
+        for (i, entry) in table.iter().enumerate() {
+            // All ones iff `i == index`.
+            let diff = (i ^ index) as u64;
+            let mask = ((diff | diff.wrapping_neg()) >> 63).wrapping_sub(1);
+            for (s_j, &e_j) in selected.iter_mut().zip(entry) {
+                *s_j |= e_j & mask;
+            }
+        }
+        selected
+    }
+
+    /// The limbs of `a mod n`, padded to the count of limbs of `n`.
+    fn reduced_limbs(&self, a: &BigUint) -> Vec<u64> {
+        if a < &self.modulus {
+            Self::to_limbs(a, self.n.len())
+        } else {
+            Self::to_limbs(&(a % &self.modulus), self.n.len())
+        }
+    }
+
+    fn to_limbs(a: &BigUint, l: usize) -> Vec<u64> {
+        let mut limbs = a.to_u64_digits();
+        limbs.resize(l, 0);
+        limbs
+    }
+
+    fn from_limbs(limbs: &[u64]) -> BigUint {
+        if limbs.iter().all(Zero::is_zero) {
+            return BigUint::zero();
+        }
+        let digits = limbs
+            .iter()
+            .flat_map(|&limb| [limb as u32, (limb >> 32) as u32])
+            .collect();
+        BigUint::new(digits)
+    }
+
+    /// The Montgomery product `a * b * R^(-1) mod n` of `a, b < n`.
+    ///
+    /// The rows of the product and of the reduction are accumulated in a buffer of `2l` limbs,
+    /// see Gueron, "Efficient Software Implementations of Modular Exponentiation",
+    /// <https://eprint.iacr.org/2011/239.pdf>.
+    fn mul_limbs(&self, a: &[u64], b: &[u64]) -> Vec<u64> {
+        let n = &self.n[..];
+        let l = n.len();
+        let mut z = vec![0_u64; 2 * l];
+
+        let mut carry = false;
+        for (i, &b_i) in b[..l].iter().enumerate() {
+            let z = &mut z[i..];
+            let c_ab = Self::add_mul(&mut z[..l], a, b_i);
+            let m = z[0].wrapping_mul(self.n0_inv);
+            let c_mn = Self::add_mul(&mut z[..l], n, m);
+            let (sum, overflow_ab) = c_ab.overflowing_add(carry as u64);
+            let (sum, overflow_mn) = sum.overflowing_add(c_mn);
+            z[l] = sum;
+            carry = overflow_ab || overflow_mn;
+        }
+
+        self.reduce_once(z.split_off(l), carry)
+    }
+
+    /// Reduces `t + carry * R < 2n` to `t mod n` by subtracting `n` at most once.
+    ///
+    /// The difference is always computed and then selected with a mask, so that the time does
+    /// not depend on `t`.
+    fn reduce_once(&self, t: Vec<u64>, carry: bool) -> Vec<u64> {
+        let n = &self.n[..];
+        let mut d = Vec::with_capacity(t.len());
+        let mut borrow = false;
+        for (&t_j, &n_j) in t.iter().zip(n) {
+            let (d_j, b1) = t_j.overflowing_sub(n_j);
+            let (d_j, b2) = d_j.overflowing_sub(borrow as u64);
+            d.push(d_j);
+            borrow = b1 | b2;
+        }
+
+        // All ones iff `t < n`, i.e., iff there is no carry and the difference borrows.
+        let keep_t = ((!carry & borrow) as u64).wrapping_neg();
+        t.iter()
+            .zip(d)
+            .map(|(&t_j, d_j)| (t_j & keep_t) | (d_j & !keep_t))
+            .collect()
+    }
+
+    /// The Montgomery square `a * a * R^(-1) mod n` of `a < n`.
+    ///
+    /// Computing the square first needs only about half of the limb products of
+    /// [`Montgomery::mul_limbs`], as `a_i * a_j = a_j * a_i`.
+    fn sqr_limbs(&self, a: &[u64]) -> Vec<u64> {
+        let n = &self.n[..];
+        let l = n.len();
+        let mut z = vec![0_u64; 2 * l];
+
+        // The products a_i * a_j for i < j.
+        for i in 0..l {
+            let c = Self::add_mul(&mut z[2 * i + 1..i + l], &a[i + 1..l], a[i]);
+            z[i + l] = c;
+        }
+
+        // Double them and add the squares a_i * a_i.
+        let mut shifted_out = 0_u64;
+        for z_j in z.iter_mut() {
+            let next_shifted_out = *z_j >> 63;
+            *z_j = (*z_j << 1) | shifted_out;
+            shifted_out = next_shifted_out;
+        }
+        let mut carry = false;
+        for (i, &a_i) in a[..l].iter().enumerate() {
+            let s = (a_i as u128) * (a_i as u128);
+            let (lo, c1) = z[2 * i].overflowing_add(s as u64);
+            let (lo, c2) = lo.overflowing_add(carry as u64);
+            let (hi, c3) = z[2 * i + 1].overflowing_add((s >> 64) as u64);
+            let (hi, c4) = hi.overflowing_add((c1 || c2) as u64);
+            z[2 * i] = lo;
+            z[2 * i + 1] = hi;
+            carry = c3 || c4;
+        }
+
+        // Montgomery reduction of the square.
+        let mut carry = 0_u64;
+        for i in 0..l {
+            let m = z[i].wrapping_mul(self.n0_inv);
+            let c = Self::add_mul(&mut z[i..i + l], n, m);
+            let (sum, c1) = z[i + l].overflowing_add(c);
+            let (sum, c2) = sum.overflowing_add(carry);
+            z[i + l] = sum;
+            carry = (c1 || c2) as u64;
+        }
+
+        self.reduce_once(z.split_off(l), carry != 0)
+    }
+
+    /// Computes `z = z + x * y` and returns the carry limb.
+    #[inline(always)]
+    fn add_mul(z: &mut [u64], x: &[u64], y: u64) -> u64 {
+        let mut carry = 0_u64;
+        for (z_j, &x_j) in z.iter_mut().zip(x) {
+            let s = (x_j as u128) * (y as u128) + (*z_j as u128);
+            let (lo, overflow) = (s as u64).overflowing_add(carry);
+            *z_j = lo;
+            carry = (s >> 64) as u64 + overflow as u64;
+        }
+        carry
+    }
+}
+
+/// A [`Montgomery`] context that is computed when it is first used.
+///
+/// Clones share the computed context. Two lazy contexts always compare as equal, as they are
+/// only a cache of their modulus.
+#[derive(Clone, Default)]
+pub struct LazyMontgomery(OnceLock<Option<Arc<Montgomery>>>);
+
+impl LazyMontgomery {
+    /// Returns the context for the given modulus, or `None` if the modulus is even or `1`.
+    ///
+    /// The context is computed for `modulus` on first use. If it was computed for a different
+    /// modulus, this returns `None`.
+    pub fn get(&self, modulus: &BigUint) -> Option<&Montgomery> {
+        self.0
+            .get_or_init(|| Montgomery::new(modulus).map(Arc::new))
+            .as_deref()
+            .filter(|montgomery| montgomery.modulus() == modulus)
+    }
+}
+
+impl PartialEq for LazyMontgomery {
+    fn eq(&self, _other: &Self) -> bool {
+        true
+    }
+}
+
+impl Eq for LazyMontgomery {}
+
+impl std::fmt::Debug for LazyMontgomery {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LazyMontgomery")
+            .field("is_computed", &self.0.get().is_some())
+            .finish()
+    }
+}
+
+// Unit tests for Montgomery arithmetic.
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod test {
+    use std::num::NonZeroUsize;
+
+    use super::*;
+    use crate::csprng::Csprng;
+
+    #[test]
+    fn test_montgomery() {
+        let mut csprng = Csprng::new(b"testing montgomery arithmetic");
+
+        assert!(Montgomery::new(&BigUint::from(59184_u32)).is_none());
+        assert!(Montgomery::new(&BigUint::one()).is_none());
+
+        let exponent_bits = NonZeroUsize::new(256).unwrap();
+        for (bits, all_ones) in [
+            (7, false),
+            (64, true),
+            (65, false),
+            (255, false),
+            (1024, false),
+            (4096, false),
+            (4096, true),
+        ] {
+            // Moduli with all bits set maximize the carries.
+            let modulus = if all_ones {
+                (BigUint::one() << bits) - BigUint::one()
+            } else {
+                let bits = NonZeroUsize::new(bits).unwrap();
+                csprng.next_biguint_requiring_bits(bits) | BigUint::one()
+            };
+            let montgomery = Montgomery::new(&modulus).unwrap();
+
+            for _ in 0..10 {
+                let a = csprng.next_biguint_lt(&modulus);
+                let b = csprng.next_biguint_lt(&modulus);
+                let e = csprng.next_biguint(exponent_bits);
+
+                assert_eq!(montgomery.mul(&a, &b), (&a * &b) % &modulus);
+                assert_eq!(montgomery.pow(&a, &e), a.modpow(&e, &modulus));
+                assert_eq!(montgomery.pow_secret(&a, &e, 256), a.modpow(&e, &modulus));
+                // Exponents with more bits than announced are processed completely.
+                assert_eq!(montgomery.pow_secret(&a, &e, 7), a.modpow(&e, &modulus));
+
+                let a_r = montgomery.to_montgomery(&a);
+                let b_r = montgomery.to_montgomery(&b);
+                let ab_r = montgomery.mul_montgomery(&a_r, &b_r);
+                assert_eq!(montgomery.from_montgomery(&ab_r), (&a * &b) % &modulus);
+            }
+
+            // Edge cases
+            let n_minus_1 = &modulus - BigUint::one();
+            assert_eq!(
+                montgomery.pow(&n_minus_1, &BigUint::from(3_u8)),
+                n_minus_1.modpow(&BigUint::from(3_u8), &modulus)
+            );
+            assert_eq!(
+                montgomery.mul(&n_minus_1, &n_minus_1),
+                (&n_minus_1 * &n_minus_1) % &modulus
+            );
+            assert_eq!(montgomery.pow(&n_minus_1, &BigUint::zero()), BigUint::one());
+            assert_eq!(
+                montgomery.pow(&BigUint::zero(), &BigUint::one()),
+                BigUint::zero()
+            );
+            assert_eq!(
+                montgomery.pow_secret(&n_minus_1, &BigUint::zero(), 256),
+                BigUint::one()
+            );
+            let a = &modulus + BigUint::from(3_u8);
+            assert_eq!(montgomery.mul(&a, &a), BigUint::from(9_u8) % &modulus);
+
+            let candidates = [BigUint::from(5_u8), n_minus_1.clone(), BigUint::zero()];
+            for (i, c) in candidates.iter().enumerate() {
+                assert_eq!(&montgomery.select_secret(&candidates, i), c);
+            }
+            assert_eq!(montgomery.select_secret(&candidates, 3), BigUint::zero());
+        }
+    }
+}
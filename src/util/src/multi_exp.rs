@@ -0,0 +1,305 @@
+// Copyright (C) Microsoft Corporation. All rights reserved.
+
+#![deny(clippy::unwrap_used)]
+#![deny(clippy::expect_used)]
+#![deny(clippy::panic)]
+#![deny(clippy::manual_assert)]
+
+//! This module provides simultaneous multi-exponentiation in a [`Group`] and, based on it, batch
+//! verification of group membership.
+//!
+//! A product `b_1^x_1 * ... * b_n^x_n` computed by [`multi_exp`] shares the squarings between all
+//! terms. For few terms it uses Straus' method with a small table of powers per base, for many
+//! terms Pippenger's bucket method, which needs no table per base and about `|x|/c` instead of
+//! `|x|/w` multiplications per term for its larger window width `c`.
+//!
+//! [`batch_is_valid`] uses a multi-exponentiation with short random exponents to check that many
+//! elements lie in the group at the cost of a single exponentiation with the group order.
+
+use num_bigint::BigUint;
+
+use crate::{
+    algebra::{FieldElement, Group, GroupElement},
+    algebra_utils::jacobi_symbol,
+    csprng::Csprng,
+};
+
+/// The window width in bits of Straus' method.
+const STRAUS_WINDOW_BITS: usize = 4;
+
+/// From this number of terms on [`multi_exp`] uses Pippenger's method.
+const PIPPENGER_MIN_TERMS: usize = 32;
+
+/// Computes `b_1^x_1 * ... * b_n^x_n mod p` for the given `(b_i, x_i)`.
+///
+/// The exponents may be arbitrary, i.e., they need not be reduced modulo the group order. The
+/// running time depends on the exponents, so they must not be secret.
+pub fn multi_exp(terms: &[(&GroupElement, &FieldElement)], group: &Group) -> GroupElement {
+    let cnt_bits = terms
+        .iter()
+        .map(|(_, x)| x.0.bits() as usize)
+        .max()
+        .unwrap_or(0);
+
+    let opt_result = if terms.len() < PIPPENGER_MIN_TERMS {
+        straus(terms, cnt_bits, group)
+    } else {
+        pippenger(terms, cnt_bits, group)
+    };
+
+    match opt_result {
+        Some(result) => GroupElement(group.repr_value(result)),
+        None => Group::one(),
+    }
+}
+
+/// Checks that all elements are valid members of the given group, like
+/// [`GroupElement::is_valid`] for each of them.
+///
+/// Every element `x_i` must be less than `p` and have Jacobi symbol `1`, i.e., lie in the
+/// subgroup of order `(p-1)/2`. Then `y = x_1^r_1 * ... * x_n^r_n` is computed for random
+/// `64`-bit exponents `r_i`, and it is checked that `y^q = 1`.
+///
+/// If one of the elements is not in the group of order `q`, this check passes with probability
+/// at most `2^-64`, as long as `(p-1)/(2q)` is a prime larger than `2^64`. For the standard
+/// parameters of Section `3.1.1` of the Electionguard specification `2.0.0` this is checked by the
+/// test `standard_parameters_cofactor` of the `eg` crate. The randomness must be unpredictable to
+/// whoever provided the elements.
+pub fn batch_is_valid(elements: &[&GroupElement], group: &Group, csprng: &mut Csprng) -> bool {
+    let p = group.modulus();
+    let all_squares = elements
+        .iter()
+        .all(|x| x.as_biguint() < p && jacobi_symbol(x.as_biguint(), p) == 1);
+    if !all_squares {
+        return false;
+    }
+
+    let exponents = elements
+        .iter()
+        .map(|_| FieldElement(BigUint::from(csprng.next_u64())))
+        .collect::<Vec<_>>();
+    let terms = elements.iter().copied().zip(&exponents).collect::<Vec<_>>();
+
+    multi_exp(&terms, group).is_valid(group)
+}
+
+/// Computes the product of the terms with Straus' method, in the representation of
+/// [`Group::repr_of`]. Returns `None` for the empty product.
+fn straus(
+    terms: &[(&GroupElement, &FieldElement)],
+    cnt_bits: usize,
+    group: &Group,
+) -> Option<BigUint> {
+    let cnt_digits = (1_usize << STRAUS_WINDOW_BITS) - 1;
+
+    // tables[k][d - 1] = b_k^d
+    let tables = terms
+        .iter()
+        .map(|(base, _)| {
+            let b = group.repr_of(base.as_biguint());
+            let mut row = Vec::with_capacity(cnt_digits);
+            row.push(b.clone());
+            for d in 1..cnt_digits {
+                let power = group.mul_repr(&row[d - 1], &b);
+                row.push(power);
+            }
+            row
+        })
+        .collect::<Vec<_>>();
+    let limbs = terms
+        .iter()
+        .map(|(_, x)| x.0.to_u64_digits())
+        .collect::<Vec<_>>();
+
+    let mut result = None;
+    for i in (0..cnt_bits.div_ceil(STRAUS_WINDOW_BITS)).rev() {
+        result = square_times(result, STRAUS_WINDOW_BITS, group);
+        for (row, limbs_k) in tables.iter().zip(&limbs) {
+            let digit = window_digit(limbs_k, i * STRAUS_WINDOW_BITS, STRAUS_WINDOW_BITS);
+            if digit != 0 {
+                result = mul_opt(result, &row[digit - 1], group);
+            }
+        }
+    }
+    result
+}
+
+/// Computes the product of the terms with Pippenger's bucket method, in the representation of
+/// [`Group::repr_of`]. Returns `None` for the empty product.
+fn pippenger(
+    terms: &[(&GroupElement, &FieldElement)],
+    cnt_bits: usize,
+    group: &Group,
+) -> Option<BigUint> {
+    // The window width that roughly balances the multiplications into the buckets against the
+    // ones for combining the buckets.
+    let window_bits = (terms.len().ilog2() as usize)
+        .saturating_sub(3)
+        .clamp(2, 16);
+
+    let bases = terms
+        .iter()
+        .map(|(base, _)| group.repr_of(base.as_biguint()))
+        .collect::<Vec<_>>();
+    let limbs = terms
+        .iter()
+        .map(|(_, x)| x.0.to_u64_digits())
+        .collect::<Vec<_>>();
+
+    let mut result = None;
+    for i in (0..cnt_bits.div_ceil(window_bits)).rev() {
+        result = square_times(result, window_bits, group);
+
+        // buckets[d - 1] is the product of all bases with digit d in this window.
+        let mut buckets: Vec<Option<BigUint>> = vec![None; (1 << window_bits) - 1];
+        for (b, limbs_k) in bases.iter().zip(&limbs) {
+            let digit = window_digit(limbs_k, i * window_bits, window_bits);
+            if digit != 0 {
+                buckets[digit - 1] = mul_opt(buckets[digit - 1].take(), b, group);
+            }
+        }
+
+        // The product of bucket^d over all digits d, from running products of the buckets.
+        let mut running = None;
+        let mut window_product = None;
+        for bucket in buckets.iter().rev() {
+            if let Some(bucket) = bucket {
+                running = mul_opt(running, bucket, group);
+            }
+            if let Some(running) = &running {
+                window_product = mul_opt(window_product, running, group);
+            }
+        }
+        if let Some(window_product) = &window_product {
+            result = mul_opt(result, window_product, group);
+        }
+    }
+    result
+}
+
+/// Returns the digit of `width < 64` bits at bit `offset` of the number with the given limbs.
+fn window_digit(limbs: &[u64], offset: usize, width: usize) -> usize {
+    let (limb_ix, shift) = (offset / 64, offset % 64);
+    let Some(&limb) = limbs.get(limb_ix) else {
+        return 0;
+    };
+    let mut digit = limb >> shift;
+    if shift + width > 64 {
+        if let Some(&next_limb) = limbs.get(limb_ix + 1) {
+            digit |= next_limb << (64 - shift);
+        }
+    }
+    (digit & ((1_u64 << width) - 1)) as usize
+}
+
+/// Multiplies `acc` with `x`, where `None` stands for `1`.
+fn mul_opt(acc: Option<BigUint>, x: &BigUint, group: &Group) -> Option<BigUint> {
+    match acc {
+        Some(acc) => Some(group.mul_repr(&acc, x)),
+        None => Some(x.clone()),
+    }
+}
+
+/// Squares `acc` the given number of times, where `None` stands for `1`.
+fn square_times(acc: Option<BigUint>, cnt: usize, group: &Group) -> Option<BigUint> {
+    acc.map(|mut acc| {
+        for _ in 0..cnt {
+            acc = group.mul_repr(&acc, &acc);
+        }
+        acc
+    })
+}
+
+// Unit tests for multi-exponentiation.
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod test {
+    use super::*;
+    use crate::algebra::ScalarField;
+
+    fn get_toy_algebras() -> (ScalarField, Group) {
+        (
+            ScalarField::new_unchecked(BigUint::from(127_u8)),
+            Group::new_unchecked(
+                BigUint::from(59183_u32),
+                BigUint::from(127_u8),
+                BigUint::from(32616_u32),
+            ),
+        )
+    }
+
+    fn naive_multi_exp(terms: &[(&GroupElement, &FieldElement)], group: &Group) -> GroupElement {
+        terms.iter().fold(Group::one(), |acc, (base, x)| {
+            acc.mul(&base.pow(x.0.clone(), group), group)
+        })
+    }
+
+    #[test]
+    fn test_multi_exp() {
+        let mut csprng = Csprng::new(b"test_multi_exp");
+        let (field, group) = get_toy_algebras();
+
+        assert_eq!(multi_exp(&[], &group), Group::one());
+
+        // Straus for few terms, Pippenger for many, with reduced and with 64-bit exponents.
+        for cnt_terms in [1, 2, 5, PIPPENGER_MIN_TERMS - 1, PIPPENGER_MIN_TERMS, 300] {
+            let bases = (0..cnt_terms)
+                .map(|_| group.random_group_elem(&mut csprng))
+                .collect::<Vec<_>>();
+            for exponents in [
+                (0..cnt_terms)
+                    .map(|_| field.random_field_elem(&mut csprng))
+                    .collect::<Vec<_>>(),
+                (0..cnt_terms)
+                    .map(|_| FieldElement(BigUint::from(csprng.next_u64())))
+                    .collect::<Vec<_>>(),
+            ] {
+                let terms = bases.iter().zip(&exponents).collect::<Vec<_>>();
+                assert_eq!(
+                    multi_exp(&terms, &group),
+                    naive_multi_exp(&terms, &group),
+                    "{cnt_terms} terms"
+                );
+            }
+        }
+
+        // All exponents zero
+        let g = group.generator();
+        let zero = ScalarField::zero();
+        assert_eq!(multi_exp(&[(&g, &zero), (&g, &zero)], &group), Group::one());
+    }
+
+    #[test]
+    fn test_batch_is_valid() {
+        let mut csprng = Csprng::new(b"test_batch_is_valid");
+        let (_, group) = get_toy_algebras();
+        let p = group.modulus();
+
+        let mut elements = (0..40)
+            .map(|_| group.random_group_elem(&mut csprng))
+            .collect::<Vec<_>>();
+        let refs = elements.iter().collect::<Vec<_>>();
+        assert!(batch_is_valid(&refs, &group, &mut csprng));
+        assert!(batch_is_valid(&[], &group, &mut csprng));
+
+        // A square of order (p-1)/(2q) = 233 passes the Jacobi symbol check, but not the batch.
+        let h = GroupElement(BigUint::from(2_u8).modpow(&BigUint::from(254_u32), p));
+        assert!(!h.is_valid(&group));
+        assert_eq!(jacobi_symbol(h.as_biguint(), p), 1);
+
+        // Non-squares, zero, and elements out of range are rejected.
+        let invalid = [
+            h,
+            GroupElement(p - 1_u8),
+            GroupElement(BigUint::from(0_u8)),
+            GroupElement(p.clone()),
+            GroupElement(p + 1_u8),
+        ];
+        for x in invalid {
+            assert!(!batch_is_valid(&[&x], &group, &mut csprng));
+            elements[17] = x;
+            let refs = elements.iter().collect::<Vec<_>>();
+            assert!(!batch_is_valid(&refs, &group, &mut csprng));
+        }
+    }
+}
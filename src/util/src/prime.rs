@@ -195,14 +195,14 @@ mod test_primes {
             assert!(a < 32);
             let two_to_a = 1_usize << a;
 
-            assert!(n.is_multiple_of(&two_to_a));
+            assert!(Integer::is_multiple_of(&n, &two_to_a));
 
             for invalid_a in (a + 1)..32 {
                 let two_to_invalid_a = 1_usize << invalid_a;
-                if n.is_multiple_of(&two_to_invalid_a) {
+                if Integer::is_multiple_of(&n, &two_to_invalid_a) {
                     println!("\n\nn={n}, a={a}, invalid_a={invalid_a}, two_to_invalid_a={two_to_invalid_a}\n");
                 }
-                assert!(!n.is_multiple_of(&two_to_invalid_a));
+                assert!(!Integer::is_multiple_of(&n, &two_to_invalid_a));
             }
         }
     }